@@ -1,17 +1,21 @@
 use windows::core::GUID;
 use windows::Win32::Media::MediaFoundation::*;
 
-use rustcv_core::error::{CameraError, Result};
+use rustcv_core::error::Result;
 use rustcv_core::traits::{
-    DeviceControls, LensControl, SensorControl, SystemControl, TriggerConfig, TriggerMode,
+    DeviceControls, LensControl, SensorControl, SystemControl, TriggerConfig,
 };
+use rustcv_core::trigger::SoftwareTrigger;
 
 use crate::stream::SendableSourceReader;
 
 /// Default exposure time in microseconds (10ms).
 const DEFAULT_EXPOSURE_US: u32 = 10000;
 
-pub fn create_controls(source_reader: SendableSourceReader) -> DeviceControls {
+pub fn create_controls(
+    source_reader: SendableSourceReader,
+    trigger: SoftwareTrigger,
+) -> DeviceControls {
     DeviceControls {
         sensor: Box::new(MsmfSensor {
             source_reader: source_reader.clone(),
@@ -19,7 +23,10 @@ pub fn create_controls(source_reader: SendableSourceReader) -> DeviceControls {
         lens: Box::new(MsmfLens {
             source_reader: source_reader.clone(),
         }),
-        system: Box::new(MsmfSystem { source_reader }),
+        system: Box::new(MsmfSystem {
+            source_reader,
+            trigger,
+        }),
     }
 }
 
@@ -121,6 +128,7 @@ impl LensControl for MsmfLens {
 /// Provides system-level operations such as reset and trigger control.
 struct MsmfSystem {
     source_reader: SendableSourceReader,
+    trigger: SoftwareTrigger,
 }
 
 unsafe impl Send for MsmfSystem {}
@@ -139,13 +147,16 @@ impl SystemControl for MsmfSystem {
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if trigger is disabled, or an error for other modes
-    /// as hardware trigger is not supported.
+    /// Returns `Ok(())` for `Off` and for `Standard` with a software source,
+    /// which is emulated by dropping untriggered frames in `MsmfStream`.
+    /// Hardware trigger lines and bulb mode are not supported.
     fn set_trigger(&self, config: TriggerConfig) -> Result<()> {
-        if config.mode == TriggerMode::Off {
-            return Ok(());
-        }
-        Err(CameraError::FormatNotSupported)
+        self.trigger.configure(config)
+    }
+
+    /// Fires a software trigger; the next frame exposed after it is delivered.
+    fn fire_software_trigger(&self) -> Result<()> {
+        self.trigger.fire()
     }
 
     fn export_state(&self) -> Result<serde_json::Value> {
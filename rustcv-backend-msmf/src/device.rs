@@ -17,6 +17,7 @@ use rustcv_core::builder::CameraConfig;
 use rustcv_core::error::{CameraError, Result};
 use rustcv_core::pixel_format::PixelFormat;
 use rustcv_core::traits::{DeviceControls, DeviceInfo, Stream};
+use rustcv_core::trigger::SoftwareTrigger;
 
 use crate::controls::create_controls;
 use crate::pixel_map;
@@ -226,10 +227,10 @@ pub fn open(id: &str, config: CameraConfig) -> Result<(Box<dyn Stream>, DeviceCo
 
     // 3. Create MsmfStream
     // Async Reader creation and callback binding are now fully encapsulated inside MsmfStream
-    let stream = MsmfStream::new(&media_source, negotiated_fmt)
+    let stream = MsmfStream::new(&media_source, negotiated_fmt, SoftwareTrigger::new())
         .map_err(|e| CameraError::Io(std::io::Error::other(e.to_string())))?;
 
-    let controls = create_controls(stream.get_reader());
+    let controls = create_controls(stream.get_reader(), stream.get_trigger());
 
     Ok((Box::new(stream), controls))
 }
@@ -456,7 +457,6 @@ pub struct NegotiatedFormat {
     /// Pixel format (e.g., YUYV, NV12).
     pub format: PixelFormat,
     /// Frame rate in frames per second.
-    #[allow(dead_code)]
     pub fps: u32,
     /// The Media Foundation media type for the source reader.
     pub media_type: IMFMediaType,
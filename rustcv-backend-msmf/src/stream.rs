@@ -28,9 +28,10 @@ use rustcv_core::frame::{BackendBufferHandle, Frame, FrameMetadata, Timestamp};
 use rustcv_core::pixel_format::{FourCC, PixelFormat};
 use rustcv_core::time::ClockSynchronizer;
 use rustcv_core::traits::Stream;
+use rustcv_core::trigger::{SoftwareTrigger, TriggerDecision};
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::{Arc, Mutex, RwLock, Weak};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use windows::core::{implement, IUnknown, Interface};
 use windows::Win32::Media::MediaFoundation::{
@@ -208,10 +209,20 @@ pub struct MsmfStream {
 
     /// Clock synchronizer for timestamp correction.
     clock_sync: ClockSynchronizer,
+
+    /// Software trigger shared with the system controls.
+    trigger: SoftwareTrigger,
+
+    /// Nominal frame interval, used to estimate when a sample started exposing.
+    frame_interval: Duration,
 }
 
 impl MsmfStream {
-    pub fn new(media_source: &IMFMediaSource, fmt: NegotiatedFormat) -> Result<Self> {
+    pub fn new(
+        media_source: &IMFMediaSource,
+        fmt: NegotiatedFormat,
+        trigger: SoftwareTrigger,
+    ) -> Result<Self> {
         let (line_width_bytes, total_size) =
             Self::calculate_buffer_size(fmt.width, fmt.height, fmt.format);
 
@@ -269,6 +280,8 @@ impl MsmfStream {
             line_width_bytes,
             sequence: 0,
             clock_sync: ClockSynchronizer::new(30),
+            trigger,
+            frame_interval: Duration::from_secs(1) / fmt.fps.max(1),
         })
     }
 
@@ -441,6 +454,11 @@ impl MsmfStream {
         Ok(())
     }
 
+    /// Returns a clone of the software trigger for control interfaces.
+    pub fn get_trigger(&self) -> SoftwareTrigger {
+        self.trigger.clone()
+    }
+
     /// Returns a clone of the source reader for control interfaces.
     pub fn get_reader(&self) -> SendableSourceReader {
         SendableSourceReader(self.source_reader.0.clone())
@@ -492,9 +510,29 @@ impl Stream for MsmfStream {
     ///
     /// This method uses a semaphore to efficiently wait for new frames
     /// without busy-waiting.
+    ///
+    /// UVC cameras on Media Foundation are free-running, so trigger mode is
+    /// emulated: samples that started exposing before the pending software
+    /// trigger are discarded.
     async fn next_frame(&mut self) -> Result<Frame<'_>> {
-        let sample = self.wait_for_sample().await?;
-        let ts_raw = self.shared.timestamp.load(Ordering::Acquire);
+        let (sample, ts_raw, arrival_time, decision) = loop {
+            let sample = self.wait_for_sample().await?;
+            let ts_raw = self.shared.timestamp.load(Ordering::Acquire);
+            let arrival_time = Instant::now();
+
+            let decision = if self.trigger.is_enabled() {
+                // A sample is delivered at least one frame interval after its exposure began.
+                let exposure_start = arrival_time
+                    .checked_sub(self.frame_interval)
+                    .unwrap_or(arrival_time);
+                self.trigger.admit(exposure_start)
+            } else {
+                TriggerDecision::FreeRun
+            };
+            if decision != TriggerDecision::Drop {
+                break (sample, ts_raw, arrival_time, decision);
+            }
+        };
 
         self.copy_sample_to_linear_buffer(&sample.0)?;
 
@@ -510,9 +548,12 @@ impl Stream for MsmfStream {
             sequence: self.sequence,
             timestamp: Timestamp {
                 hw_raw_ns: hw_ns,
-                system_synced: self.clock_sync.correct(hw_ns, Instant::now()),
+                system_synced: self.clock_sync.correct(hw_ns, arrival_time),
+            },
+            metadata: FrameMetadata {
+                trigger_fired: decision == TriggerDecision::Fired,
+                ..Default::default()
             },
-            metadata: FrameMetadata::default(),
             backend_handle: &MSMF_HANDLE_INSTANCE,
         })
     }
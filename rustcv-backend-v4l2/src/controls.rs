@@ -5,8 +5,9 @@ use v4l::Device;
 
 use rustcv_core::error::{CameraError, Result};
 use rustcv_core::traits::{
-    DeviceControls, LensControl, SensorControl, SystemControl, TriggerConfig, TriggerMode,
+    DeviceControls, LensControl, SensorControl, SystemControl, TriggerConfig,
 };
+use rustcv_core::trigger::SoftwareTrigger;
 
 // --- 手动定义 V4L2 标准常量 (Linux ABI) ---
 // 来源: /usr/include/linux/v4l2-controls.h
@@ -27,11 +28,11 @@ const CID_ZOOM_ABSOLUTE: u32 = V4L2_CID_CAMERA_CLASS_BASE + 13; // 0x009A090D
 
 // --- 工厂函数 ---
 
-pub fn create_controls(dev: Arc<Device>) -> DeviceControls {
+pub fn create_controls(dev: Arc<Device>, trigger: SoftwareTrigger) -> DeviceControls {
     DeviceControls {
         sensor: Box::new(V4l2Sensor { dev: dev.clone() }),
         lens: Box::new(V4l2Lens { dev: dev.clone() }),
-        system: Box::new(V4l2System { dev }),
+        system: Box::new(V4l2System { dev, trigger }),
     }
 }
 
@@ -108,6 +109,7 @@ impl LensControl for V4l2Lens {
 // --- 3. 系统控制 (System) ---
 struct V4l2System {
     dev: Arc<Device>,
+    trigger: SoftwareTrigger,
 }
 
 impl SystemControl for V4l2System {
@@ -115,11 +117,13 @@ impl SystemControl for V4l2System {
         Ok(())
     }
 
+    // UVC 相机没有触发输入，由 V4l2Stream 丢弃未触发的帧来模拟
     fn set_trigger(&self, config: TriggerConfig) -> Result<()> {
-        if config.mode == TriggerMode::Off {
-            return Ok(());
-        }
-        Err(CameraError::FormatNotSupported)
+        self.trigger.configure(config)
+    }
+
+    fn fire_software_trigger(&self) -> Result<()> {
+        self.trigger.fire()
     }
 
     fn export_state(&self) -> Result<serde_json::Value> {
@@ -134,6 +138,7 @@ impl SystemControl for V4l2System {
             // 这里的 Value 实现了 Debug，可以直接 format!
             "exposure": exp.map(|v| format!("{:?}", v.value)),
             "gain": gain.map(|v| format!("{:?}", v.value)),
+            "trigger": self.trigger.is_enabled(),
         }))
     }
 }
@@ -6,6 +6,7 @@ use rustcv_core::builder::CameraConfig;
 use rustcv_core::error::{CameraError, Result};
 use rustcv_core::pixel_format::PixelFormat;
 use rustcv_core::traits::{DeviceControls, DeviceInfo, Stream};
+use rustcv_core::trigger::SoftwareTrigger;
 
 use crate::controls::create_controls;
 use crate::pixel_map;
@@ -68,11 +69,19 @@ pub fn open(id: &str, config: CameraConfig) -> Result<(Box<dyn Stream>, DeviceCo
     // Stream 和 Controls 都需要访问同一个 fd，但在 V4L2 中多线程访问同一个 fd 是安全的
     let dev_arc = Arc::new(dev);
 
-    // 5. 初始化流 (申请 Buffer, mmap)
-    let stream = V4l2Stream::new(dev_arc.clone(), &applied_fmt, config.buffer_count)?;
+    // 5. 软件触发状态，由 Stream (丢帧模拟) 与 System 控制共享
+    let trigger = SoftwareTrigger::new();
 
-    // 6. 初始化控制器 (Sensor, Lens, System)
-    let controls = create_controls(dev_arc);
+    // 6. 初始化流 (申请 Buffer, mmap)
+    let stream = V4l2Stream::new(
+        dev_arc.clone(),
+        &applied_fmt,
+        config.buffer_count,
+        trigger.clone(),
+    )?;
+
+    // 7. 初始化控制器 (Sensor, Lens, System)
+    let controls = create_controls(dev_arc, trigger);
 
     Ok((Box::new(stream), controls))
 }
@@ -1,6 +1,6 @@
 use std::io;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use v4l::buffer::Type;
@@ -12,6 +12,7 @@ use rustcv_core::error::{CameraError, Result};
 use rustcv_core::frame::{BackendBufferHandle, Frame, FrameMetadata, Timestamp};
 use rustcv_core::time::ClockSynchronizer;
 use rustcv_core::traits::Stream; // 这里是 rustcv 定义的 trait
+use rustcv_core::trigger::{SoftwareTrigger, TriggerDecision};
 
 // 本地句柄结构体，解决孤儿规则
 #[derive(Debug)]
@@ -26,13 +27,19 @@ pub struct V4l2Stream {
     format: v4l::Format,
     clock_sync: ClockSynchronizer,
     is_streaming: bool,
+    trigger: SoftwareTrigger,
     _dev: Arc<v4l::Device>,
 }
 
 unsafe impl Send for V4l2Stream {}
 
 impl V4l2Stream {
-    pub fn new(dev: Arc<v4l::Device>, fmt: &v4l::Format, buf_count: usize) -> Result<Self> {
+    pub fn new(
+        dev: Arc<v4l::Device>,
+        fmt: &v4l::Format,
+        buf_count: usize,
+        trigger: SoftwareTrigger,
+    ) -> Result<Self> {
         let stream =
             v4l::io::mmap::Stream::with_buffers(&dev, Type::VideoCapture, buf_count as u32)
                 .map_err(CameraError::Io)?;
@@ -42,6 +49,7 @@ impl V4l2Stream {
             format: *fmt,
             clock_sync: ClockSynchronizer::new(30),
             is_streaming: false,
+            trigger,
             _dev: dev,
         })
     }
@@ -63,24 +71,54 @@ impl Stream for V4l2Stream {
         Ok(())
     }
 
+    /// 出队下一帧
+    ///
+    /// 触发模式下被丢弃的帧不会交给调用方。理想的写法是在循环里直接返回 `inner.next()`
+    /// 借出的缓冲区，但条件性地从循环中返回可变借用目前无法通过借用检查 (NLL)，
+    /// 而 v4l 的 mmap `Stream` 不提供按索引重新借出已出队缓冲区的接口，
+    /// 所以循环内只保留裸指针，循环外再转回切片。这依赖以下不变量:
+    ///
+    /// - 切片指向 `inner` 持有的 mmap 区域，只在 `inner` 被 drop 时解除映射；
+    /// - 该缓冲区只在下一次 `inner.next()` 时重新入队，而那需要 `&mut self`，
+    ///   返回的 `Frame<'_>` 借用着 `self`，两者不会重叠；
+    /// - 循环中丢弃的帧对应的指针在下一轮 `next()` 之前就被覆盖，从未被解引用。
     async fn next_frame(&mut self) -> Result<Frame<'_>> {
         if !self.is_streaming {
             return Err(CameraError::Io(io::Error::other("Stream not started")));
         }
 
         // 调用 CaptureStream 的 next
-        let (buf, meta) = self.inner.next().map_err(CameraError::Io)?;
-        let arrival_time = Instant::now();
+        // 触发模式下持续出队，直到有一帧响应了触发 (丢弃的缓冲区在下次 next 时重新入队)
+        let (buf, meta, arrival_time, decision) = loop {
+            let (buf, meta) = self.inner.next().map_err(CameraError::Io)?;
+            let meta = *meta;
+            let arrival_time = Instant::now();
+
+            let decision = if self.trigger.is_enabled() {
+                let hw_ns = timeval_to_ns(&meta.timestamp);
+                self.trigger
+                    .admit(monotonic_to_instant(hw_ns, arrival_time))
+            } else {
+                TriggerDecision::FreeRun
+            };
+            if decision == TriggerDecision::Drop {
+                continue;
+            }
+            // 只保留指针以结束对 inner 的借用，见上方的不变量说明
+            break (buf as *const [u8], meta, arrival_time, decision);
+        };
+        // SAFETY: buf 是最后一次 inner.next() 返回的 mmap 缓冲区，
+        // 在返回的 Frame (借用 self) 结束之前不会重新入队或解除映射
+        let buf: &[u8] = unsafe { &*buf };
 
-        let hw_ns =
-            (meta.timestamp.sec as u64 * 1_000_000_000) + (meta.timestamp.usec as u64 * 1_000);
+        let hw_ns = timeval_to_ns(&meta.timestamp);
 
         let synced_time = self.clock_sync.correct(hw_ns, arrival_time);
 
         let metadata = FrameMetadata {
             actual_exposure_us: None,
             actual_gain_db: None,
-            trigger_fired: false,
+            trigger_fired: decision == TriggerDecision::Fired,
             strobe_active: false,
         };
 
@@ -109,3 +147,18 @@ impl Stream for V4l2Stream {
         ))
     }
 }
+
+fn timeval_to_ns(ts: &v4l::timestamp::Timestamp) -> u64 {
+    (ts.sec as u64 * 1_000_000_000) + (ts.usec as u64 * 1_000)
+}
+
+/// 将 CLOCK_MONOTONIC 时间戳换算为 Instant
+/// uvcvideo 默认使用单调时钟，并在帧开始 (SOE) 时打时间戳，可视为曝光开始时刻
+fn monotonic_to_instant(ts_ns: u64, now: Instant) -> Instant {
+    // SAFETY: timespec 是纯数据结构，全零合法；tp 是有效的可写指针
+    let mut tp: libc::timespec = unsafe { std::mem::zeroed() };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut tp) };
+    let now_ns = tp.tv_sec as u64 * 1_000_000_000 + tp.tv_nsec as u64;
+    now.checked_sub(Duration::from_nanos(now_ns.saturating_sub(ts_ns)))
+        .unwrap_or(now)
+}
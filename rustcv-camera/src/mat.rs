@@ -73,6 +73,35 @@ impl Mat {
         self.step = (cols as usize) * (channels as usize);
     }
 
+    /// Wrap an existing tightly packed BGR buffer without copying.
+    /// 包装已有的紧凑 BGR 缓冲区（不复制）。
+    ///
+    /// Returns `None` if `data.len() != rows * cols * 3`.
+    /// 如果 `data.len() != rows * cols * 3` 则返回 `None`。
+    pub fn from_bgr(rows: u32, cols: u32, data: Vec<u8>) -> Option<Self> {
+        if data.len() != (rows as usize) * (cols as usize) * 3 {
+            return None;
+        }
+        Some(Self {
+            data,
+            rows,
+            cols,
+            channels: 3,
+            step: (cols as usize) * 3,
+        })
+    }
+
+    /// Consume the Mat and return its BGR buffer without copying.
+    /// 消费 Mat 并返回其 BGR 缓冲区（不复制）。
+    ///
+    /// Together with [`from_bgr()`](Self::from_bgr) this lets other image
+    /// containers (e.g. `rustcv::Mat`) take over the pixels for free.
+    /// 与 [`from_bgr()`](Self::from_bgr) 配合，其他图像容器（如 `rustcv::Mat`）
+    /// 可以零成本接管像素数据。
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
     /// Returns `true` if this Mat contains no data.
     /// 如果此 Mat 不包含数据则返回 `true`。
     pub fn is_empty(&self) -> bool {
@@ -9,6 +9,7 @@ pub mod pixel_format;
 pub mod telemetry;
 pub mod time;
 pub mod traits;
+pub mod trigger;
 
 // 方便用户使用的 Prelude
 pub mod prelude {
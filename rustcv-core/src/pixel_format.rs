@@ -43,6 +43,10 @@ impl FourCC {
     pub const NV12: Self = Self::new(b'N', b'V', b'1', b'2');
     /// YV12 4:2:0 (Planar)
     pub const YV12: Self = Self::new(b'Y', b'V', b'1', b'2');
+    /// I420 4:2:0 (Planar, Y-U-V 顺序，V4L2 中称为 YU12)
+    pub const I420: Self = Self::new(b'Y', b'U', b'1', b'2');
+    /// 8-bit 灰度 (仅 Y 平面)
+    pub const GREY: Self = Self::new(b'G', b'R', b'E', b'Y');
 
     // --- RGB Formats ---
     /// RGB24 (Little Endian: B-G-R)
@@ -114,7 +118,8 @@ impl PixelFormat {
                 FourCC::YUYV | FourCC::UYVY => 16,
                 FourCC::BGR3 | FourCC::RGB3 => 24,
                 FourCC::RGBA | FourCC::BGRA => 32,
-                FourCC::NV12 | FourCC::YV12 => 12, // 平均 12 bpp
+                FourCC::NV12 | FourCC::YV12 | FourCC::I420 => 12, // 平均 12 bpp
+                FourCC::GREY => 8,
                 FourCC::Z16 => 16,
                 // Bayer 8-bit
                 FourCC::BA81 | FourCC::GBRG | FourCC::GRBG | FourCC::RGGB => 8,
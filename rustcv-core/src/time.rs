@@ -1,11 +1,77 @@
 use std::collections::VecDeque;
-use std::sync::OnceLock;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
-// 1. 定义一个全局静态变量来存储进程启动时间
+// 全局静态变量存储进程启动时间，作为单调时钟的锚点
 // OnceLock 保证它只会被初始化一次，且是线程安全的。
 static PROCESS_START: OnceLock<Instant> = OnceLock::new();
-static PROCESS_START_TIME: OnceLock<Instant> = OnceLock::new();
+
+/// 时间源：系统侧的 "现在"
+///
+/// 返回值是相对某个固定锚点的单调时间。`ClockSynchronizer` 只关心差值，
+/// 所以不同时间源的锚点可以不同，但同一个同步器内必须使用同一个时间源。
+pub trait TimeSource: Send + Sync + Debug {
+    fn now(&self) -> Duration;
+}
+
+/// 真实单调时钟 (锚定到进程内第一次使用的时刻)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonotonicClock;
+
+impl MonotonicClock {
+    /// 将 `Instant` 换算到本时钟的时间基准
+    pub fn since_anchor(t: Instant) -> Duration {
+        let anchor = PROCESS_START.get_or_init(Instant::now);
+        // 使用 saturating_duration_since 防止 t 早于锚点时 panic
+        t.saturating_duration_since(*anchor)
+    }
+}
+
+impl TimeSource for MonotonicClock {
+    fn now(&self) -> Duration {
+        Self::since_anchor(Instant::now())
+    }
+}
+
+/// 虚拟时钟：只有手动推进时才会走
+///
+/// 克隆共享同一个时刻，测试代码持有一份，被测对象持有另一份。
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now_ns: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从指定时刻开始
+    pub fn starting_at(start: Duration) -> Self {
+        let clock = Self::new();
+        clock.set(start);
+        clock
+    }
+
+    /// 向前推进
+    pub fn advance(&self, by: Duration) {
+        self.now_ns
+            .fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// 直接设置当前时刻 (允许回拨，用于模拟时钟异常)
+    pub fn set(&self, now: Duration) {
+        self.now_ns.store(now.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl TimeSource for VirtualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.now_ns.load(Ordering::SeqCst))
+    }
+}
 
 /// 软件锁相环 (Software PLL) 与时间同步器
 ///
@@ -19,50 +85,66 @@ pub struct ClockSynchronizer {
     /// 滑动窗口大小 (例如最近 30 帧)
     window_size: usize,
     /// 历史数据点 (HW_Timestamp, System_Arrival_Time)
-    history: VecDeque<(u64, Instant)>,
+    history: VecDeque<(u64, Duration)>,
     /// 是否已初始化基准
     #[allow(dead_code)]
     baseline_established: bool,
     /// 估算的斜率 (Drift Rate)
     estimated_slope: f64,
-    /// 估算的截距 (Offset)
+    /// 估算的截距 (Offset，相对窗口内第一个数据点，纳秒)
     estimated_offset: f64,
+    /// 到达时间的来源
+    source: Arc<dyn TimeSource>,
 }
 
 impl ClockSynchronizer {
+    /// 使用真实单调时钟
     pub fn new(window_size: usize) -> Self {
+        Self::with_time_source(window_size, Arc::new(MonotonicClock))
+    }
+
+    /// 使用指定时间源 (例如 `VirtualClock`)
+    pub fn with_time_source(window_size: usize, source: Arc<dyn TimeSource>) -> Self {
         Self {
             window_size: window_size.max(2), // 至少两点决定一条直线
             history: VecDeque::with_capacity(window_size),
             baseline_established: false,
             estimated_slope: 1.0,
             estimated_offset: 0.0,
+            source,
         }
     }
 
+    pub fn time_source(&self) -> &Arc<dyn TimeSource> {
+        &self.source
+    }
+
     /// 输入一帧的原始硬件时间戳，返回矫正后的系统时间
     ///
     /// * `hw_ns`: 驱动提供的硬件时间戳 (纳秒)
-    /// * `arrival_time`: 帧到达用户态的系统时刻
+    /// * `arrival_time`: 帧到达用户态的系统时刻 (按 `MonotonicClock` 换算)
     pub fn correct(&mut self, hw_ns: u64, arrival_time: Instant) -> Duration {
+        self.correct_at(hw_ns, MonotonicClock::since_anchor(arrival_time))
+    }
+
+    /// 同 `correct`，到达时间取时间源的当前时刻
+    pub fn correct_now(&mut self, hw_ns: u64) -> Duration {
+        let now = self.source.now();
+        self.correct_at(hw_ns, now)
+    }
+
+    /// 同 `correct`，到达时间直接以时间源的基准给出
+    pub fn correct_at(&mut self, hw_ns: u64, arrival: Duration) -> Duration {
         // 1. 记录数据点
         if self.history.len() >= self.window_size {
             self.history.pop_front();
         }
-        self.history.push_back((hw_ns, arrival_time));
+        self.history.push_back((hw_ns, arrival));
 
-        // 2. 如果数据不够，直接返回到达时间作为降级方案
+        // 2. 如果数据不够，假设无漂移，直接对齐到第一帧
         if self.history.len() < 5 {
-            // 在初始化阶段，假设无漂移，直接对齐到第一帧
-            if let Some(start_time) = self.history.front() {
-                // 简单偏移计算
-                let elapsed_hw = hw_ns.saturating_sub(start_time.0);
-                return start_time.1.elapsed() + Duration::from_nanos(elapsed_hw);
-                // 粗略估算
-            }
-            // 兜底：直接返回当前系统时间 (不推荐，但作为 fallback)
-            // 注意：这里需要计算相对于 System Boot 的 duration
-            return self.instant_to_duration(arrival_time);
+            let (base_hw, base_sys) = self.history[0];
+            return base_sys + Duration::from_nanos(hw_ns.saturating_sub(base_hw));
         }
 
         // 3. 计算线性回归 (y = kx + b)
@@ -72,83 +154,132 @@ impl ClockSynchronizer {
         self.recalculate_regression();
 
         // 4. 应用矫正
-        let (base_hw, base_sys) = self.history.front().unwrap();
-        let dx = (hw_ns as f64) - (*base_hw as f64);
-        let predicted_dy_ns = self.estimated_slope * dx + self.estimated_offset;
+        let (base_hw, base_sys) = self.history[0];
+        let dx = (hw_ns as f64) - (base_hw as f64);
+        let predicted_ns =
+            base_sys.as_nanos() as f64 + self.estimated_slope * dx + self.estimated_offset;
+        Duration::from_nanos(predicted_ns.max(0.0) as u64)
+    }
 
-        let base_sys_dur = self.instant_to_duration(*base_sys);
-        base_sys_dur + Duration::from_nanos(predicted_dy_ns as u64)
+    /// 当前估计的斜率 (系统时间 / 硬件时间)，1.0 表示没有漂移
+    pub fn slope(&self) -> f64 {
+        self.estimated_slope
     }
 
-    /// 简单的最小二乘法实现
+    /// 当前估计的截距：硬件时间 0 对应的系统时间 (纳秒，可能为负)
+    pub fn offset_ns(&self) -> f64 {
+        match self.history.front() {
+            Some(&(base_hw, base_sys)) => {
+                base_sys.as_nanos() as f64 + self.estimated_offset
+                    - self.estimated_slope * base_hw as f64
+            }
+            None => 0.0,
+        }
+    }
+
+    /// 最小二乘法 (先去均值，避免大数相减损失精度)
     fn recalculate_regression(&mut self) {
         let n = self.history.len() as f64;
-        let (base_hw, base_sys) = self.history.front().unwrap();
-        let base_sys_scalar = self.instant_to_scalar(*base_sys);
-
-        let mut sum_x = 0.0;
-        let mut sum_y = 0.0;
-        let mut sum_xy = 0.0;
-        let mut sum_xx = 0.0;
-
-        for (hw, sys) in &self.history {
-            let x = (*hw as f64) - (*base_hw as f64);
-            let y = self.instant_to_scalar(*sys) - base_sys_scalar;
-
-            sum_x += x;
-            sum_y += y;
-            sum_xy += x * y;
-            sum_xx += x * x;
+        let (base_hw, base_sys) = self.history[0];
+        let point = |&(hw, sys): &(u64, Duration)| {
+            let x = hw as f64 - base_hw as f64;
+            let y = sys.as_nanos() as f64 - base_sys.as_nanos() as f64;
+            (x, y)
+        };
+
+        let (sum_x, sum_y) = self
+            .history
+            .iter()
+            .map(point)
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        let (mean_x, mean_y) = (sum_x / n, sum_y / n);
+
+        let (mut sxy, mut sxx) = (0.0, 0.0);
+        for (x, y) in self.history.iter().map(point) {
+            sxy += (x - mean_x) * (y - mean_y);
+            sxx += (x - mean_x) * (x - mean_x);
         }
 
-        let denominator = n * sum_xx - sum_x * sum_x;
-        if denominator.abs() < 1e-6 {
+        if sxx.abs() < 1e-6 {
             // 避免除零 (比如时间戳完全没变)
             self.estimated_slope = 1.0;
             self.estimated_offset = 0.0;
         } else {
-            self.estimated_slope = (n * sum_xy - sum_x * sum_y) / denominator;
-            self.estimated_offset = (sum_y * sum_xx - sum_x * sum_xy) / denominator;
+            self.estimated_slope = sxy / sxx;
+            self.estimated_offset = mean_y - self.estimated_slope * mean_x;
         }
     }
+}
 
-    // 辅助：将 Instant 转为 f64 (秒), 仅用于计算差值
-    fn instant_to_scalar(&self, t: Instant) -> f64 {
-        // 这里实际上只需要相对值，不需要绝对 epoch
-        // 我们可以用 t.elapsed() 的反向值，或者既然在一个进程内，
-        // 我们统一参考 lazy_static 的启动时间点会更准。
-        // 为简化代码，这里假设 t 是单调的，转换成纳秒 float。
-        // 在实际生产代码中，应使用 Duration since Boot。
-        // t.elapsed().as_secs_f64() // 这是一个负相关的量，有点 tricky。
-        // 修正：应该保存一个 Process Start Time。
-        // 这里暂略，仅展示逻辑框架。
-        // 0.0
-
-        // 获取启动时间锚点（如果还没初始化，这里会初始化为当前时间）
-        let start_time = PROCESS_START.get_or_init(Instant::now);
-
-        if t >= *start_time {
-            // 正常情况：t 在启动时间之后
-            t.duration_since(*start_time).as_secs_f64()
-        } else {
-            // 边缘情况：t 在启动时间之前（极少见，但为了数学严谨性）
-            // 返回负数
-            -(start_time.duration_since(t).as_secs_f64())
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 确定性的均匀抖动 [-max, +max] (LCG)
+    fn jitter(state: &mut u64, max_ns: i64) -> i64 {
+        *state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((*state >> 33) as i64 % (2 * max_ns + 1)) - max_ns
     }
 
-    fn instant_to_duration(&self, t: Instant) -> Duration {
-        // 将 Instant 转换为 "System Up Time" (CLOCK_MONOTONIC)
-        // 这是一个平台相关的操作。
-        // 在 Linux 上 Instant 已经是 monotonic。
-        // 我们简单返回 elapsed。实际上应该对齐到 UNIX EPOCH 或 Boot Time。
-        // 这是一个 Placeholder。
-        // Duration::from_nanos(0)
+    #[test]
+    fn virtual_clock_only_moves_when_told() {
+        let clock = VirtualClock::starting_at(Duration::from_secs(3));
+        let shared = clock.clone();
+        assert_eq!(clock.now(), Duration::from_secs(3));
+        shared.advance(Duration::from_millis(250));
+        assert_eq!(clock.now(), Duration::from_millis(3_250));
+    }
 
-        // 第一次调用时会执行 Instant::now()，后续调用直接返回该值
-        let anchor = PROCESS_START_TIME.get_or_init(Instant::now);
+    #[test]
+    fn recovers_drift_and_offset_under_jitter() {
+        // 硬件时钟快 200ppm，USB 固定延迟 3ms，叠加 ±2ms 的到达抖动
+        let drift = 200e-6;
+        let offset = Duration::from_secs(5) + Duration::from_millis(3);
+        let clock = VirtualClock::starting_at(offset);
+        let mut sync = ClockSynchronizer::with_time_source(300, Arc::new(clock.clone()));
 
-        // 使用 saturating_duration_since 防止在极端边缘情况下 (t 早于 anchor) panic
-        t.saturating_duration_since(*anchor)
+        let interval = Duration::from_nanos(33_333_333);
+        let mut rng = 42;
+        let mut max_err = 0i64;
+        for i in 0..600u64 {
+            let true_ns = i * interval.as_nanos() as u64;
+            let hw_ns = (true_ns as f64 * (1.0 + drift)) as u64;
+
+            clock.set(offset + Duration::from_nanos(true_ns));
+            let j = jitter(&mut rng, 2_000_000);
+            let arrival = clock.now().as_nanos() as i64 + j;
+            let synced = sync.correct_at(hw_ns, Duration::from_nanos(arrival as u64));
+
+            if i >= 300 {
+                let err = synced.as_nanos() as i64 - (offset.as_nanos() as i64 + true_ns as i64);
+                max_err = max_err.max(err.abs());
+            }
+        }
+
+        let expected_slope = 1.0 / (1.0 + drift);
+        assert!(
+            (sync.slope() - expected_slope).abs() < 2e-5,
+            "{}",
+            sync.slope()
+        );
+        assert!((sync.offset_ns() - offset.as_nanos() as f64).abs() < 500_000.0);
+        // 矫正后的时间戳误差远小于原始抖动
+        assert!(max_err < 500_000, "{}", max_err);
+    }
+
+    #[test]
+    fn correct_now_reads_the_time_source() {
+        let clock = VirtualClock::new();
+        let mut sync = ClockSynchronizer::with_time_source(30, Arc::new(clock.clone()));
+        clock.set(Duration::from_secs(10));
+        assert_eq!(sync.correct_now(1_000), Duration::from_secs(10));
+        clock.advance(Duration::from_millis(40));
+        // 数据不足时按硬件时间差外推
+        assert_eq!(
+            sync.correct_now(1_000 + 33_000_000),
+            Duration::from_secs(10) + Duration::from_millis(33)
+        );
     }
 }
@@ -117,8 +117,13 @@ pub trait Stream: Send {
     async fn next_frame(&mut self) -> Result<Frame<'_>>;
 
     /// 【逃生舱口】直接注入虚拟帧 (用于仿真)
+    /// 真实硬件后端无需实现，默认返回 SimulationError
     #[cfg(feature = "simulation")]
-    async fn inject_frame(&mut self, frame: Frame<'_>) -> Result<()>;
+    async fn inject_frame(&mut self, _frame: Frame<'_>) -> Result<()> {
+        Err(crate::error::CameraError::SimulationError(
+            "Frame injection is not supported by this backend".into(),
+        ))
+    }
 }
 
 /// 3. 控制面聚合体
@@ -152,6 +157,14 @@ pub trait SystemControl: Send + Sync {
     /// 设置硬件触发模式
     fn set_trigger(&self, config: TriggerConfig) -> Result<()>;
 
+    /// 发出一次软件触发
+    /// 需先以 `TriggerSource::Software` 开启触发模式，`TriggerMode::Standard` 下每次触发产出一帧
+    fn fire_software_trigger(&self) -> Result<()> {
+        Err(crate::error::CameraError::BackendError(
+            "Software trigger is not supported by this backend".into(),
+        ))
+    }
+
     /// 导出当前配置快照 (用于持久化)
     /// 返回值使用 serde_json::Value 以兼容不同后端的配置结构
     #[cfg(feature = "serialize")]
@@ -0,0 +1,239 @@
+//! 软件触发
+//!
+//! 控制面 (`SystemControl::set_trigger` / `fire_software_trigger`) 与数据面 (`Stream`)
+//! 共享同一个 `SoftwareTrigger`。
+//!
+//! - 原生支持触发的后端 (仿真) 在收到触发后才开始曝光，用 `wait()` 等待触发。
+//! - 自由运行的 UVC 相机没有触发输入，用丢帧模拟：`TriggerMode::Standard` 下，
+//!   每次触发只放行第一帧曝光开始时刻不早于 "触发时刻 + delay" 的帧，见 `admit()`。
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::error::{CameraError, Result};
+use crate::traits::{TriggerConfig, TriggerMode, TriggerSource};
+
+/// 数据面对一帧的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerDecision {
+    /// 触发关闭，连续采集
+    FreeRun,
+    /// 该帧响应了一次触发 (`FrameMetadata::trigger_fired = true`)
+    Fired,
+    /// 没有对应的触发，丢弃
+    Drop,
+}
+
+#[derive(Debug, Default)]
+struct TriggerState {
+    config: TriggerConfig,
+    /// 尚未被消费的触发时刻
+    pending: VecDeque<Instant>,
+    wakers: Vec<Waker>,
+}
+
+/// 软件触发句柄 (克隆共享同一份状态)
+#[derive(Debug, Clone, Default)]
+pub struct SoftwareTrigger {
+    state: Arc<Mutex<TriggerState>>,
+}
+
+impl SoftwareTrigger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 应用触发配置
+    /// 软件实现只有一个触发源，仅支持 `Off` 与 `Standard` + `TriggerSource::Software`
+    pub fn configure(&self, config: TriggerConfig) -> Result<()> {
+        match (config.mode, config.source) {
+            (TriggerMode::Off, _) | (TriggerMode::Standard, TriggerSource::Software) => {}
+            _ => return Err(CameraError::FormatNotSupported),
+        }
+
+        let mut state = self.lock();
+        state.config = config;
+        state.pending.clear();
+        // 唤醒等待者，让它们按新配置重新判断
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// 当前触发配置
+    pub fn config(&self) -> TriggerConfig {
+        self.lock().config
+    }
+
+    /// 是否处于触发模式
+    pub fn is_enabled(&self) -> bool {
+        self.lock().config.mode != TriggerMode::Off
+    }
+
+    /// 发出一次软件触发
+    pub fn fire(&self) -> Result<()> {
+        let mut state = self.lock();
+        if state.config.mode == TriggerMode::Off {
+            return Err(CameraError::BackendError(
+                "Trigger mode is off, call set_trigger first".into(),
+            ));
+        }
+        state.pending.push_back(Instant::now());
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// 尚未被消费的触发次数
+    pub fn pending(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    /// 丢帧模拟：判断一帧是否放行
+    ///
+    /// * `exposure_start`: 该帧曝光开始时刻的估计值 (由后端根据硬件时间戳换算)
+    pub fn admit(&self, exposure_start: Instant) -> TriggerDecision {
+        let mut state = self.lock();
+        if state.config.mode == TriggerMode::Off {
+            return TriggerDecision::FreeRun;
+        }
+
+        let delay = Duration::from_micros(state.config.delay_us as u64);
+        match state.pending.front() {
+            // 触发之后才开始曝光的第一帧
+            Some(&fired_at) if exposure_start >= fired_at + delay => {
+                state.pending.pop_front();
+                TriggerDecision::Fired
+            }
+            _ => TriggerDecision::Drop,
+        }
+    }
+
+    /// 原生触发：等待并消费一次触发，返回触发时刻
+    /// 等待期间触发模式被关闭时返回 `None`
+    pub fn wait(&self) -> WaitTrigger<'_> {
+        WaitTrigger { trigger: self }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TriggerState> {
+        // 持锁期间不会 panic，中毒时直接取回数据
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// `SoftwareTrigger::wait` 返回的 Future
+#[derive(Debug)]
+pub struct WaitTrigger<'a> {
+    trigger: &'a SoftwareTrigger,
+}
+
+impl Future for WaitTrigger<'_> {
+    type Output = Option<Instant>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.trigger.lock();
+        if state.config.mode == TriggerMode::Off {
+            return Poll::Ready(None);
+        }
+        if let Some(fired_at) = state.pending.pop_front() {
+            return Poll::Ready(Some(fired_at));
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard() -> TriggerConfig {
+        TriggerConfig {
+            mode: TriggerMode::Standard,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_configs() {
+        let trigger = SoftwareTrigger::new();
+        assert!(trigger.fire().is_err());
+
+        let bulb = TriggerConfig {
+            mode: TriggerMode::Bulb,
+            ..Default::default()
+        };
+        assert!(trigger.configure(bulb).is_err());
+
+        let line = TriggerConfig {
+            source: TriggerSource::Line0,
+            ..standard()
+        };
+        assert!(trigger.configure(line).is_err());
+        assert!(!trigger.is_enabled());
+    }
+
+    #[test]
+    fn admits_one_frame_per_trigger() {
+        let trigger = SoftwareTrigger::new();
+        let before = Instant::now();
+        assert_eq!(trigger.admit(before), TriggerDecision::FreeRun);
+
+        trigger.configure(standard()).unwrap();
+        assert_eq!(trigger.admit(before), TriggerDecision::Drop);
+
+        trigger.fire().unwrap();
+        // 触发前就已开始曝光的帧 (驱动队列里的旧帧) 被丢弃
+        assert_eq!(trigger.admit(before), TriggerDecision::Drop);
+        let after = Instant::now() + Duration::from_millis(1);
+        assert_eq!(trigger.admit(after), TriggerDecision::Fired);
+        assert_eq!(trigger.admit(after), TriggerDecision::Drop);
+    }
+
+    #[test]
+    fn delay_postpones_admission() {
+        let trigger = SoftwareTrigger::new();
+        trigger
+            .configure(TriggerConfig {
+                delay_us: 50_000,
+                ..standard()
+            })
+            .unwrap();
+        trigger.fire().unwrap();
+
+        let now = Instant::now();
+        assert_eq!(trigger.admit(now), TriggerDecision::Drop);
+        let later = now + Duration::from_millis(60);
+        assert_eq!(trigger.admit(later), TriggerDecision::Fired);
+    }
+
+    #[tokio::test]
+    async fn wait_resolves_on_fire_and_on_disable() {
+        let trigger = SoftwareTrigger::new();
+        trigger.configure(standard()).unwrap();
+
+        let remote = trigger.clone();
+        let firing = tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            remote.fire().unwrap();
+        });
+        assert!(trigger.wait().await.is_some());
+        firing.await.unwrap();
+        assert_eq!(trigger.pending(), 0);
+
+        let remote = trigger.clone();
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            remote.configure(TriggerConfig::default()).unwrap();
+        });
+        assert!(trigger.wait().await.is_none());
+    }
+}
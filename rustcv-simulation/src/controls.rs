@@ -0,0 +1,209 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use rustcv_core::error::Result;
+use rustcv_core::traits::{
+    DeviceControls, LensControl, SensorControl, SystemControl, TriggerConfig,
+};
+use rustcv_core::trigger::SoftwareTrigger;
+
+use crate::sensor::{self, ControlSnapshot};
+
+/// 控制面与数据面共享的参数
+/// `SimStream` 每出一帧读取一次快照，设置立即在下一帧生效
+#[derive(Debug)]
+pub(crate) struct ControlState {
+    pub(crate) exposure_us: AtomicU32,
+    pub(crate) zoom: AtomicU32,
+    pub(crate) focus: AtomicU32,
+    /// 合焦位置 (设备属性，不可修改)
+    pub(crate) focus_target: u32,
+    /// 软件触发 (仿真传感器原生支持)
+    pub(crate) trigger: SoftwareTrigger,
+}
+
+impl ControlState {
+    pub(crate) fn new(focus_target: u32) -> Self {
+        Self {
+            exposure_us: AtomicU32::new(sensor::NOMINAL_EXPOSURE_US),
+            zoom: AtomicU32::new(sensor::ZOOM_1X),
+            focus: AtomicU32::new(0),
+            focus_target,
+            trigger: SoftwareTrigger::new(),
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> ControlSnapshot {
+        ControlSnapshot {
+            exposure_us: self.exposure_us.load(Ordering::Relaxed),
+            zoom: self.zoom.load(Ordering::Relaxed),
+            defocus: self
+                .focus
+                .load(Ordering::Relaxed)
+                .abs_diff(self.focus_target),
+        }
+    }
+}
+
+// --- 工厂函数 ---
+
+pub(crate) fn create_controls(state: Arc<ControlState>) -> DeviceControls {
+    DeviceControls {
+        sensor: Box::new(SimSensor {
+            state: state.clone(),
+        }),
+        lens: Box::new(SimLens {
+            state: state.clone(),
+        }),
+        system: Box::new(SimSystem { state }),
+    }
+}
+
+// --- 1. 传感器控制 (Sensor) ---
+struct SimSensor {
+    state: Arc<ControlState>,
+}
+
+impl SensorControl for SimSensor {
+    fn set_exposure(&self, value_us: u32) -> Result<()> {
+        self.state.exposure_us.store(value_us, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn get_exposure(&self) -> Result<u32> {
+        Ok(self.state.exposure_us.load(Ordering::Relaxed))
+    }
+}
+
+// --- 2. 镜头控制 (Lens) ---
+struct SimLens {
+    state: Arc<ControlState>,
+}
+
+impl LensControl for SimLens {
+    fn set_zoom(&self, zoom: u32) -> Result<()> {
+        self.state.zoom.store(zoom, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn set_focus(&self, focus: u32) -> Result<()> {
+        self.state.focus.store(focus, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+// --- 3. 系统控制 (System) ---
+struct SimSystem {
+    state: Arc<ControlState>,
+}
+
+impl SystemControl for SimSystem {
+    unsafe fn force_reset(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_trigger(&self, config: TriggerConfig) -> Result<()> {
+        self.state.trigger.configure(config)
+    }
+
+    fn fire_software_trigger(&self) -> Result<()> {
+        self.state.trigger.fire()
+    }
+
+    fn export_state(&self) -> Result<serde_json::Value> {
+        use serde_json::json;
+
+        Ok(json!({
+            "backend": "simulation",
+            "exposure": self.state.exposure_us.load(Ordering::Relaxed),
+            "zoom": self.state.zoom.load(Ordering::Relaxed),
+            "focus": self.state.focus.load(Ordering::Relaxed),
+            "focus_target": self.state.focus_target,
+            "trigger": self.state.trigger.is_enabled(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SimDevice, SimDriver, TestPattern};
+    use rustcv_core::builder::{CameraConfig, Priority};
+    use rustcv_core::pixel_format::FourCC;
+    use rustcv_core::traits::{DeviceControls, Driver, Stream};
+
+    fn device() -> SimDevice {
+        SimDevice::new("sim0", "Sim").mode(FourCC::BGR3, 160, 120, 30)
+    }
+
+    async fn open(device: SimDevice) -> (Box<dyn Stream>, DeviceControls) {
+        let driver = SimDriver::with_devices(vec![device.realtime(false)]);
+        let config = CameraConfig::new().format(FourCC::BGR3, Priority::Required);
+        let (mut stream, controls) = driver.open("sim0", config).unwrap();
+        stream.start().await.unwrap();
+        (stream, controls)
+    }
+
+    fn mean(data: &[u8]) -> f64 {
+        data.iter().map(|&v| v as f64).sum::<f64>() / data.len() as f64
+    }
+
+    fn sharpness(data: &[u8]) -> u64 {
+        data.windows(4).map(|p| p[0].abs_diff(p[3]) as u64).sum()
+    }
+
+    #[tokio::test]
+    async fn exposure_changes_brightness() {
+        let device = device().pattern(TestPattern::Solid([50, 50, 50]));
+        let (mut stream, controls) = open(device).await;
+
+        let frame = stream.next_frame().await.unwrap();
+        assert_eq!(mean(frame.data), 50.0);
+        assert_eq!(frame.metadata.actual_exposure_us, Some(10_000));
+        assert_eq!(frame.metadata.actual_gain_db, Some(0.0));
+
+        controls.sensor.set_exposure(30_000).unwrap();
+        assert_eq!(controls.sensor.get_exposure().unwrap(), 30_000);
+        let frame = stream.next_frame().await.unwrap();
+        assert_eq!(mean(frame.data), 150.0);
+        assert_eq!(frame.metadata.actual_exposure_us, Some(30_000));
+
+        // 超过帧间隔的曝光被截断
+        controls.sensor.set_exposure(1_000_000).unwrap();
+        let frame = stream.next_frame().await.unwrap();
+        assert_eq!(frame.metadata.actual_exposure_us, Some(33_333));
+        assert_eq!(mean(frame.data), 167.0);
+    }
+
+    #[tokio::test]
+    async fn focus_sweep_finds_target() {
+        let device = device()
+            .pattern(TestPattern::Checkerboard(8))
+            .focus_target(40);
+        let (mut stream, controls) = open(device).await;
+
+        let mut best = (0, 0);
+        for focus in (0..=96).step_by(8) {
+            controls.lens.set_focus(focus).unwrap();
+            let s = sharpness(stream.next_frame().await.unwrap().data);
+            if s > best.1 {
+                best = (focus, s);
+            }
+        }
+        assert_eq!(best.0, 40);
+    }
+
+    #[tokio::test]
+    async fn zoom_magnifies_the_center() {
+        // 8 条彩条，2x 变焦后只剩中间 4 条
+        let device = device().pattern(TestPattern::ColorBars);
+        let (mut stream, controls) = open(device).await;
+
+        controls.lens.set_zoom(200).unwrap();
+        let frame = stream.next_frame().await.unwrap();
+        let row = &frame.data[..160 * 3];
+        // 最左侧原本是白条，现在是第 3 条 (青)
+        assert_eq!(&row[..3], &[255, 255, 0]);
+        // 最右侧原本是黑条，现在是第 6 条 (红)
+        assert_eq!(&row[row.len() - 3..], &[0, 0, 255]);
+    }
+}
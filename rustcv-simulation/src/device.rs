@@ -0,0 +1,284 @@
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+
+use rustcv_core::builder::{CameraConfig, Priority};
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::pixel_format::{FourCC, PixelFormat};
+use rustcv_core::time::{MonotonicClock, TimeSource};
+use rustcv_core::traits::DeviceInfo;
+
+use crate::encode;
+use crate::fault::FaultPlan;
+use crate::inject::FrameInjector;
+use crate::pattern::TestPattern;
+
+/// 虚拟设备支持的一种输出模式
+/// 帧率为上限，协商时可以降到任意更低的帧率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimMode {
+    pub format: FourCC,
+    pub width: u32,
+    pub height: u32,
+    pub max_fps: u32,
+}
+
+impl SimMode {
+    pub fn new(format: FourCC, width: u32, height: u32, max_fps: u32) -> Self {
+        Self {
+            format,
+            width,
+            height,
+            max_fps,
+        }
+    }
+}
+
+/// 可配置的虚拟摄像头
+#[derive(Debug, Clone)]
+pub struct SimDevice {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) modes: Vec<SimMode>,
+    pub(crate) pattern: TestPattern,
+    pub(crate) realtime: bool,
+    pub(crate) injector: FrameInjector,
+    pub(crate) faults: FaultPlan,
+    pub(crate) focus_target: u32,
+    pub(crate) time_source: Arc<dyn TimeSource>,
+    /// 已尝试 open 的次数 (用于 `FaultPlan::busy_on_open`)
+    pub(crate) open_attempts: Arc<AtomicU32>,
+}
+
+impl SimDevice {
+    /// 创建一个没有任何模式的设备，通过 `mode()` 添加
+    /// 不添加任何模式时，设备接受任意配置，只输出注入的帧
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            modes: vec![],
+            pattern: TestPattern::default(),
+            realtime: true,
+            injector: FrameInjector::new(),
+            faults: FaultPlan::default(),
+            focus_target: 0,
+            time_source: Arc::new(MonotonicClock),
+            open_attempts: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// 一个典型 UVC 摄像头的模式表 (YUYV / MJPEG / BGR3 / NV12 / Bayer)
+    pub fn standard(index: u32) -> Self {
+        Self::new(
+            format!("sim{}", index),
+            format!("Simulated Camera {}", index),
+        )
+        .mode(FourCC::YUYV, 640, 480, 30)
+        .mode(FourCC::YUYV, 1280, 720, 10)
+        .mode(FourCC::MJPEG, 640, 480, 30)
+        .mode(FourCC::MJPEG, 1280, 720, 30)
+        .mode(FourCC::MJPEG, 1920, 1080, 30)
+        .mode(FourCC::BGR3, 640, 480, 30)
+        .mode(FourCC::NV12, 640, 480, 30)
+        .mode(FourCC::NV12, 1280, 720, 30)
+        .mode(FourCC::BA81, 640, 480, 60)
+        .mode(FourCC::RGGB, 640, 480, 60)
+    }
+
+    /// 添加输出模式
+    pub fn mode(mut self, format: FourCC, width: u32, height: u32, max_fps: u32) -> Self {
+        self.modes
+            .push(SimMode::new(format, width, height, max_fps));
+        self
+    }
+
+    /// 设置测试图案
+    pub fn pattern(mut self, pattern: TestPattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// 是否按协商帧率实时出帧 (默认 true)
+    /// 关闭后 `next_frame` 立即返回，适合跑批测试
+    pub fn realtime(mut self, realtime: bool) -> Self {
+        self.realtime = realtime;
+        self
+    }
+
+    /// 设置故障脚本
+    pub fn faults(mut self, plan: FaultPlan) -> Self {
+        self.faults = plan;
+        self
+    }
+
+    /// 设置合焦位置 (默认 0，即初始对焦值就是清晰的)
+    /// 对焦值偏离该位置越远，画面越模糊，用于测试自动对焦
+    pub fn focus_target(mut self, focus: u32) -> Self {
+        self.focus_target = focus;
+        self
+    }
+
+    /// 设置到达时间的时间源 (默认真实单调时钟)
+    /// 传入 `VirtualClock` 后，帧的到达时刻完全由测试代码控制
+    pub fn time_source(mut self, source: Arc<dyn TimeSource>) -> Self {
+        self.time_source = source;
+        self
+    }
+
+    /// 获取注入队列句柄 (设备的所有克隆共享同一个队列)
+    pub fn injector(&self) -> FrameInjector {
+        self.injector.clone()
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn modes(&self) -> &[SimMode] {
+        &self.modes
+    }
+
+    pub(crate) fn info(&self) -> DeviceInfo {
+        DeviceInfo {
+            name: self.name.clone(),
+            id: self.id.clone(),
+            backend: "Simulation".to_string(),
+            bus_info: Some(format!("sim:{}", self.id)),
+        }
+    }
+}
+
+/// 协商结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedMode {
+    pub format: FourCC,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+}
+
+/// 格式协商：打分规则与 V4L2 后端一致，额外处理帧率与 `Priority::Required`
+pub(crate) fn negotiate(modes: &[SimMode], config: &CameraConfig) -> Result<NegotiatedMode> {
+    let mut best: Option<(i32, NegotiatedMode)> = None;
+
+    for mode in modes {
+        if !encode::is_supported(mode.format)
+            || !encode::dimensions_valid(mode.format, mode.width, mode.height)
+            || mode.max_fps == 0
+        {
+            continue;
+        }
+
+        let Some(score) = score_mode(mode, config) else {
+            continue;
+        };
+
+        let fps = match config.fps_req {
+            Some((fps, _)) if fps > 0 => fps.min(mode.max_fps),
+            _ => mode.max_fps,
+        };
+
+        if best.as_ref().is_none_or(|(s, _)| score > *s) {
+            best = Some((
+                score,
+                NegotiatedMode {
+                    format: mode.format,
+                    width: mode.width,
+                    height: mode.height,
+                    fps,
+                },
+            ));
+        }
+    }
+
+    best.map(|(_, m)| m).ok_or(CameraError::FormatNotSupported)
+}
+
+/// 计算模式得分，不满足 `Required` 约束时返回 None
+fn score_mode(mode: &SimMode, config: &CameraConfig) -> Option<i32> {
+    let mut score = 0;
+
+    // 1. 分辨率
+    let mut required_res = false;
+    let mut res_ok = false;
+    for (w, h, prio) in &config.resolution_req {
+        let hit = mode.width == *w && mode.height == *h;
+        if hit {
+            score += *prio as i32 * 10;
+            res_ok = true;
+        }
+        required_res |= *prio == Priority::Required;
+    }
+    if required_res && !res_ok {
+        return None;
+    }
+
+    // 2. 像素格式
+    let mut required_fmt = false;
+    let mut fmt_ok = false;
+    for (fmt, prio) in &config.format_req {
+        if PixelFormat::Known(mode.format) == *fmt {
+            score += *prio as i32 * 10;
+            fmt_ok = true;
+        }
+        required_fmt |= *prio == Priority::Required;
+    }
+    if required_fmt && !fmt_ok {
+        return None;
+    }
+
+    // 3. 帧率：上限足够即视为满足
+    if let Some((fps, prio)) = config.fps_req {
+        if fps <= mode.max_fps {
+            score += prio as i32 * 10;
+        } else if prio == Priority::Required {
+            return None;
+        }
+    }
+
+    // 4. 分辨率越大基础分越高 (Tie-breaker)
+    score += (mode.width / 100) as i32;
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_requested_format_and_resolution() {
+        let dev = SimDevice::standard(0);
+        let cfg = CameraConfig::new()
+            .resolution(640, 480, Priority::High)
+            .format(FourCC::YUYV, Priority::High)
+            .fps(15, Priority::Medium);
+        let m = negotiate(dev.modes(), &cfg).unwrap();
+        assert_eq!(
+            m,
+            NegotiatedMode {
+                format: FourCC::YUYV,
+                width: 640,
+                height: 480,
+                fps: 15
+            }
+        );
+    }
+
+    #[test]
+    fn required_constraints_are_enforced() {
+        let dev = SimDevice::standard(0);
+        // YUYV 1280x720 上限只有 10fps
+        let cfg = CameraConfig::new()
+            .resolution(1280, 720, Priority::Required)
+            .format(FourCC::YUYV, Priority::Required)
+            .fps(30, Priority::Required);
+        assert!(matches!(
+            negotiate(dev.modes(), &cfg),
+            Err(CameraError::FormatNotSupported)
+        ));
+
+        let cfg = CameraConfig::new().format(FourCC::H264, Priority::Required);
+        assert!(negotiate(dev.modes(), &cfg).is_err());
+    }
+}
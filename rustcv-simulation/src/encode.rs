@@ -0,0 +1,205 @@
+//! BGR24 -> 相机原生像素格式
+//!
+//! 仿真相机按真实硬件的输出布局组帧，这样上层解码路径
+//! (`yuyv_to_bgr`、MJPEG 解码、Demosaic) 拿到的字节与真机一致。
+//!
+//! YUV 换算使用 BT.601 有限范围 (Y: 16-235) 整数公式，
+//! 与 `rustcv` / `rustcv-camera` 中的解码公式互为逆运算。
+
+use image::codecs::jpeg::JpegEncoder;
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::pixel_format::FourCC;
+
+/// MJPEG 合成时的 JPEG 质量 (1-100)
+pub(crate) const MJPEG_QUALITY: u8 = 90;
+
+/// 仿真后端可以产出的像素格式
+pub const SUPPORTED_FORMATS: [FourCC; 8] = [
+    FourCC::YUYV,
+    FourCC::MJPEG,
+    FourCC::BGR3,
+    FourCC::NV12,
+    FourCC::BA81,
+    FourCC::GBRG,
+    FourCC::GRBG,
+    FourCC::RGGB,
+];
+
+/// 判断格式是否可以合成
+pub fn is_supported(fourcc: FourCC) -> bool {
+    SUPPORTED_FORMATS.contains(&fourcc)
+}
+
+/// 判断给定尺寸能否用该格式表示
+/// YUYV 按像素对打包，NV12 与 Bayer 以 2x2 为单元，要求宽高为偶数
+pub(crate) fn dimensions_valid(fourcc: FourCC, width: u32, height: u32) -> bool {
+    if width == 0 || height == 0 {
+        return false;
+    }
+    match fourcc {
+        FourCC::YUYV => width.is_multiple_of(2),
+        FourCC::NV12 | FourCC::BA81 | FourCC::GBRG | FourCC::GRBG | FourCC::RGGB => {
+            width.is_multiple_of(2) && height.is_multiple_of(2)
+        }
+        _ => true,
+    }
+}
+
+/// 每行字节数 (压缩格式没有固定跨距，返回 0)
+pub(crate) fn stride_of(fourcc: FourCC, width: u32) -> usize {
+    let w = width as usize;
+    match fourcc {
+        FourCC::YUYV => w * 2,
+        FourCC::BGR3 => w * 3,
+        // NV12 报告 Y 平面的跨距，UV 平面与其相同
+        FourCC::NV12 | FourCC::BA81 | FourCC::GBRG | FourCC::GRBG | FourCC::RGGB => w,
+        _ => 0,
+    }
+}
+
+/// 将紧凑 BGR24 图像编码为 `fourcc` 格式，结果写入 `out` (会被清空重用)
+pub(crate) fn encode_bgr(
+    fourcc: FourCC,
+    bgr: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let (w, h) = (width as usize, height as usize);
+    out.clear();
+
+    match fourcc {
+        FourCC::BGR3 => out.extend_from_slice(&bgr[..w * h * 3]),
+        FourCC::YUYV => {
+            out.reserve(w * h * 2);
+            for pair in bgr[..w * h * 3].chunks_exact(6) {
+                let (y0, u0, v0) = bgr_to_yuv(pair[0], pair[1], pair[2]);
+                let (y1, u1, v1) = bgr_to_yuv(pair[3], pair[4], pair[5]);
+                out.extend_from_slice(&[y0, avg2(u0, u1), y1, avg2(v0, v1)]);
+            }
+        }
+        FourCC::NV12 => {
+            out.resize(w * h * 3 / 2, 0);
+            let (y_plane, uv_plane) = out.split_at_mut(w * h);
+            for (i, px) in bgr[..w * h * 3].chunks_exact(3).enumerate() {
+                y_plane[i] = bgr_to_yuv(px[0], px[1], px[2]).0;
+            }
+            // 2x2 块平均得到一组 UV
+            for by in 0..h / 2 {
+                for bx in 0..w / 2 {
+                    let (mut u_sum, mut v_sum) = (0u32, 0u32);
+                    for (dy, dx) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                        let idx = ((by * 2 + dy) * w + bx * 2 + dx) * 3;
+                        let (_, u, v) = bgr_to_yuv(bgr[idx], bgr[idx + 1], bgr[idx + 2]);
+                        u_sum += u as u32;
+                        v_sum += v as u32;
+                    }
+                    let uv = by * w + bx * 2;
+                    uv_plane[uv] = ((u_sum + 2) / 4) as u8;
+                    uv_plane[uv + 1] = ((v_sum + 2) / 4) as u8;
+                }
+            }
+        }
+        FourCC::BA81 | FourCC::GBRG | FourCC::GRBG | FourCC::RGGB => {
+            let cfa = bayer_pattern(fourcc);
+            out.reserve(w * h);
+            for y in 0..h {
+                for x in 0..w {
+                    let channel = cfa[y % 2][x % 2];
+                    out.push(bgr[(y * w + x) * 3 + channel]);
+                }
+            }
+        }
+        FourCC::MJPEG => {
+            // JPEG 编码器只接受 RGB 顺序
+            let rgb: Vec<u8> = bgr[..w * h * 3]
+                .chunks_exact(3)
+                .flat_map(|px| [px[2], px[1], px[0]])
+                .collect();
+            JpegEncoder::new_with_quality(&mut *out, MJPEG_QUALITY)
+                .encode(&rgb, width, height, image::ColorType::Rgb8)
+                .map_err(|e| CameraError::SimulationError(format!("MJPEG encode: {}", e)))?;
+        }
+        other => {
+            return Err(CameraError::SimulationError(format!(
+                "Unsupported simulated format: {}",
+                other
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// Bayer 滤色阵列：`[行 % 2][列 % 2]` -> BGR 通道下标 (B=0, G=1, R=2)
+fn bayer_pattern(fourcc: FourCC) -> [[usize; 2]; 2] {
+    match fourcc {
+        FourCC::GBRG => [[1, 0], [2, 1]],
+        FourCC::GRBG => [[1, 2], [0, 1]],
+        FourCC::RGGB => [[2, 1], [1, 0]],
+        // BA81 = BGGR
+        _ => [[0, 1], [1, 2]],
+    }
+}
+
+/// BT.601 有限范围 BGR -> YUV
+#[inline(always)]
+pub(crate) fn bgr_to_yuv(b: u8, g: u8, r: u8) -> (u8, u8, u8) {
+    let (b, g, r) = (b as i32, g as i32, r as i32);
+    let y = ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16;
+    let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+    let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+    (
+        y.clamp(0, 255) as u8,
+        u.clamp(0, 255) as u8,
+        v.clamp(0, 255) as u8,
+    )
+}
+
+#[inline(always)]
+fn avg2(a: u8, b: u8) -> u8 {
+    (a as u16 + b as u16).div_ceil(2) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuyv_white_and_black() {
+        let bgr = [255u8, 255, 255, 0, 0, 0];
+        let mut out = Vec::new();
+        encode_bgr(FourCC::YUYV, &bgr, 2, 1, &mut out).unwrap();
+        // Y 取值为有限范围的两端，色度为中性
+        assert_eq!(out, vec![235, 128, 16, 128]);
+    }
+
+    #[test]
+    fn bayer_bggr_picks_channels() {
+        // 2x2 纯色 B=10, G=20, R=30
+        let bgr = [10u8, 20, 30].repeat(4);
+        let mut out = Vec::new();
+        encode_bgr(FourCC::BA81, &bgr, 2, 2, &mut out).unwrap();
+        assert_eq!(out, vec![10, 20, 20, 30]);
+        encode_bgr(FourCC::RGGB, &bgr, 2, 2, &mut out).unwrap();
+        assert_eq!(out, vec![30, 20, 20, 10]);
+    }
+
+    #[test]
+    fn nv12_plane_sizes() {
+        let bgr = vec![128u8; 4 * 2 * 3];
+        let mut out = Vec::new();
+        encode_bgr(FourCC::NV12, &bgr, 4, 2, &mut out).unwrap();
+        assert_eq!(out.len(), 4 * 2 + 4);
+        // 灰色的色度为中性
+        assert_eq!(&out[8..], &[128, 128, 128, 128]);
+    }
+
+    #[test]
+    fn mjpeg_has_soi_marker() {
+        let bgr = vec![64u8; 16 * 16 * 3];
+        let mut out = Vec::new();
+        encode_bgr(FourCC::MJPEG, &bgr, 16, 16, &mut out).unwrap();
+        assert_eq!(&out[..2], &[0xFF, 0xD8]);
+    }
+}
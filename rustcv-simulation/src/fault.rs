@@ -0,0 +1,337 @@
+//! 故障注入
+//!
+//! 真实硬件不会按需出错，这里用脚本描述一组确定性的故障，
+//! 让 `VideoCapture::read` 等上层错误处理路径可以在 CI 中复现。
+//!
+//! 帧级故障按合成帧序号匹配 (从 `start()` 起为 0)，注入帧不受影响。
+
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::pattern::XorShift64;
+
+/// 帧选择器：描述故障作用在哪些序号上
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameSelector {
+    /// 单个序号
+    At(u64),
+    /// 每 N 帧一次 (第 N、2N、3N... 帧，即序号 N-1、2N-1...)
+    Every(u64),
+    /// 半开区间 [start, end)
+    Range(Range<u64>),
+}
+
+impl FrameSelector {
+    pub fn matches(&self, sequence: u64) -> bool {
+        match self {
+            Self::At(s) => sequence == *s,
+            Self::Every(n) => *n > 0 && (sequence + 1).is_multiple_of(*n),
+            Self::Range(r) => r.contains(&sequence),
+        }
+    }
+}
+
+/// 故障脚本
+#[derive(Debug, Clone, Default)]
+pub struct FaultPlan {
+    pub(crate) busy_on_open: u32,
+    pub(crate) disconnect_at: Option<u64>,
+    pub(crate) drops: Vec<FrameSelector>,
+    pub(crate) truncations: Vec<(FrameSelector, f32)>,
+    pub(crate) corruptions: Vec<FrameSelector>,
+    pub(crate) stalls: Vec<(FrameSelector, Duration)>,
+    pub(crate) jitter_ns: u64,
+    pub(crate) arrival_jitter_ns: u64,
+    pub(crate) drift_ppm: f64,
+    pub(crate) seed: u64,
+}
+
+impl FaultPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 前 `times` 次 `Driver::open` 返回 `CameraError::DeviceBusy`
+    pub fn busy_on_open(mut self, times: u32) -> Self {
+        self.busy_on_open = times;
+        self
+    }
+
+    /// 输出到该序号时设备断开，此后 `next_frame` 一直返回 `CameraError::Disconnected`
+    pub fn disconnect_at(mut self, sequence: u64) -> Self {
+        self.disconnect_at = Some(sequence);
+        self
+    }
+
+    /// 丢弃匹配的帧：序号照常递增，下游会看到序号间隔
+    ///
+    /// 连续丢弃满 1 秒的帧时 `next_frame` 返回 `CameraError::SimulationError`，而不是一直等下去。
+    pub fn drop_frames(mut self, selector: FrameSelector) -> Self {
+        self.drops.push(selector);
+        self
+    }
+
+    /// 截断匹配帧的负载，只保留前 `keep_fraction` (0.0 - 1.0) 的字节
+    /// 对 MJPEG 而言会丢失 EOI 标记与部分熵编码数据
+    pub fn truncate(mut self, selector: FrameSelector, keep_fraction: f32) -> Self {
+        self.truncations
+            .push((selector, keep_fraction.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// 用确定性噪声覆盖匹配帧负载的后半部分
+    /// 对 MJPEG 而言 SOI 与头部保留，熵编码数据被破坏
+    pub fn corrupt(mut self, selector: FrameSelector) -> Self {
+        self.corruptions.push(selector);
+        self
+    }
+
+    /// 在输出匹配帧之前额外阻塞 `duration`，用于触发调用方的超时
+    pub fn stall(mut self, selector: FrameSelector, duration: Duration) -> Self {
+        self.stalls.push((selector, duration));
+        self
+    }
+
+    /// 硬件时间戳叠加 [-max_ns, +max_ns] 的均匀抖动
+    pub fn timestamp_jitter(mut self, max_ns: u64) -> Self {
+        self.jitter_ns = max_ns;
+        self
+    }
+
+    /// 到达时间叠加 [0, max_ns] 的随机传输延迟 (模拟 USB 调度抖动)
+    pub fn arrival_jitter(mut self, max_ns: u64) -> Self {
+        self.arrival_jitter_ns = max_ns;
+        self
+    }
+
+    /// 硬件时钟相对标称频率的漂移 (ppm，正值表示走快)
+    pub fn clock_drift_ppm(mut self, ppm: f64) -> Self {
+        self.drift_ppm = ppm;
+        self
+    }
+
+    /// 抖动与数据损坏使用的随机种子
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub(crate) fn is_dropped(&self, sequence: u64) -> bool {
+        self.drops.iter().any(|s| s.matches(sequence))
+    }
+
+    pub(crate) fn stall_for(&self, sequence: u64) -> Duration {
+        self.stalls
+            .iter()
+            .filter(|(s, _)| s.matches(sequence))
+            .map(|(_, d)| *d)
+            .sum()
+    }
+
+    /// 该帧的负载是否需要改写
+    pub(crate) fn touches_payload(&self, sequence: u64) -> bool {
+        self.truncations.iter().any(|(s, _)| s.matches(sequence))
+            || self.corruptions.iter().any(|s| s.matches(sequence))
+    }
+
+    /// 对负载施加截断与损坏 (原地修改)
+    pub(crate) fn apply_payload(&self, sequence: u64, payload: &mut Vec<u8>) {
+        if self.corruptions.iter().any(|s| s.matches(sequence)) {
+            let mut rng = self.rng(sequence);
+            let start = payload.len() / 2;
+            for b in &mut payload[start..] {
+                *b = rng.next_u64() as u8;
+            }
+        }
+
+        for (selector, keep) in &self.truncations {
+            if selector.matches(sequence) {
+                let len = (payload.len() as f32 * keep) as usize;
+                payload.truncate(len);
+            }
+        }
+    }
+
+    /// 对标称的硬件时间戳施加漂移与抖动
+    pub(crate) fn apply_timestamp(&self, sequence: u64, nominal_ns: u64) -> u64 {
+        let mut ns = nominal_ns as f64 * (1.0 + self.drift_ppm / 1e6);
+        if self.jitter_ns > 0 {
+            let span = self.jitter_ns * 2 + 1;
+            let offset = (self.rng(sequence).next_u64() % span) as f64 - self.jitter_ns as f64;
+            ns += offset;
+        }
+        ns.max(0.0) as u64
+    }
+
+    /// 对到达时间施加传输延迟抖动
+    pub(crate) fn apply_arrival(&self, sequence: u64, arrival: Duration) -> Duration {
+        if self.arrival_jitter_ns == 0 {
+            return arrival;
+        }
+        // 与时间戳抖动使用不同的随机序列
+        let r = self.rng(!sequence).next_u64() % (self.arrival_jitter_ns + 1);
+        arrival + Duration::from_nanos(r)
+    }
+
+    /// 每帧独立的随机序列，结果只取决于种子和序号
+    fn rng(&self, sequence: u64) -> XorShift64 {
+        XorShift64::new(self.seed ^ sequence.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SimDevice, SimDriver};
+    use rustcv_core::builder::{CameraConfig, Priority};
+    use rustcv_core::error::CameraError;
+    use rustcv_core::pixel_format::FourCC;
+    use rustcv_core::traits::{Driver, Stream};
+
+    fn open_with(format: FourCC, plan: FaultPlan) -> Box<dyn Stream> {
+        let device = SimDevice::new("faulty", "Faulty")
+            .mode(format, 64, 48, 30)
+            .faults(plan);
+        let driver = SimDriver::with_devices(vec![device]);
+        driver.open("faulty", CameraConfig::new()).unwrap().0
+    }
+
+    #[test]
+    fn selectors() {
+        assert!(FrameSelector::At(3).matches(3));
+        assert!(!FrameSelector::At(3).matches(4));
+
+        let every = FrameSelector::Every(5);
+        let hits: Vec<u64> = (0..15).filter(|s| every.matches(*s)).collect();
+        assert_eq!(hits, vec![4, 9, 14]);
+        assert!(!FrameSelector::Every(0).matches(0));
+
+        assert!(FrameSelector::Range(2..4).matches(3));
+        assert!(!FrameSelector::Range(2..4).matches(4));
+    }
+
+    #[test]
+    fn timestamp_drift_and_jitter_are_bounded() {
+        let plan = FaultPlan::new().clock_drift_ppm(100.0);
+        assert_eq!(plan.apply_timestamp(0, 1_000_000_000), 1_000_100_000);
+
+        let plan = FaultPlan::new().timestamp_jitter(500).seed(7);
+        for seq in 0..100 {
+            let ns = plan.apply_timestamp(seq, 1_000_000);
+            assert!((999_500..=1_000_500).contains(&ns));
+            // 同一序号结果固定
+            assert_eq!(ns, plan.apply_timestamp(seq, 1_000_000));
+        }
+    }
+
+    #[test]
+    fn busy_on_open_then_recovers() {
+        let device = SimDevice::standard(0).faults(FaultPlan::new().busy_on_open(2));
+        let driver = SimDriver::with_devices(vec![device]);
+        let config = CameraConfig::new().format(FourCC::YUYV, Priority::Required);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                driver.open("sim0", config.clone()),
+                Err(CameraError::DeviceBusy)
+            ));
+        }
+        assert!(driver.open("sim0", config).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropped_frames_leave_sequence_gaps() {
+        let plan = FaultPlan::new()
+            .drop_frames(FrameSelector::At(1))
+            .drop_frames(FrameSelector::Range(4..6));
+        let mut stream = open_with(FourCC::BGR3, plan);
+        stream.start().await.unwrap();
+
+        let mut seqs = vec![];
+        for _ in 0..4 {
+            seqs.push(stream.next_frame().await.unwrap().sequence);
+        }
+        assert_eq!(seqs, vec![0, 2, 3, 6]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropping_every_frame_does_not_hang() {
+        let mut stream = open_with(
+            FourCC::YUYV,
+            FaultPlan::new().drop_frames(FrameSelector::Every(1)),
+        );
+        stream.start().await.unwrap();
+        for _ in 0..3 {
+            assert!(matches!(
+                stream.next_frame().await,
+                Err(CameraError::SimulationError(_))
+            ));
+        }
+
+        // 30 fps：前 30 帧丢弃后报错，下一次调用跳过剩余的 15 帧
+        let mut stream = open_with(
+            FourCC::YUYV,
+            FaultPlan::new().drop_frames(FrameSelector::Range(0..45)),
+        );
+        stream.start().await.unwrap();
+        assert!(stream.next_frame().await.is_err());
+        assert_eq!(stream.next_frame().await.unwrap().sequence, 45);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnect_mid_stream() {
+        let mut stream = open_with(FourCC::YUYV, FaultPlan::new().disconnect_at(2));
+        stream.start().await.unwrap();
+        stream.next_frame().await.unwrap();
+        stream.next_frame().await.unwrap();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                stream.next_frame().await,
+                Err(CameraError::Disconnected(_))
+            ));
+        }
+        assert!(matches!(
+            stream.start().await,
+            Err(CameraError::Disconnected(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn truncated_and_corrupt_mjpeg() {
+        let plan = FaultPlan::new()
+            .truncate(FrameSelector::At(1), 0.5)
+            .corrupt(FrameSelector::At(2))
+            .seed(3);
+        let mut stream = open_with(FourCC::MJPEG, plan);
+        stream.start().await.unwrap();
+
+        let intact = stream.next_frame().await.unwrap().data.to_vec();
+        assert_eq!(&intact[intact.len() - 2..], &[0xFF, 0xD9]);
+
+        let truncated = stream.next_frame().await.unwrap().data.to_vec();
+        assert_eq!(truncated.len(), intact.len() / 2);
+        assert_eq!(&truncated[..], &intact[..truncated.len()]);
+
+        let corrupt = stream.next_frame().await.unwrap().data.to_vec();
+        assert_eq!(corrupt.len(), intact.len());
+        assert_eq!(&corrupt[..2], &[0xFF, 0xD8]);
+        assert_ne!(corrupt, intact);
+
+        // 静态图案的缓存没有被污染
+        assert_eq!(stream.next_frame().await.unwrap().data, &intact[..]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stall_exceeds_caller_timeout() {
+        let plan = FaultPlan::new().stall(FrameSelector::At(1), Duration::from_secs(2));
+        let mut stream = open_with(FourCC::YUYV, plan);
+        stream.start().await.unwrap();
+        stream.next_frame().await.unwrap();
+
+        let timeout = Duration::from_millis(500);
+        assert!(tokio::time::timeout(timeout, stream.next_frame())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rustcv_core::frame::{BackendBufferHandle, Frame, FrameMetadata, Timestamp};
+use rustcv_core::pixel_format::PixelFormat;
+
+/// 注入帧的自有副本
+/// `Frame<'a>` 借用的是调用方的缓冲区，入队时必须拷贝数据
+#[derive(Debug, Clone)]
+pub struct InjectedFrame {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub stride: usize,
+    pub format: PixelFormat,
+    pub sequence: u64,
+    pub timestamp: Timestamp,
+    pub metadata: FrameMetadata,
+}
+
+impl InjectedFrame {
+    /// 空帧 (用作读取缓冲区)
+    pub(crate) fn empty() -> Self {
+        Self {
+            data: Vec::new(),
+            width: 0,
+            height: 0,
+            stride: 0,
+            format: PixelFormat::Unknown(0),
+            sequence: 0,
+            timestamp: Timestamp {
+                hw_raw_ns: 0,
+                system_synced: Duration::ZERO,
+            },
+            metadata: FrameMetadata::default(),
+        }
+    }
+
+    /// 借用为 `Frame`
+    pub fn as_frame<'a>(&'a self, handle: &'a dyn BackendBufferHandle) -> Frame<'a> {
+        Frame {
+            data: &self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            format: self.format,
+            sequence: self.sequence,
+            timestamp: self.timestamp,
+            metadata: self.metadata.clone(),
+            backend_handle: handle,
+        }
+    }
+
+    /// 拷贝一帧 (保留时间戳与元数据)
+    pub fn from_frame(frame: &Frame<'_>) -> Self {
+        Self {
+            data: frame.data.to_vec(),
+            width: frame.width,
+            height: frame.height,
+            stride: frame.stride,
+            format: frame.format,
+            sequence: frame.sequence,
+            timestamp: frame.timestamp,
+            metadata: frame.metadata.clone(),
+        }
+    }
+}
+
+/// 注入队列句柄
+///
+/// 与设备共享同一个队列：即使 Stream 已经被移交给 `VideoCapture` 的后台任务，
+/// 测试代码仍可以通过它继续喂帧。
+#[derive(Debug, Clone, Default)]
+pub struct FrameInjector {
+    queue: Arc<Mutex<VecDeque<InjectedFrame>>>,
+}
+
+impl FrameInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 拷贝并入队一帧
+    pub fn push(&self, frame: &Frame<'_>) {
+        self.push_owned(InjectedFrame::from_frame(frame));
+    }
+
+    /// 入队一帧自有数据
+    pub fn push_owned(&self, frame: InjectedFrame) {
+        self.lock().push_back(frame);
+    }
+
+    /// 队列中尚未被取走的帧数
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// 清空队列
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    pub(crate) fn pop(&self) -> Option<InjectedFrame> {
+        self.lock().pop_front()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<InjectedFrame>> {
+        // 队列操作不会在持锁时 panic，中毒时直接取回数据即可
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,14 +1,239 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+//! 仿真相机后端
+//!
+//! 实现 `rustcv_core::traits::Driver`，无需任何硬件即可产出合成帧，
+//! 用于 CI 与上层管线的端到端测试。
+//! `record` / `replay` 模块把真实相机交付的帧录制下来，再以同样的 `Driver` 接口回放；
+//! `y4m` 模块与外部工具交换无压缩视频。
+//!
+//! ```no_run
+//! use rustcv_core::prelude::*;
+//! use rustcv_core::pixel_format::FourCC;
+//! use rustcv_simulation::{SimDevice, SimDriver, TestPattern};
+//!
+//! # async fn demo() -> rustcv_core::error::Result<()> {
+//! let driver = SimDriver::new()
+//!     .add_device(SimDevice::standard(1).pattern(TestPattern::Checkerboard(32)));
+//!
+//! let config = CameraConfig::new()
+//!     .resolution(640, 480, Priority::High)
+//!     .format(FourCC::YUYV, Priority::Required);
+//! let (mut stream, _controls) = driver.open("sim1", config)?;
+//!
+//! stream.start().await?;
+//! let frame = stream.next_frame().await?;
+//! assert_eq!(frame.data.len(), 640 * 480 * 2);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod controls;
+pub mod device;
+pub mod encode;
+pub mod fault;
+pub mod inject;
+pub mod pattern;
+pub mod record;
+pub mod replay;
+pub mod sensor;
+pub mod stream;
+pub mod y4m;
+
+pub use device::{NegotiatedMode, SimDevice, SimMode};
+pub use fault::{FaultPlan, FrameSelector};
+pub use inject::{FrameInjector, InjectedFrame};
+pub use pattern::TestPattern;
+pub use record::{Recorder, RecordingReader};
+pub use replay::{ReplayDriver, ReplayPace, ReplayStream};
+pub use stream::SimStream;
+pub use y4m::{Y4mColorspace, Y4mHeader, Y4mInterlace, Y4mReader, Y4mStream, Y4mWriter};
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use rustcv_core::builder::CameraConfig;
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::traits::{DeviceControls, DeviceInfo, Driver, Stream};
+
+use crate::controls::{create_controls, ControlState};
+
+/// 仿真驱动
+/// 持有一组可配置的虚拟设备
+#[derive(Debug, Clone)]
+pub struct SimDriver {
+    devices: Vec<SimDevice>,
+}
+
+impl Default for SimDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimDriver {
+    /// 创建驱动，默认挂载一个标准虚拟摄像头 ("sim0")
+    pub fn new() -> Self {
+        Self {
+            devices: vec![SimDevice::standard(0)],
+        }
+    }
+
+    /// 使用指定的设备列表创建驱动
+    pub fn with_devices(devices: Vec<SimDevice>) -> Self {
+        Self { devices }
+    }
+
+    /// 追加一个虚拟设备 (ID 重复时替换旧设备)
+    pub fn add_device(mut self, device: SimDevice) -> Self {
+        self.devices.retain(|d| d.id != device.id);
+        self.devices.push(device);
+        self
+    }
+
+    /// 获取指定设备的注入队列句柄
+    pub fn injector(&self, id: &str) -> Result<FrameInjector> {
+        self.device(id).map(SimDevice::injector)
+    }
+
+    fn device(&self, id: &str) -> Result<&SimDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.id == id)
+            .ok_or_else(|| CameraError::SimulationError(format!("No such device: {}", id)))
+    }
+}
+
+impl Driver for SimDriver {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        Ok(self.devices.iter().map(SimDevice::info).collect())
+    }
+
+    fn open(&self, id: &str, config: CameraConfig) -> Result<(Box<dyn Stream>, DeviceControls)> {
+        let device = self.device(id)?;
+
+        let attempt = device.open_attempts.fetch_add(1, Ordering::Relaxed);
+        if attempt < device.faults.busy_on_open {
+            return Err(CameraError::DeviceBusy);
+        }
+        // 没有任何模式的设备只回放注入帧，不参与协商
+        let mode = if device.modes.is_empty() {
+            None
+        } else {
+            Some(device::negotiate(&device.modes, &config)?)
+        };
+
+        match mode {
+            Some(m) => tracing::debug!(
+                target: "rustcv::simulation",
+                "Opened {}: {}x{} {} @ {}fps",
+                id,
+                m.width,
+                m.height,
+                m.format,
+                m.fps
+            ),
+            None => tracing::debug!(target: "rustcv::simulation", "Opened {}: injection only", id),
+        }
+
+        // 数据面与控制面共享同一份参数
+        let state = Arc::new(ControlState::new(device.focus_target));
+        let stream = SimStream::new(device, mode, state.clone());
+        let controls = create_controls(state);
+
+        Ok((Box::new(stream), controls))
+    }
+}
+
+// 为了方便直接使用，提供一个默认实例
+pub fn default_driver() -> Arc<dyn Driver> {
+    Arc::new(SimDriver::new())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rustcv_core::builder::Priority;
+    use rustcv_core::pixel_format::FourCC;
+    use rustcv_core::time::VirtualClock;
+    use std::time::Duration;
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn lists_configured_devices() {
+        let driver = SimDriver::new().add_device(SimDevice::new("cam-a", "A"));
+        let ids: Vec<_> = driver
+            .list_devices()
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(ids, vec!["sim0", "cam-a"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn streams_paced_frames() {
+        let driver = SimDriver::new();
+        let config = CameraConfig::new()
+            .resolution(640, 480, Priority::Required)
+            .format(FourCC::NV12, Priority::Required)
+            .fps(20, Priority::High);
+        let (mut stream, _) = driver.open("sim0", config).unwrap();
+
+        assert!(stream.next_frame().await.is_err());
+        stream.start().await.unwrap();
+
+        let begin = tokio::time::Instant::now();
+        for expected in 0..3u64 {
+            let frame = stream.next_frame().await.unwrap();
+            assert_eq!(frame.sequence, expected);
+            assert_eq!(frame.format, FourCC::NV12);
+            assert_eq!(frame.stride, 640);
+            assert_eq!(frame.data.len(), 640 * 480 * 3 / 2);
+            assert_eq!(frame.timestamp.hw_raw_ns, expected * 50_000_000);
+        }
+        // 第 0 帧立即返回，之后每帧间隔 50ms
+        assert_eq!(begin.elapsed(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn unknown_device_is_an_error() {
+        let driver = SimDriver::new();
+        assert!(driver.open("sim9", CameraConfig::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn virtual_clock_drives_synced_timestamps() {
+        // 硬件时钟快 150ppm，到达时间叠加 0-2ms 的传输延迟
+        let start = Duration::from_secs(100);
+        let clock = VirtualClock::starting_at(start);
+        let device = SimDevice::new("vc", "Virtual clock")
+            .mode(FourCC::BGR3, 16, 16, 30)
+            .realtime(false)
+            .time_source(Arc::new(clock.clone()))
+            .faults(
+                FaultPlan::new()
+                    .clock_drift_ppm(150.0)
+                    .arrival_jitter(2_000_000),
+            );
+        let driver = SimDriver::with_devices(vec![device]);
+        let (mut stream, _) = driver.open("vc", CameraConfig::new()).unwrap();
+        stream.start().await.unwrap();
+
+        let interval_ns = 1_000_000_000 / 30;
+        for i in 0..90u64 {
+            let true_ns = i * interval_ns;
+            clock.set(start + Duration::from_nanos(true_ns));
+            let frame = stream.next_frame().await.unwrap();
+            assert_eq!(
+                frame.timestamp.hw_raw_ns,
+                true_ns + true_ns * 150 / 1_000_000
+            );
+
+            if i >= 30 {
+                // 平均传输延迟 1ms
+                let expected =
+                    (start + Duration::from_nanos(true_ns + 1_000_000)).as_nanos() as i64;
+                let err = frame.timestamp.system_synced.as_nanos() as i64 - expected;
+                assert!(err.abs() < 1_000_000, "frame {}: {}ns", i, err);
+            }
+        }
     }
 }
@@ -0,0 +1,143 @@
+/// 合成测试图案
+///
+/// 所有图案先渲染为紧凑排列的 BGR24 (OpenCV 默认通道顺序)，
+/// 再由 `encode` 模块转换为设备协商出的像素格式。
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TestPattern {
+    /// 8 条 100% 彩条 (白、黄、青、绿、品红、红、蓝、黑)
+    #[default]
+    ColorBars,
+    /// 随帧号水平滚动的渐变，用于检查丢帧和撕裂
+    MovingGradient,
+    /// 黑白棋盘格，参数为格子边长 (像素)
+    Checkerboard(u32),
+    /// 确定性伪随机噪声，参数为种子 (同一种子 + 帧号 => 同一帧)
+    Noise(u64),
+    /// 纯色 [B, G, R]
+    Solid([u8; 3]),
+}
+
+impl TestPattern {
+    /// 图案是否与帧号无关 (静态图案只需渲染一次)
+    pub fn is_static(&self) -> bool {
+        !matches!(self, Self::MovingGradient | Self::Noise(_))
+    }
+
+    /// 渲染第 `frame_index` 帧到 `dst` (BGR24, 长度 >= width * height * 3)
+    pub fn render_bgr(&self, dst: &mut [u8], width: u32, height: u32, frame_index: u64) {
+        let (w, h) = (width as usize, height as usize);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let dst = &mut dst[..w * h * 3];
+
+        match *self {
+            Self::ColorBars => {
+                // BGR 顺序
+                const BARS: [[u8; 3]; 8] = [
+                    [255, 255, 255], // 白
+                    [0, 255, 255],   // 黄
+                    [255, 255, 0],   // 青
+                    [0, 255, 0],     // 绿
+                    [255, 0, 255],   // 品红
+                    [0, 0, 255],     // 红
+                    [255, 0, 0],     // 蓝
+                    [0, 0, 0],       // 黑
+                ];
+                for row in dst.chunks_exact_mut(w * 3) {
+                    for (x, px) in row.chunks_exact_mut(3).enumerate() {
+                        px.copy_from_slice(&BARS[x * BARS.len() / w]);
+                    }
+                }
+            }
+            Self::MovingGradient => {
+                // 每帧向右滚动 4 像素
+                let shift = (frame_index as usize).wrapping_mul(4);
+                for (y, row) in dst.chunks_exact_mut(w * 3).enumerate() {
+                    let g = (y * 255 / (h - 1).max(1)) as u8;
+                    for (x, px) in row.chunks_exact_mut(3).enumerate() {
+                        let b = (((x + shift) % w) * 256 / w) as u8;
+                        px[0] = b;
+                        px[1] = g;
+                        px[2] = 255 - b;
+                    }
+                }
+            }
+            Self::Checkerboard(size) => {
+                let size = size.max(1) as usize;
+                for (y, row) in dst.chunks_exact_mut(w * 3).enumerate() {
+                    for (x, px) in row.chunks_exact_mut(3).enumerate() {
+                        let v = if ((x / size) + (y / size)).is_multiple_of(2) {
+                            255
+                        } else {
+                            0
+                        };
+                        px.fill(v);
+                    }
+                }
+            }
+            Self::Noise(seed) => {
+                let mut rng =
+                    XorShift64::new(seed ^ frame_index.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                for chunk in dst.chunks_mut(8) {
+                    let bytes = rng.next_u64().to_le_bytes();
+                    chunk.copy_from_slice(&bytes[..chunk.len()]);
+                }
+            }
+            Self::Solid(bgr) => {
+                for px in dst.chunks_exact_mut(3) {
+                    px.copy_from_slice(&bgr);
+                }
+            }
+        }
+    }
+}
+
+/// 极简 xorshift64 伪随机数发生器
+/// 只求确定性与速度，不用于任何安全场景
+#[derive(Debug, Clone)]
+pub(crate) struct XorShift64(u64);
+
+impl XorShift64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        // 状态不能为 0，否则序列恒为 0
+        Self(seed.max(1))
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_bars_layout() {
+        let mut buf = vec![0u8; 16 * 2 * 3];
+        TestPattern::ColorBars.render_bgr(&mut buf, 16, 2, 0);
+        // 第一条白色，最后一条黑色
+        assert_eq!(&buf[0..3], &[255, 255, 255]);
+        assert_eq!(&buf[15 * 3..16 * 3], &[0, 0, 0]);
+        // 第 6 条 (x = 10) 为红色 BGR(0, 0, 255)
+        assert_eq!(&buf[10 * 3..11 * 3], &[0, 0, 255]);
+    }
+
+    #[test]
+    fn noise_is_deterministic() {
+        let mut a = vec![0u8; 8 * 8 * 3];
+        let mut b = vec![0u8; 8 * 8 * 3];
+        TestPattern::Noise(42).render_bgr(&mut a, 8, 8, 3);
+        TestPattern::Noise(42).render_bgr(&mut b, 8, 8, 3);
+        assert_eq!(a, b);
+
+        TestPattern::Noise(42).render_bgr(&mut b, 8, 8, 4);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,415 @@
+//! 原始帧录制
+//!
+//! 把相机实际交付的每一帧 (原始字节 + 格式 + 时间戳 + 元数据) 追加写入一个紧凑的二进制文件，
+//! 供 `replay` 模块离线回放，用来复现现场问题。
+//!
+//! 文件格式 (全部小端)：
+//!
+//! ```text
+//! 文件头  : b"RCVR" | u16 版本 (1) | u16 保留
+//! 帧记录  : b"FRAM"
+//!           u32 格式类型 (0 = Known, 1 = Unknown) | u32 FourCC / 私有格式代码
+//!           u32 width | u32 height | u64 stride
+//!           u64 sequence | u64 hw_raw_ns | u64 system_synced (纳秒)
+//!           u8  标志位 (bit0 有曝光, bit1 有增益, bit2 trigger_fired, bit3 strobe_active)
+//!           u32 actual_exposure_us | f32 actual_gain_db
+//!           u32 数据长度 | 数据
+//! ```
+//!
+//! 文件只追加不修改。进程崩溃留下的半条记录在读取时被忽略，`Recorder::append` 会先截掉它。
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::frame::{Frame, FrameMetadata, Timestamp};
+use rustcv_core::pixel_format::{FourCC, PixelFormat};
+
+use crate::inject::InjectedFrame;
+
+const FILE_MAGIC: &[u8; 4] = b"RCVR";
+const FRAME_MAGIC: &[u8; 4] = b"FRAM";
+const VERSION: u16 = 1;
+const HEADER_LEN: u64 = 8;
+/// 帧记录中数据之前的固定部分
+const RECORD_HEAD_LEN: usize = 4 + 4 + 4 + 4 + 4 + 8 + 8 + 8 + 8 + 1 + 4 + 4 + 4;
+
+const FLAG_EXPOSURE: u8 = 1 << 0;
+const FLAG_GAIN: u8 = 1 << 1;
+const FLAG_TRIGGER: u8 = 1 << 2;
+const FLAG_STROBE: u8 = 1 << 3;
+
+fn invalid(msg: &str) -> CameraError {
+    CameraError::Io(io::Error::new(io::ErrorKind::InvalidData, msg.to_string()))
+}
+
+/// 录制器
+#[derive(Debug)]
+pub struct Recorder<W: Write> {
+    out: W,
+    frames: u64,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// 新建录制文件 (已存在则覆盖)
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// 追加到已有的录制文件 (不存在则新建)
+    /// 末尾不完整的记录会被截掉
+    pub fn append(path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            return Self::new(BufWriter::new(file));
+        }
+
+        let mut reader = RecordingReader::new(BufReader::new(&mut file))?;
+        let mut frame = InjectedFrame::empty();
+        let mut frames = 0;
+        while reader.read_into(&mut frame)? {
+            frames += 1;
+        }
+        let end = reader.position();
+
+        file.set_len(end)?;
+        file.seek(SeekFrom::Start(end))?;
+        Ok(Self {
+            out: BufWriter::new(file),
+            frames,
+        })
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// 写入文件头
+    pub fn new(mut out: W) -> Result<Self> {
+        out.write_all(FILE_MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        Ok(Self { out, frames: 0 })
+    }
+
+    /// 追加一帧
+    pub fn record(&mut self, frame: &Frame<'_>) -> Result<()> {
+        let (kind, code) = match frame.format {
+            PixelFormat::Known(cc) => (0u32, cc.0),
+            PixelFormat::Unknown(code) => (1u32, code),
+        };
+        let meta = &frame.metadata;
+        let mut flags = 0u8;
+        if meta.actual_exposure_us.is_some() {
+            flags |= FLAG_EXPOSURE;
+        }
+        if meta.actual_gain_db.is_some() {
+            flags |= FLAG_GAIN;
+        }
+        if meta.trigger_fired {
+            flags |= FLAG_TRIGGER;
+        }
+        if meta.strobe_active {
+            flags |= FLAG_STROBE;
+        }
+        let len = u32::try_from(frame.data.len()).map_err(|_| invalid("Frame too large"))?;
+
+        let mut head = Vec::with_capacity(RECORD_HEAD_LEN);
+        head.extend_from_slice(FRAME_MAGIC);
+        head.extend_from_slice(&kind.to_le_bytes());
+        head.extend_from_slice(&code.to_le_bytes());
+        head.extend_from_slice(&frame.width.to_le_bytes());
+        head.extend_from_slice(&frame.height.to_le_bytes());
+        head.extend_from_slice(&(frame.stride as u64).to_le_bytes());
+        head.extend_from_slice(&frame.sequence.to_le_bytes());
+        head.extend_from_slice(&frame.timestamp.hw_raw_ns.to_le_bytes());
+        head.extend_from_slice(&(frame.timestamp.system_synced.as_nanos() as u64).to_le_bytes());
+        head.push(flags);
+        head.extend_from_slice(&meta.actual_exposure_us.unwrap_or(0).to_le_bytes());
+        head.extend_from_slice(&meta.actual_gain_db.unwrap_or(0.0).to_le_bytes());
+        head.extend_from_slice(&len.to_le_bytes());
+
+        self.out.write_all(&head)?;
+        self.out.write_all(frame.data)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// 已写入的帧数 (含追加前已有的帧)
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush().map_err(CameraError::Io)
+    }
+
+    /// 刷新并取回底层写入器
+    pub fn into_inner(mut self) -> Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// 录制文件读取器
+#[derive(Debug)]
+pub struct RecordingReader<R: Read> {
+    input: R,
+    /// 最后一条完整记录之后的字节偏移
+    position: u64,
+}
+
+impl RecordingReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// 校验文件头
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut header = [0u8; HEADER_LEN as usize];
+        input
+            .read_exact(&mut header)
+            .map_err(|_| invalid("Not a RustCV recording"))?;
+        if &header[..4] != FILE_MAGIC {
+            return Err(invalid("Not a RustCV recording"));
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != VERSION {
+            return Err(invalid(&format!(
+                "Unsupported recording version {}",
+                version
+            )));
+        }
+        Ok(Self {
+            input,
+            position: HEADER_LEN,
+        })
+    }
+
+    /// 读取下一帧到 `frame` (复用其数据缓冲区)
+    /// 到达文件末尾或遇到不完整的尾部记录时返回 false
+    pub fn read_into(&mut self, frame: &mut InjectedFrame) -> Result<bool> {
+        let mut head = [0u8; RECORD_HEAD_LEN];
+        if !read_full(&mut self.input, &mut head)? {
+            return Ok(false);
+        }
+        if &head[..4] != FRAME_MAGIC {
+            return Err(invalid("Corrupt recording: bad frame marker"));
+        }
+
+        let mut at = 4;
+        let mut take = |n: usize| {
+            let field = &head[at..at + n];
+            at += n;
+            field
+        };
+        let u32_at = |b: &[u8]| u32::from_le_bytes(b.try_into().unwrap());
+        let u64_at = |b: &[u8]| u64::from_le_bytes(b.try_into().unwrap());
+
+        let kind = u32_at(take(4));
+        let code = u32_at(take(4));
+        let width = u32_at(take(4));
+        let height = u32_at(take(4));
+        let stride = u64_at(take(8)) as usize;
+        let sequence = u64_at(take(8));
+        let hw_raw_ns = u64_at(take(8));
+        let synced_ns = u64_at(take(8));
+        let flags = take(1)[0];
+        let exposure = u32_at(take(4));
+        let gain = f32::from_le_bytes(take(4).try_into().unwrap());
+        let len = u32_at(take(4)) as usize;
+
+        // 按实际读到的数据增长缓冲区：损坏的长度字段最多只会读到文件末尾，
+        // 不会预先分配 4 GiB；长度超出文件剩余部分与崩溃留下的半条记录同样处理
+        frame.data.clear();
+        self.input
+            .by_ref()
+            .take(len as u64)
+            .read_to_end(&mut frame.data)?;
+        if frame.data.len() < len {
+            tracing::warn!(target: "rustcv::simulation", "Recording ends with a truncated frame");
+            return Ok(false);
+        }
+
+        frame.format = match kind {
+            0 => PixelFormat::Known(FourCC(code)),
+            _ => PixelFormat::Unknown(code),
+        };
+        frame.width = width;
+        frame.height = height;
+        frame.stride = stride;
+        frame.sequence = sequence;
+        frame.timestamp = Timestamp {
+            hw_raw_ns,
+            system_synced: Duration::from_nanos(synced_ns),
+        };
+        frame.metadata = FrameMetadata {
+            actual_exposure_us: (flags & FLAG_EXPOSURE != 0).then_some(exposure),
+            actual_gain_db: (flags & FLAG_GAIN != 0).then_some(gain),
+            trigger_fired: flags & FLAG_TRIGGER != 0,
+            strobe_active: flags & FLAG_STROBE != 0,
+        };
+
+        self.position += (RECORD_HEAD_LEN + len) as u64;
+        Ok(true)
+    }
+
+    /// 读取下一帧 (分配新缓冲区)
+    pub fn read_frame(&mut self) -> Result<Option<InjectedFrame>> {
+        let mut frame = InjectedFrame::empty();
+        Ok(self.read_into(&mut frame)?.then_some(frame))
+    }
+
+    /// 已读取的完整记录末尾的字节偏移
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+/// 读满缓冲区；干净的 EOF 或半条记录都返回 false
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => {
+                if filled > 0 {
+                    tracing::warn!(target: "rustcv::simulation", "Recording ends with a truncated frame");
+                }
+                return Ok(false);
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(CameraError::Io(e)),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample(data: &[u8], sequence: u64, format: PixelFormat) -> Frame<'_> {
+        Frame {
+            data,
+            width: 4,
+            height: 2,
+            stride: 8,
+            format,
+            sequence,
+            timestamp: Timestamp {
+                hw_raw_ns: 123_456_789 + sequence,
+                system_synced: Duration::new(17, 5 + sequence as u32),
+            },
+            metadata: FrameMetadata {
+                actual_exposure_us: Some(8_000),
+                actual_gain_db: sequence.is_multiple_of(2).then_some(3.5),
+                trigger_fired: true,
+                strobe_active: sequence == 1,
+            },
+            backend_handle: &(),
+        }
+    }
+
+    #[test]
+    fn round_trip_preserves_every_field() {
+        let payload: Vec<u8> = (0..16).collect();
+        let frames = [
+            sample(&payload, 0, PixelFormat::Known(FourCC::YUYV)),
+            sample(&payload[..5], 1, PixelFormat::Unknown(0xDEAD_BEEF)),
+        ];
+
+        let mut rec = Recorder::new(Vec::new()).unwrap();
+        for f in &frames {
+            rec.record(f).unwrap();
+        }
+        assert_eq!(rec.frames(), 2);
+        let bytes = rec.into_inner().unwrap();
+
+        let mut reader = RecordingReader::new(Cursor::new(bytes)).unwrap();
+        for f in &frames {
+            let got = reader.read_frame().unwrap().unwrap();
+            let want = InjectedFrame::from_frame(f);
+            assert_eq!(got.data, want.data);
+            assert_eq!(got.format, want.format);
+            assert_eq!((got.width, got.height, got.stride), (4, 2, 8));
+            assert_eq!(got.sequence, want.sequence);
+            assert_eq!(got.timestamp.hw_raw_ns, want.timestamp.hw_raw_ns);
+            assert_eq!(got.timestamp.system_synced, want.timestamp.system_synced);
+            assert_eq!(got.metadata.actual_exposure_us, Some(8_000));
+            assert_eq!(got.metadata.actual_gain_db, want.metadata.actual_gain_db);
+            assert_eq!(got.metadata.trigger_fired, want.metadata.trigger_fired);
+            assert_eq!(got.metadata.strobe_active, want.metadata.strobe_active);
+        }
+        assert!(reader.read_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        assert!(RecordingReader::new(Cursor::new(b"RIFF\0\0\0\0".to_vec())).is_err());
+        assert!(RecordingReader::new(Cursor::new(b"RCV".to_vec())).is_err());
+    }
+
+    #[test]
+    fn append_drops_truncated_tail() {
+        let path = std::env::temp_dir().join(format!("rustcv-record-{}.rcvr", std::process::id()));
+        let payload = [7u8; 32];
+
+        let mut rec = Recorder::create(&path).unwrap();
+        rec.record(&sample(&payload, 0, FourCC::NV12.into()))
+            .unwrap();
+        rec.record(&sample(&payload, 1, FourCC::NV12.into()))
+            .unwrap();
+        drop(rec);
+
+        // 模拟写到一半崩溃
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 10)
+            .unwrap();
+
+        let mut reader = RecordingReader::open(&path).unwrap();
+        assert!(reader.read_frame().unwrap().is_some());
+        assert!(reader.read_frame().unwrap().is_none());
+
+        let mut rec = Recorder::append(&path).unwrap();
+        assert_eq!(rec.frames(), 1);
+        rec.record(&sample(&payload, 2, FourCC::NV12.into()))
+            .unwrap();
+        drop(rec);
+
+        let mut reader = RecordingReader::open(&path).unwrap();
+        let seqs: Vec<u64> = std::iter::from_fn(|| reader.read_frame().unwrap())
+            .map(|f| f.sequence)
+            .collect();
+        assert_eq!(seqs, vec![0, 2]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_length_does_not_allocate_it() {
+        let mut rec = Recorder::new(Vec::new()).unwrap();
+        rec.record(&sample(&[1, 2, 3], 0, FourCC::YUYV.into()))
+            .unwrap();
+        let mut bytes = rec.into_inner().unwrap();
+        let at = HEADER_LEN as usize + RECORD_HEAD_LEN - 4;
+        bytes[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let mut reader = RecordingReader::new(Cursor::new(bytes)).unwrap();
+        let mut frame = InjectedFrame::empty();
+        assert!(!reader.read_into(&mut frame).unwrap());
+        assert!(frame.data.capacity() < 1 << 20);
+    }
+}
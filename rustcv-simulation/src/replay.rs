@@ -0,0 +1,373 @@
+//! 录制文件回放
+//!
+//! `ReplayDriver` 把 `record` 模块写出的文件当作虚拟设备，
+//! 逐帧还原原始字节、格式、时间戳与元数据，可按原始节奏或尽可能快地回放。
+//! Y4M 文件也可以挂载为设备，见 `ReplayDriver::add_y4m`。
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant as TokioInstant;
+
+use rustcv_core::builder::CameraConfig;
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::frame::{BackendBufferHandle, Frame};
+use rustcv_core::pixel_format::FourCC;
+use rustcv_core::traits::{
+    DeviceControls, DeviceInfo, Driver, LensControl, SensorControl, Stream, SystemControl,
+    TriggerConfig, TriggerMode,
+};
+
+use crate::inject::InjectedFrame;
+use crate::record::RecordingReader;
+use crate::y4m::Y4mStream;
+
+#[derive(Debug)]
+pub struct ReplayBufferHandle;
+impl BackendBufferHandle for ReplayBufferHandle {}
+
+static REPLAY_HANDLE_INSTANCE: ReplayBufferHandle = ReplayBufferHandle;
+
+/// 回放节奏
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayPace {
+    /// 按录制时 `system_synced` 的间隔出帧
+    #[default]
+    Original,
+    /// 不等待，读到就交付 (离线批处理)
+    AsFastAsPossible,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SourceKind {
+    Recording,
+    /// 输出格式 (`None` 为色彩空间的默认格式)
+    Y4m(Option<FourCC>),
+}
+
+#[derive(Debug, Clone)]
+struct ReplaySource {
+    id: String,
+    path: PathBuf,
+    kind: SourceKind,
+}
+
+/// 回放驱动：每个录制文件对应一个设备
+#[derive(Debug, Clone, Default)]
+pub struct ReplayDriver {
+    sources: Vec<ReplaySource>,
+    pace: ReplayPace,
+}
+
+impl ReplayDriver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 挂载一个录制文件 (ID 重复时替换)
+    pub fn add_recording(self, id: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.add_source(id.into(), path.into(), SourceKind::Recording)
+    }
+
+    /// 挂载一个 Y4M 文件，按 `format` 输出 (`None` 为 YUYV / I420 / GREY)
+    pub fn add_y4m(
+        self,
+        id: impl Into<String>,
+        path: impl Into<PathBuf>,
+        format: Option<FourCC>,
+    ) -> Self {
+        self.add_source(id.into(), path.into(), SourceKind::Y4m(format))
+    }
+
+    fn add_source(mut self, id: String, path: PathBuf, kind: SourceKind) -> Self {
+        self.sources.retain(|s| s.id != id);
+        self.sources.push(ReplaySource { id, path, kind });
+        self
+    }
+
+    /// 设置回放节奏 (对所有设备生效)
+    pub fn pace(mut self, pace: ReplayPace) -> Self {
+        self.pace = pace;
+        self
+    }
+}
+
+impl Driver for ReplayDriver {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        Ok(self
+            .sources
+            .iter()
+            .map(|s| DeviceInfo {
+                name: s
+                    .path
+                    .file_name()
+                    .map_or_else(|| s.id.clone(), |n| n.to_string_lossy().into_owned()),
+                id: s.id.clone(),
+                backend: "Replay".to_string(),
+                bus_info: Some(s.path.display().to_string()),
+            })
+            .collect())
+    }
+
+    /// 录制内容是固定的，`config` 不参与协商
+    fn open(&self, id: &str, _config: CameraConfig) -> Result<(Box<dyn Stream>, DeviceControls)> {
+        let source =
+            self.sources.iter().find(|s| s.id == id).ok_or_else(|| {
+                CameraError::SimulationError(format!("No such recording: {}", id))
+            })?;
+
+        let stream: Box<dyn Stream> = match source.kind {
+            SourceKind::Recording => Box::new(ReplayStream::open(&source.path, self.pace)?),
+            SourceKind::Y4m(format) => Box::new(Y4mStream::open(&source.path, format, self.pace)?),
+        };
+        tracing::debug!(
+            target: "rustcv::simulation",
+            "Replaying {} from {}",
+            id,
+            source.path.display()
+        );
+
+        let controls = DeviceControls {
+            sensor: Box::new(ReplayControls),
+            lens: Box::new(ReplayControls),
+            system: Box::new(ReplayControls),
+        };
+        Ok((stream, controls))
+    }
+}
+
+/// 回放数据流
+///
+/// 帧内容与录制时完全一致 (包括序号间隔和时间戳)。
+/// 读完后 `next_frame` 返回错误，重新 `start()` 从头开始。
+#[derive(Debug)]
+pub struct ReplayStream {
+    path: PathBuf,
+    pace: ReplayPace,
+    reader: RecordingReader<BufReader<File>>,
+    current: InjectedFrame,
+    is_streaming: bool,
+    /// 第一帧交付的时刻与其录制时间
+    origin: Option<(TokioInstant, Duration)>,
+}
+
+impl ReplayStream {
+    /// 打开录制文件 (立即校验文件头)
+    pub fn open(path: impl AsRef<Path>, pace: ReplayPace) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        Ok(Self {
+            reader: RecordingReader::open(&path)?,
+            path,
+            pace,
+            current: InjectedFrame::empty(),
+            is_streaming: false,
+            origin: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Stream for ReplayStream {
+    async fn start(&mut self) -> Result<()> {
+        self.reader = RecordingReader::open(&self.path)?;
+        self.origin = None;
+        self.is_streaming = true;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming = false;
+        Ok(())
+    }
+
+    async fn next_frame(&mut self) -> Result<Frame<'_>> {
+        if !self.is_streaming {
+            return Err(CameraError::SimulationError("Stream not started".into()));
+        }
+        if !self.reader.read_into(&mut self.current)? {
+            return Err(CameraError::SimulationError("End of recording".into()));
+        }
+
+        let recorded = self.current.timestamp.system_synced;
+        match self.origin {
+            None => self.origin = Some((TokioInstant::now(), recorded)),
+            Some((start, first)) if self.pace == ReplayPace::Original => {
+                // 时间戳回退 (例如录制时时钟被校正) 时不等待
+                let offset = recorded.saturating_sub(first);
+                tokio::time::sleep_until(start + offset).await;
+            }
+            Some(_) => {}
+        }
+
+        Ok(self.current.as_frame(&REPLAY_HANDLE_INSTANCE))
+    }
+}
+
+/// 回放没有可调的硬件参数
+struct ReplayControls;
+
+fn read_only() -> CameraError {
+    CameraError::BackendError("Replayed recordings cannot be controlled".into())
+}
+
+impl SensorControl for ReplayControls {
+    fn set_exposure(&self, _value_us: u32) -> Result<()> {
+        Err(read_only())
+    }
+
+    fn get_exposure(&self) -> Result<u32> {
+        Err(read_only())
+    }
+}
+
+impl LensControl for ReplayControls {
+    fn set_zoom(&self, _zoom: u32) -> Result<()> {
+        Err(read_only())
+    }
+
+    fn set_focus(&self, _focus: u32) -> Result<()> {
+        Err(read_only())
+    }
+}
+
+impl SystemControl for ReplayControls {
+    unsafe fn force_reset(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_trigger(&self, config: TriggerConfig) -> Result<()> {
+        if config.mode == TriggerMode::Off {
+            return Ok(());
+        }
+        Err(CameraError::FormatNotSupported)
+    }
+
+    fn export_state(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::json!({ "backend": "replay" }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::Recorder;
+    use crate::{FaultPlan, FrameSelector, SimDevice, SimDriver};
+    use rustcv_core::builder::Priority;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rustcv-{}-{}.rcvr", name, std::process::id()))
+    }
+
+    /// 从仿真相机录制若干帧，返回录制下来的副本用于比对
+    async fn record_sim(path: &Path, count: usize) -> Vec<InjectedFrame> {
+        let device =
+            SimDevice::standard(0).faults(FaultPlan::new().drop_frames(FrameSelector::At(2)));
+        let driver = SimDriver::with_devices(vec![device]);
+        let config = CameraConfig::new()
+            .resolution(640, 480, Priority::Required)
+            .format(FourCC::YUYV, Priority::Required);
+        let (mut stream, _) = driver.open("sim0", config).unwrap();
+        stream.start().await.unwrap();
+
+        let mut rec = Recorder::create(path).unwrap();
+        let mut copies = vec![];
+        for _ in 0..count {
+            let frame = stream.next_frame().await.unwrap();
+            rec.record(&frame).unwrap();
+            copies.push(InjectedFrame::from_frame(&frame));
+        }
+        rec.flush().unwrap();
+        copies
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replays_exactly_at_original_pace() {
+        let path = temp_path("replay-original");
+        let recorded = record_sim(&path, 4).await;
+
+        let driver = ReplayDriver::new().add_recording("field", &path);
+        assert_eq!(driver.list_devices().unwrap()[0].backend, "Replay");
+        let (mut stream, controls) = driver.open("field", CameraConfig::new()).unwrap();
+        assert!(controls.sensor.set_exposure(100).is_err());
+        stream.start().await.unwrap();
+
+        let begin = TokioInstant::now();
+        for want in &recorded {
+            let got = stream.next_frame().await.unwrap();
+            assert_eq!(got.data, &want.data[..]);
+            assert_eq!(got.format, want.format);
+            assert_eq!(got.sequence, want.sequence);
+            assert_eq!(got.timestamp.hw_raw_ns, want.timestamp.hw_raw_ns);
+            assert_eq!(got.timestamp.system_synced, want.timestamp.system_synced);
+        }
+        // 序号 2 被丢弃，录制中的间隔原样保留
+        assert_eq!(recorded[2].sequence, 3);
+        let span = recorded[3].timestamp.system_synced - recorded[0].timestamp.system_synced;
+        // tokio 计时器精度为 1ms
+        assert!(begin.elapsed().abs_diff(span) <= Duration::from_millis(1));
+
+        assert!(stream.next_frame().await.is_err());
+        // 重新 start 从头回放
+        stream.start().await.unwrap();
+        assert_eq!(stream.next_frame().await.unwrap().sequence, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn as_fast_as_possible_does_not_wait() {
+        let path = temp_path("replay-fast");
+        record_sim(&path, 3).await;
+
+        let driver = ReplayDriver::new()
+            .add_recording("field", &path)
+            .pace(ReplayPace::AsFastAsPossible);
+        let (mut stream, _) = driver.open("field", CameraConfig::new()).unwrap();
+        stream.start().await.unwrap();
+
+        let begin = TokioInstant::now();
+        for _ in 0..3 {
+            stream.next_frame().await.unwrap();
+        }
+        assert_eq!(begin.elapsed(), Duration::ZERO);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn y4m_files_mount_as_devices() {
+        use crate::y4m::{Y4mColorspace, Y4mHeader, Y4mWriter};
+
+        let path = temp_path("replay-y4m");
+        let mut writer =
+            Y4mWriter::create(&path, Y4mHeader::new(2, 2, Y4mColorspace::C420Jpeg)).unwrap();
+        let frame = InjectedFrame {
+            data: vec![1, 2, 3, 4, 5, 6],
+            width: 2,
+            height: 2,
+            format: FourCC::I420.into(),
+            ..InjectedFrame::empty()
+        };
+        writer.write_frame(&frame.as_frame(&())).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let driver = ReplayDriver::new()
+            .pace(ReplayPace::AsFastAsPossible)
+            .add_y4m("clip", &path, Some(FourCC::NV12));
+        let (mut stream, _) = driver.open("clip", CameraConfig::new()).unwrap();
+        stream.start().await.unwrap();
+        let got = stream.next_frame().await.unwrap();
+        assert_eq!(got.format, FourCC::NV12);
+        assert_eq!(got.data, [1, 2, 3, 4, 5, 6]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_recording_is_an_error() {
+        let driver = ReplayDriver::new().add_recording("gone", temp_path("does-not-exist"));
+        assert!(driver.open("gone", CameraConfig::new()).is_err());
+        assert!(driver.open("other", CameraConfig::new()).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! 传感器与镜头模型
+//!
+//! 把控制面的参数作用到渲染好的 BGR24 图案上，顺序与真实光路一致：
+//! 变焦裁剪 -> 离焦模糊 -> 曝光增益。
+//! 模型刻意保持简单且确定，方便在没有硬件时测试自动曝光/自动对焦逻辑。
+
+/// 图案原样输出时对应的曝光时间 (微秒)，曝光加倍则亮度加倍 (饱和到 255)
+pub const NOMINAL_EXPOSURE_US: u32 = 10_000;
+
+/// 变焦 100 表示 1.0x，上限 10.0x
+pub const ZOOM_1X: u32 = 100;
+pub const ZOOM_MAX: u32 = 1_000;
+
+/// 对焦值每偏离合焦位置这么多，模糊半径增加 1 像素
+pub const FOCUS_UNITS_PER_PIXEL: u32 = 8;
+pub const MAX_BLUR_RADIUS: u32 = 15;
+
+/// 一帧生效的控制参数快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ControlSnapshot {
+    pub(crate) exposure_us: u32,
+    pub(crate) zoom: u32,
+    /// 当前对焦值与合焦位置的距离
+    pub(crate) defocus: u32,
+}
+
+/// 实际曝光时间：不能超过帧间隔
+pub(crate) fn actual_exposure_us(requested_us: u32, fps: u32) -> u32 {
+    let frame_period_us = 1_000_000 / fps.max(1);
+    requested_us.clamp(1, frame_period_us)
+}
+
+/// 模糊半径 (像素)
+pub(crate) fn blur_radius(defocus: u32) -> u32 {
+    (defocus / FOCUS_UNITS_PER_PIXEL).min(MAX_BLUR_RADIUS)
+}
+
+/// 对 `bgr` (紧凑排列的 BGR24) 原地施加变焦、对焦与曝光
+/// `scratch` 为中间缓冲，长度不足时自动扩容
+pub(crate) fn apply(
+    bgr: &mut [u8],
+    scratch: &mut Vec<u8>,
+    width: u32,
+    height: u32,
+    snapshot: ControlSnapshot,
+    fps: u32,
+) {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 {
+        return;
+    }
+    let bgr = &mut bgr[..w * h * 3];
+    scratch.resize(w * h * 3, 0);
+
+    let zoom = snapshot.zoom.clamp(ZOOM_1X, ZOOM_MAX);
+    if zoom != ZOOM_1X {
+        zoom_center(bgr, scratch, w, h, zoom);
+    }
+
+    let radius = blur_radius(snapshot.defocus) as usize;
+    if radius > 0 {
+        box_blur(bgr, scratch, w, h, radius);
+    }
+
+    let exposure = actual_exposure_us(snapshot.exposure_us, fps);
+    if exposure != NOMINAL_EXPOSURE_US {
+        let lut: [u8; 256] = std::array::from_fn(|v| {
+            let scaled = (v as u64 * exposure as u64 + NOMINAL_EXPOSURE_US as u64 / 2)
+                / NOMINAL_EXPOSURE_US as u64;
+            scaled.min(255) as u8
+        });
+        for p in bgr.iter_mut() {
+            *p = lut[*p as usize];
+        }
+    }
+}
+
+/// 中心裁剪并以最近邻放大回原尺寸
+fn zoom_center(bgr: &mut [u8], scratch: &mut [u8], w: usize, h: usize, zoom: u32) {
+    let crop_w = (w * ZOOM_1X as usize / zoom as usize).max(1);
+    let crop_h = (h * ZOOM_1X as usize / zoom as usize).max(1);
+    let (x0, y0) = ((w - crop_w) / 2, (h - crop_h) / 2);
+
+    scratch.copy_from_slice(bgr);
+    for y in 0..h {
+        let sy = y0 + y * crop_h / h;
+        let src_row = &scratch[sy * w * 3..(sy + 1) * w * 3];
+        let dst_row = &mut bgr[y * w * 3..(y + 1) * w * 3];
+        for x in 0..w {
+            let sx = x0 + x * crop_w / w;
+            dst_row[x * 3..x * 3 + 3].copy_from_slice(&src_row[sx * 3..sx * 3 + 3]);
+        }
+    }
+}
+
+/// 可分离的方框模糊 (边界复制)
+fn box_blur(bgr: &mut [u8], scratch: &mut [u8], w: usize, h: usize, radius: usize) {
+    let taps = (2 * radius + 1) as u32;
+
+    // 水平: bgr -> scratch
+    for y in 0..h {
+        let row = &bgr[y * w * 3..(y + 1) * w * 3];
+        let out = &mut scratch[y * w * 3..(y + 1) * w * 3];
+        for x in 0..w {
+            for c in 0..3 {
+                let mut sum = 0u32;
+                for k in 0..taps as usize {
+                    let sx = (x + k).saturating_sub(radius).min(w - 1);
+                    sum += row[sx * 3 + c] as u32;
+                }
+                out[x * 3 + c] = ((sum + taps / 2) / taps) as u8;
+            }
+        }
+    }
+
+    // 垂直: scratch -> bgr
+    for y in 0..h {
+        for x in 0..w {
+            for c in 0..3 {
+                let mut sum = 0u32;
+                for k in 0..taps as usize {
+                    let sy = (y + k).saturating_sub(radius).min(h - 1);
+                    sum += scratch[(sy * w + x) * 3 + c] as u32;
+                }
+                bgr[(y * w + x) * 3 + c] = ((sum + taps / 2) / taps) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEUTRAL: ControlSnapshot = ControlSnapshot {
+        exposure_us: NOMINAL_EXPOSURE_US,
+        zoom: ZOOM_1X,
+        defocus: 0,
+    };
+
+    fn checkerboard(w: usize, h: usize) -> Vec<u8> {
+        let mut v = vec![0u8; w * h * 3];
+        for y in 0..h {
+            for x in 0..w {
+                let on = ((x / 4) + (y / 4)) % 2 == 0;
+                v[(y * w + x) * 3..(y * w + x) * 3 + 3].fill(if on { 200 } else { 40 });
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn neutral_controls_leave_image_untouched() {
+        let orig = checkerboard(16, 8);
+        let mut img = orig.clone();
+        apply(&mut img, &mut vec![], 16, 8, NEUTRAL, 30);
+        assert_eq!(img, orig);
+    }
+
+    #[test]
+    fn exposure_scales_and_saturates() {
+        let mut img = checkerboard(16, 8);
+        let snap = ControlSnapshot {
+            exposure_us: 2 * NOMINAL_EXPOSURE_US,
+            ..NEUTRAL
+        };
+        apply(&mut img, &mut vec![], 16, 8, snap, 30);
+        assert_eq!(img[0], 255);
+        assert_eq!(img[4 * 3], 80);
+
+        // 曝光时间受帧间隔限制
+        assert_eq!(actual_exposure_us(100_000, 30), 33_333);
+        assert_eq!(actual_exposure_us(5_000, 30), 5_000);
+    }
+
+    #[test]
+    fn defocus_blurs_edges() {
+        let orig = checkerboard(32, 32);
+        let mut img = orig.clone();
+        let snap = ControlSnapshot {
+            defocus: 2 * FOCUS_UNITS_PER_PIXEL,
+            ..NEUTRAL
+        };
+        apply(&mut img, &mut vec![], 32, 32, snap, 30);
+        let contrast = |v: &[u8]| {
+            v.windows(4)
+                .map(|p| p[0].abs_diff(p[3]) as u32)
+                .sum::<u32>()
+        };
+        assert!(contrast(&img) < contrast(&orig) / 2);
+    }
+
+    #[test]
+    fn zoom_crops_the_center() {
+        // 左半 10，右半 250；2x 变焦后中心 1/2 区域铺满画面
+        let (w, h) = (8usize, 2usize);
+        let mut img: Vec<u8> = (0..w * h * 3)
+            .map(|i| if (i / 3) % w < w / 2 { 10 } else { 250 })
+            .collect();
+        let snap = ControlSnapshot {
+            zoom: 2 * ZOOM_1X,
+            ..NEUTRAL
+        };
+        apply(&mut img, &mut vec![], w as u32, h as u32, snap, 30);
+        let row: Vec<u8> = img[..w * 3].iter().step_by(3).copied().collect();
+        assert_eq!(row, vec![10, 10, 10, 10, 250, 250, 250, 250]);
+    }
+}
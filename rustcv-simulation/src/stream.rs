@@ -0,0 +1,395 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant as TokioInstant;
+
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::frame::{BackendBufferHandle, Frame, FrameMetadata, Timestamp};
+use rustcv_core::pixel_format::PixelFormat;
+use rustcv_core::time::ClockSynchronizer;
+use rustcv_core::traits::Stream;
+
+use crate::controls::ControlState;
+use crate::device::{NegotiatedMode, SimDevice};
+use crate::encode;
+use crate::fault::FaultPlan;
+use crate::inject::{FrameInjector, InjectedFrame};
+use crate::pattern::TestPattern;
+use crate::sensor::{self, ControlSnapshot};
+
+// 本地句柄结构体
+#[derive(Debug)]
+pub struct SimBufferHandle;
+impl BackendBufferHandle for SimBufferHandle {}
+
+static SIM_HANDLE_INSTANCE: SimBufferHandle = SimBufferHandle;
+
+/// 仿真数据流
+///
+/// 注入队列非空时按入队顺序输出注入帧，否则按协商的格式与帧率产出合成帧。
+/// 没有协商模式的流 (仅注入设备) 在队列为空时返回错误。
+/// 设备配置了 `FaultPlan` 时，合成帧按脚本出错。
+/// 曝光、对焦、变焦设置作用于合成帧 (注入帧原样输出)。
+/// 开启 `TriggerMode::Standard` 后，每次软件触发产出一帧合成帧。
+pub struct SimStream {
+    device_id: String,
+    mode: Option<NegotiatedMode>,
+    pattern: TestPattern,
+    realtime: bool,
+    clock_sync: ClockSynchronizer,
+    injector: FrameInjector,
+    faults: FaultPlan,
+    controls: Arc<ControlState>,
+
+    /// 渲染中间结果 (BGR24)
+    bgr: Vec<u8>,
+    /// 传感器模型的中间缓冲
+    scratch: Vec<u8>,
+    /// 当前合成帧的原生格式数据，Frame 借用它
+    buffer: Vec<u8>,
+    /// 静态图案已按这组参数编码，可直接复用 buffer
+    cached: Option<ControlSnapshot>,
+    /// 当前输出的注入帧，Frame 借用它
+    injected: Option<InjectedFrame>,
+    /// 被截断/损坏的负载副本 (不能污染静态图案的缓存)
+    faulted: Vec<u8>,
+
+    is_streaming: bool,
+    disconnected: bool,
+    started_at: Option<TokioInstant>,
+    sequence: u64,
+}
+
+impl SimStream {
+    /// 按设备配置创建数据流，`controls` 与控制面共享
+    pub(crate) fn new(
+        device: &SimDevice,
+        mode: Option<NegotiatedMode>,
+        controls: Arc<ControlState>,
+    ) -> Self {
+        let bgr_len = mode.map_or(0, |m| m.width as usize * m.height as usize * 3);
+        Self {
+            device_id: device.id.clone(),
+            mode,
+            pattern: device.pattern,
+            realtime: device.realtime,
+            clock_sync: ClockSynchronizer::with_time_source(30, device.time_source.clone()),
+            injector: device.injector(),
+            faults: device.faults.clone(),
+            controls,
+            bgr: vec![0; bgr_len],
+            scratch: Vec::new(),
+            buffer: Vec::new(),
+            cached: None,
+            injected: None,
+            faulted: Vec::new(),
+            is_streaming: false,
+            disconnected: false,
+            started_at: None,
+            sequence: 0,
+        }
+    }
+
+    /// 协商出的输出模式 (仅注入设备为 None)
+    pub fn mode(&self) -> Option<NegotiatedMode> {
+        self.mode
+    }
+
+    /// 注入队列句柄
+    pub fn injector(&self) -> FrameInjector {
+        self.injector.clone()
+    }
+
+    /// 按当前控制参数渲染并编码第 `index` 帧到 buffer
+    fn render(
+        &mut self,
+        mode: NegotiatedMode,
+        index: u64,
+        snapshot: ControlSnapshot,
+    ) -> Result<()> {
+        if self.cached == Some(snapshot) && self.pattern.is_static() {
+            return Ok(());
+        }
+        let (w, h) = (mode.width, mode.height);
+        self.pattern.render_bgr(&mut self.bgr, w, h, index);
+        sensor::apply(&mut self.bgr, &mut self.scratch, w, h, snapshot, mode.fps);
+        encode::encode_bgr(mode.format, &self.bgr, w, h, &mut self.buffer)?;
+        self.cached = Some(snapshot);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Stream for SimStream {
+    async fn start(&mut self) -> Result<()> {
+        if self.disconnected {
+            return Err(CameraError::Disconnected(self.device_id.clone()));
+        }
+        self.is_streaming = true;
+        self.started_at = Some(TokioInstant::now());
+        self.sequence = 0;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming = false;
+        self.started_at = None;
+        Ok(())
+    }
+
+    async fn next_frame(&mut self) -> Result<Frame<'_>> {
+        if self.disconnected {
+            return Err(CameraError::Disconnected(self.device_id.clone()));
+        }
+        if !self.is_streaming {
+            return Err(CameraError::SimulationError("Stream not started".into()));
+        }
+
+        // 1. 注入帧优先，原样输出 (含时间戳与元数据)
+        if let Some(frame) = self.injector.pop() {
+            return Ok(self.injected.insert(frame).as_frame(&SIM_HANDLE_INSTANCE));
+        }
+
+        let Some(mode) = self.mode else {
+            return Err(CameraError::SimulationError(
+                "Injection queue is empty".into(),
+            ));
+        };
+
+        // 2. 合成帧：模拟传感器按固定节拍出帧
+        let interval_ns = 1_000_000_000 / mode.fps.max(1) as u64;
+
+        // 被丢弃的帧照样占用序号和时间片
+        // 连续丢弃满 1 秒 (如 `Every(1)`) 时返回错误，下次调用从后续序号继续
+        let max_drops = mode.fps.max(1) as u64;
+        let mut seq = self.sequence;
+        while self.faults.is_dropped(seq) {
+            seq += 1;
+            if seq - self.sequence == max_drops {
+                self.sequence = seq;
+                if self.realtime {
+                    if let Some(start) = self.started_at {
+                        let deadline = start + Duration::from_nanos(seq * interval_ns);
+                        tokio::time::sleep_until(deadline).await;
+                    }
+                }
+                return Err(CameraError::SimulationError(format!(
+                    "{} consecutive frames dropped",
+                    max_drops
+                )));
+            }
+        }
+
+        if self.faults.disconnect_at.is_some_and(|at| seq >= at) {
+            tracing::warn!(target: "rustcv::simulation", "{}: simulated disconnect", self.device_id);
+            self.disconnected = true;
+            self.is_streaming = false;
+            return Err(CameraError::Disconnected(self.device_id.clone()));
+        }
+
+        // 触发模式：收到触发 (+ delay) 才开始曝光，不再按帧率节拍出帧
+        // 等待期间触发被关闭则回到连续采集
+        let mut trigger_fired = false;
+        let trigger = &self.controls.trigger;
+        if trigger.is_enabled() {
+            let delay = Duration::from_micros(trigger.config().delay_us as u64);
+            if trigger.wait().await.is_some() {
+                tokio::time::sleep(delay).await;
+                trigger_fired = true;
+            }
+        }
+
+        if self.realtime && !trigger_fired {
+            if let Some(start) = self.started_at {
+                let deadline = start + Duration::from_nanos(seq * interval_ns);
+                tokio::time::sleep_until(deadline).await;
+            }
+        }
+
+        let stall = self.faults.stall_for(seq);
+        if !stall.is_zero() {
+            tokio::time::sleep(stall).await;
+        }
+
+        let snapshot = self.controls.snapshot();
+        self.render(mode, seq, snapshot)?;
+        self.sequence = seq + 1;
+
+        // 虚拟传感器时钟：从 start() 起计时 (叠加脚本中的漂移与抖动)
+        // 连续采集按帧间隔递增，触发帧取实际曝光时刻
+        let nominal_ns = if trigger_fired {
+            self.started_at
+                .map_or(0, |start| start.elapsed().as_nanos() as u64)
+        } else {
+            seq * interval_ns
+        };
+        let hw_ns = self.faults.apply_timestamp(seq, nominal_ns);
+        let arrival = self
+            .faults
+            .apply_arrival(seq, self.clock_sync.time_source().now());
+        let synced_time = self.clock_sync.correct_at(hw_ns, arrival);
+
+        let data = if self.faults.touches_payload(seq) {
+            self.faulted.clear();
+            self.faulted.extend_from_slice(&self.buffer);
+            self.faults.apply_payload(seq, &mut self.faulted);
+            &self.faulted
+        } else {
+            &self.buffer
+        };
+
+        Ok(Frame {
+            data,
+            width: mode.width,
+            height: mode.height,
+            stride: encode::stride_of(mode.format, mode.width),
+            format: PixelFormat::Known(mode.format),
+            sequence: seq,
+            timestamp: Timestamp {
+                hw_raw_ns: hw_ns,
+                system_synced: synced_time,
+            },
+            metadata: FrameMetadata {
+                actual_exposure_us: Some(sensor::actual_exposure_us(
+                    snapshot.exposure_us,
+                    mode.fps,
+                )),
+                // 仿真传感器没有增益控制，固定为 0 dB
+                actual_gain_db: Some(0.0),
+                trigger_fired,
+                ..Default::default()
+            },
+            backend_handle: &SIM_HANDLE_INSTANCE,
+        })
+    }
+
+    async fn inject_frame(&mut self, frame: Frame<'_>) -> Result<()> {
+        self.injector.push(&frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SimDevice, SimDriver};
+    use rustcv_core::builder::{CameraConfig, Priority};
+    use rustcv_core::pixel_format::FourCC;
+    use rustcv_core::traits::{Driver, TriggerConfig, TriggerMode};
+
+    fn handmade(data: &[u8], sequence: u64) -> Frame<'_> {
+        Frame {
+            data,
+            width: 2,
+            height: 1,
+            stride: 4,
+            format: PixelFormat::Known(FourCC::YUYV),
+            sequence,
+            timestamp: Timestamp {
+                hw_raw_ns: 1_000 * sequence,
+                system_synced: Duration::from_millis(sequence),
+            },
+            metadata: FrameMetadata {
+                actual_exposure_us: Some(500),
+                trigger_fired: true,
+                ..Default::default()
+            },
+            backend_handle: &(),
+        }
+    }
+
+    #[tokio::test]
+    async fn injected_frames_come_back_in_order() {
+        let driver = SimDriver::with_devices(vec![SimDevice::new("inj", "Injected")]);
+        let (mut stream, _) = driver.open("inj", CameraConfig::new()).unwrap();
+        stream.start().await.unwrap();
+
+        stream
+            .inject_frame(handmade(&[1, 2, 3, 4], 7))
+            .await
+            .unwrap();
+        driver
+            .injector("inj")
+            .unwrap()
+            .push(&handmade(&[5, 6, 7, 8], 9));
+
+        let f = stream.next_frame().await.unwrap();
+        assert_eq!(f.data, &[1, 2, 3, 4]);
+        assert_eq!(f.sequence, 7);
+        assert_eq!(f.timestamp.hw_raw_ns, 7_000);
+        assert_eq!(f.metadata.actual_exposure_us, Some(500));
+        assert!(f.metadata.trigger_fired);
+
+        let f = stream.next_frame().await.unwrap();
+        assert_eq!(f.data, &[5, 6, 7, 8]);
+        assert_eq!(f.timestamp.system_synced, Duration::from_millis(9));
+
+        // 仅注入设备：队列耗尽后报错
+        assert!(stream.next_frame().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn injected_frames_preempt_synthetic_ones() {
+        let driver = SimDriver::with_devices(vec![SimDevice::standard(0).realtime(false)]);
+        let config = CameraConfig::new().format(FourCC::BGR3, Priority::Required);
+        let (mut stream, _) = driver.open("sim0", config).unwrap();
+        stream.start().await.unwrap();
+        stream
+            .inject_frame(handmade(&[9, 9, 9, 9], 42))
+            .await
+            .unwrap();
+
+        assert_eq!(stream.next_frame().await.unwrap().sequence, 42);
+        // 注入帧不占用合成帧的序号
+        assert_eq!(stream.next_frame().await.unwrap().sequence, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn software_trigger_yields_one_frame_per_fire() {
+        let device = SimDevice::new("trig", "Triggered").mode(FourCC::BGR3, 32, 24, 30);
+        let driver = SimDriver::with_devices(vec![device]);
+        let (mut stream, controls) = driver.open("trig", CameraConfig::new()).unwrap();
+        stream.start().await.unwrap();
+
+        // 未开启触发模式时不能触发
+        assert!(controls.system.fire_software_trigger().is_err());
+        controls
+            .system
+            .set_trigger(TriggerConfig {
+                mode: TriggerMode::Standard,
+                delay_us: 2_000,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // 没有触发就没有帧
+        let idle = tokio::time::timeout(Duration::from_secs(1), stream.next_frame()).await;
+        assert!(idle.is_err());
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let fired_at = tokio::time::Instant::now();
+        controls.system.fire_software_trigger().unwrap();
+        controls.system.fire_software_trigger().unwrap();
+        for expected in 0..2 {
+            let frame = stream.next_frame().await.unwrap();
+            assert!(frame.metadata.trigger_fired);
+            assert_eq!(frame.sequence, expected);
+        }
+        // 两次触发各自延迟 2ms
+        assert_eq!(fired_at.elapsed(), Duration::from_millis(4));
+        assert!(
+            tokio::time::timeout(Duration::from_secs(1), stream.next_frame())
+                .await
+                .is_err()
+        );
+
+        // 关闭触发后回到连续采集
+        controls
+            .system
+            .set_trigger(TriggerConfig::default())
+            .unwrap();
+        let frame = stream.next_frame().await.unwrap();
+        assert!(!frame.metadata.trigger_fired);
+    }
+}
@@ -0,0 +1,889 @@
+//! YUV4MPEG2 (Y4M) 读写
+//!
+//! Y4M 是最简单的无压缩视频容器：一行文本头，之后每帧一行 `FRAME` 加平面 YUV 数据，
+//! ffmpeg / mpv 等工具都能直接读写。
+//!
+//! - `Y4mWriter` 把相机交付的 YUYV / UYVY / NV12 / I420 / GREY 帧无损重排为平面格式写入。
+//! - `Y4mReader` 读取平面数据，并可还原为 YUYV (4:2:2)、I420 / NV12 / YV12 (4:2:0) 或 GREY。
+//! - `Y4mStream` 把 Y4M 文件当作可重复回放的 `Stream`，为解码器提供逐字节一致的回归输入。
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant as TokioInstant;
+
+use rustcv_core::error::{CameraError, Result};
+use rustcv_core::frame::{BackendBufferHandle, Frame, FrameMetadata, Timestamp};
+use rustcv_core::pixel_format::{FourCC, PixelFormat};
+use rustcv_core::traits::Stream;
+
+use crate::replay::ReplayPace;
+
+const MAGIC: &str = "YUV4MPEG2";
+const FRAME_TAG: &[u8] = b"FRAME";
+
+#[derive(Debug)]
+pub struct Y4mBufferHandle;
+impl BackendBufferHandle for Y4mBufferHandle {}
+
+static Y4M_HANDLE_INSTANCE: Y4mBufferHandle = Y4mBufferHandle;
+
+fn invalid(msg: &str) -> CameraError {
+    CameraError::Io(io::Error::new(io::ErrorKind::InvalidData, msg.to_string()))
+}
+
+/// 色彩空间 / 色度采样 (`C` 标签)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Y4mColorspace {
+    /// 4:2:0，色度位于 2x2 块中心 (`C420jpeg`，也是省略 `C` 标签时的默认值)
+    C420Jpeg,
+    /// 4:2:0，色度水平与左侧亮度对齐 (`C420mpeg2`)
+    C420Mpeg2,
+    /// 4:2:0，PAL DV 色度排列 (`C420paldv`)
+    C420PalDv,
+    /// 4:2:2 (`C422`)
+    C422,
+    /// 仅亮度 (`Cmono`)
+    Mono,
+}
+
+impl Y4mColorspace {
+    fn tag(self) -> &'static str {
+        match self {
+            Self::C420Jpeg => "420jpeg",
+            Self::C420Mpeg2 => "420mpeg2",
+            Self::C420PalDv => "420paldv",
+            Self::C422 => "422",
+            Self::Mono => "mono",
+        }
+    }
+
+    fn parse(tag: &str) -> Result<Self> {
+        match tag {
+            "420" | "420jpeg" => Ok(Self::C420Jpeg),
+            "420mpeg2" => Ok(Self::C420Mpeg2),
+            "420paldv" => Ok(Self::C420PalDv),
+            "422" => Ok(Self::C422),
+            "mono" => Ok(Self::Mono),
+            _ => Err(invalid(&format!("Unsupported Y4M colorspace C{}", tag))),
+        }
+    }
+
+    /// 读取时默认还原的格式
+    pub fn native_format(self) -> FourCC {
+        match self {
+            Self::C422 => FourCC::YUYV,
+            Self::Mono => FourCC::GREY,
+            _ => FourCC::I420,
+        }
+    }
+
+    /// 该色彩空间能否无损表示 `format`
+    pub fn supports(self, format: FourCC) -> bool {
+        match self {
+            Self::C422 => matches!(format, FourCC::YUYV | FourCC::UYVY),
+            Self::Mono => format == FourCC::GREY,
+            _ => matches!(format, FourCC::I420 | FourCC::NV12 | FourCC::YV12),
+        }
+    }
+
+    /// 色度平面的宽高除数
+    fn chroma_shift(self) -> Option<(usize, usize)> {
+        match self {
+            Self::C422 => Some((2, 1)),
+            Self::Mono => None,
+            _ => Some((2, 2)),
+        }
+    }
+}
+
+/// 隔行扫描方式 (`I` 标签)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Y4mInterlace {
+    #[default]
+    Progressive,
+    TopFieldFirst,
+    BottomFieldFirst,
+    /// 每帧在 `FRAME` 行单独标注
+    Mixed,
+}
+
+impl Y4mInterlace {
+    fn tag(self) -> char {
+        match self {
+            Self::Progressive => 'p',
+            Self::TopFieldFirst => 't',
+            Self::BottomFieldFirst => 'b',
+            Self::Mixed => 'm',
+        }
+    }
+}
+
+/// Y4M 文件头
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Y4mHeader {
+    pub width: u32,
+    pub height: u32,
+    /// 帧率 (分子, 分母)
+    pub fps: (u32, u32),
+    pub interlace: Y4mInterlace,
+    /// 像素宽高比，(0, 0) 表示未知
+    pub aspect: (u32, u32),
+    pub colorspace: Y4mColorspace,
+    /// `X` 扩展标签 (不含前缀 `X`)，例如 `COLORRANGE=FULL`，原样保留
+    pub extensions: Vec<String>,
+}
+
+impl Y4mHeader {
+    /// 默认 30 fps、逐行、方形像素
+    pub fn new(width: u32, height: u32, colorspace: Y4mColorspace) -> Self {
+        Self {
+            width,
+            height,
+            fps: (30, 1),
+            interlace: Y4mInterlace::Progressive,
+            aspect: (1, 1),
+            colorspace,
+            extensions: vec![],
+        }
+    }
+
+    /// 按相机格式选择色彩空间 (4:2:0 默认 `C420jpeg`，可用 `colorspace` 修改)
+    pub fn for_format(width: u32, height: u32, format: FourCC) -> Result<Self> {
+        let colorspace = [
+            Y4mColorspace::C422,
+            Y4mColorspace::C420Jpeg,
+            Y4mColorspace::Mono,
+        ]
+        .into_iter()
+        .find(|cs| cs.supports(format))
+        .ok_or(CameraError::FormatNotSupported)?;
+        Ok(Self::new(width, height, colorspace))
+    }
+
+    pub fn fps(mut self, num: u32, den: u32) -> Self {
+        self.fps = (num, den);
+        self
+    }
+
+    pub fn interlace(mut self, interlace: Y4mInterlace) -> Self {
+        self.interlace = interlace;
+        self
+    }
+
+    pub fn aspect(mut self, num: u32, den: u32) -> Self {
+        self.aspect = (num, den);
+        self
+    }
+
+    pub fn colorspace(mut self, colorspace: Y4mColorspace) -> Self {
+        self.colorspace = colorspace;
+        self
+    }
+
+    pub fn extension(mut self, tag: impl Into<String>) -> Self {
+        self.extensions.push(tag.into());
+        self
+    }
+
+    /// 第 `index` 帧相对第一帧的时刻 (帧率未知时为 0)
+    pub fn frame_time(&self, index: u64) -> Duration {
+        let (num, den) = self.fps;
+        if num == 0 {
+            return Duration::ZERO;
+        }
+        let ns = index as u128 * 1_000_000_000 * den as u128 / num as u128;
+        Duration::from_nanos(ns as u64)
+    }
+
+    /// 一帧平面数据的字节数 (溢出时为 `usize::MAX`，读写器会拒绝这样的文件头)
+    pub fn frame_len(&self) -> usize {
+        self.checked_frame_len().unwrap_or(usize::MAX)
+    }
+
+    fn checked_frame_len(&self) -> Option<usize> {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let chroma = match self.colorspace.chroma_shift() {
+            Some((sx, sy)) => w.div_ceil(sx).checked_mul(h.div_ceil(sy))?.checked_mul(2)?,
+            None => 0,
+        };
+        w.checked_mul(h)?.checked_add(chroma)
+    }
+
+    fn check_frame_len(&self) -> Result<()> {
+        match self.checked_frame_len() {
+            Some(_) => Ok(()),
+            None => Err(invalid(&format!(
+                "Y4M frame size {}x{} is too large",
+                self.width, self.height
+            ))),
+        }
+    }
+
+    fn parse(line: &str) -> Result<Self> {
+        let mut tokens = line.split_ascii_whitespace();
+        if tokens.next() != Some(MAGIC) {
+            return Err(invalid("Not a YUV4MPEG2 file"));
+        }
+
+        let mut header = Self::new(0, 0, Y4mColorspace::C420Jpeg).aspect(0, 0);
+        for token in tokens {
+            let Some(tag) = token.get(..1) else {
+                continue;
+            };
+            let value = &token[1..];
+            let ratio = || -> Result<(u32, u32)> {
+                let (n, d) = value
+                    .split_once(':')
+                    .ok_or_else(|| invalid(&format!("Bad Y4M ratio {}", token)))?;
+                let parse = |v: &str| {
+                    v.parse::<u32>()
+                        .map_err(|_| invalid(&format!("Bad Y4M ratio {}", token)))
+                };
+                Ok((parse(n)?, parse(d)?))
+            };
+            let number = || {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| invalid(&format!("Bad Y4M tag {}", token)))
+            };
+            match tag {
+                "W" => header.width = number()?,
+                "H" => header.height = number()?,
+                "F" => header.fps = ratio()?,
+                "A" => header.aspect = ratio()?,
+                "C" => header.colorspace = Y4mColorspace::parse(value)?,
+                "I" => {
+                    header.interlace = match value {
+                        "t" => Y4mInterlace::TopFieldFirst,
+                        "b" => Y4mInterlace::BottomFieldFirst,
+                        "m" => Y4mInterlace::Mixed,
+                        // "?" 未知按逐行处理
+                        _ => Y4mInterlace::Progressive,
+                    }
+                }
+                "X" => header.extensions.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        if header.width == 0 || header.height == 0 {
+            return Err(invalid("Y4M header is missing W or H"));
+        }
+        header.check_frame_len()?;
+        Ok(header)
+    }
+}
+
+impl fmt::Display for Y4mHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} W{} H{} F{}:{} I{} A{}:{} C{}",
+            MAGIC,
+            self.width,
+            self.height,
+            self.fps.0,
+            self.fps.1,
+            self.interlace.tag(),
+            self.aspect.0,
+            self.aspect.1,
+            self.colorspace.tag()
+        )?;
+        for ext in &self.extensions {
+            write!(f, " X{}", ext)?;
+        }
+        Ok(())
+    }
+}
+
+/// Y4M 写入器
+#[derive(Debug)]
+pub struct Y4mWriter<W: Write> {
+    out: W,
+    header: Y4mHeader,
+    planar: Vec<u8>,
+    frames: u64,
+}
+
+impl Y4mWriter<BufWriter<File>> {
+    /// 新建文件 (已存在则覆盖)
+    pub fn create(path: impl AsRef<Path>, header: Y4mHeader) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write> Y4mWriter<W> {
+    /// 写入文件头
+    pub fn new(mut out: W, header: Y4mHeader) -> Result<Self> {
+        header.check_frame_len()?;
+        writeln!(out, "{}", header)?;
+        Ok(Self {
+            out,
+            header,
+            planar: vec![],
+            frames: 0,
+        })
+    }
+
+    pub fn header(&self) -> &Y4mHeader {
+        &self.header
+    }
+
+    /// 追加一帧，格式必须能被文件头的色彩空间无损表示
+    pub fn write_frame(&mut self, frame: &Frame<'_>) -> Result<()> {
+        let PixelFormat::Known(format) = frame.format else {
+            return Err(CameraError::FormatNotSupported);
+        };
+        if !self.header.colorspace.supports(format) {
+            return Err(CameraError::FormatNotSupported);
+        }
+        if (frame.width, frame.height) != (self.header.width, self.header.height) {
+            return Err(invalid(&format!(
+                "Frame is {}x{}, Y4M stream is {}x{}",
+                frame.width, frame.height, self.header.width, self.header.height
+            )));
+        }
+
+        to_planar(
+            frame.data,
+            frame.stride,
+            &self.header,
+            format,
+            &mut self.planar,
+        )?;
+        self.out.write_all(FRAME_TAG)?;
+        self.out.write_all(b"\n")?;
+        self.out.write_all(&self.planar)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Y4M 读取器
+#[derive(Debug)]
+pub struct Y4mReader<R: BufRead> {
+    input: R,
+    header: Y4mHeader,
+    planar: Vec<u8>,
+    line: Vec<u8>,
+}
+
+impl Y4mReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: BufRead> Y4mReader<R> {
+    /// 解析文件头
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut line = vec![];
+        input.read_until(b'\n', &mut line)?;
+        let text = std::str::from_utf8(&line).map_err(|_| invalid("Not a YUV4MPEG2 file"))?;
+        let header = Y4mHeader::parse(text.trim_end())?;
+        Ok(Self {
+            input,
+            header,
+            planar: vec![],
+            line,
+        })
+    }
+
+    pub fn header(&self) -> &Y4mHeader {
+        &self.header
+    }
+
+    /// 读取下一帧的平面数据 (与文件内容一致)
+    /// 文件结束 (包括末尾不完整的帧) 返回 `false`
+    pub fn read_planar(&mut self, buf: &mut Vec<u8>) -> Result<bool> {
+        self.line.clear();
+        if self.input.read_until(b'\n', &mut self.line)? == 0 {
+            return Ok(false);
+        }
+        if !self.line.starts_with(FRAME_TAG) {
+            return Err(invalid("Missing Y4M FRAME marker"));
+        }
+        if self.line.last() != Some(&b'\n') {
+            return Ok(false);
+        }
+
+        // 按实际读到的数据增长缓冲区，文件头的尺寸再大也不会预先分配
+        let len = self.header.frame_len();
+        buf.clear();
+        self.input.by_ref().take(len as u64).read_to_end(buf)?;
+        Ok(buf.len() == len)
+    }
+
+    /// 读取下一帧并转换为 `format` (紧凑排列)
+    pub fn read_frame(&mut self, format: FourCC, buf: &mut Vec<u8>) -> Result<bool> {
+        if !self.header.colorspace.supports(format) {
+            return Err(CameraError::FormatNotSupported);
+        }
+        check_size(format, self.header.width, self.header.height)?;
+        let mut planar = std::mem::take(&mut self.planar);
+        let more = self.read_planar(&mut planar)?;
+        if more {
+            from_planar(&planar, &self.header, format, buf);
+        }
+        self.planar = planar;
+        Ok(more)
+    }
+}
+
+/// 紧凑排列时的每行字节数 (平面格式为 Y 平面的跨距)
+fn packed_stride(format: FourCC, width: u32) -> usize {
+    match format {
+        FourCC::YUYV | FourCC::UYVY => width as usize * 2,
+        _ => width as usize,
+    }
+}
+
+/// 色度子采样的格式要求宽 (4:2:0 还要求高) 为偶数
+fn check_size(format: FourCC, width: u32, height: u32) -> Result<()> {
+    let even = match format {
+        FourCC::GREY => true,
+        FourCC::YUYV | FourCC::UYVY => width.is_multiple_of(2),
+        _ => width.is_multiple_of(2) && height.is_multiple_of(2),
+    };
+    if width == 0 || height == 0 || !even {
+        return Err(invalid(&format!(
+            "{}x{} is not a valid size for {}",
+            width, height, format
+        )));
+    }
+    Ok(())
+}
+
+/// 相机格式 -> Y4M 平面 (Y, U, V)
+fn to_planar(
+    src: &[u8],
+    stride: usize,
+    header: &Y4mHeader,
+    format: FourCC,
+    dst: &mut Vec<u8>,
+) -> Result<()> {
+    check_size(format, header.width, header.height)?;
+    let (w, h) = (header.width as usize, header.height as usize);
+    let stride = if stride == 0 {
+        packed_stride(format, header.width)
+    } else {
+        stride
+    };
+
+    // 最后一行的结束位置
+    let (cw, ch, cstride) = (w / 2, h / 2, stride / 2);
+    let needed = match format {
+        FourCC::YUYV | FourCC::UYVY | FourCC::GREY => {
+            stride * (h - 1) + packed_stride(format, header.width)
+        }
+        FourCC::NV12 => stride * h + stride * (ch - 1) + w,
+        _ => stride * h + cstride * (2 * ch - 1) + cw,
+    };
+    if src.len() < needed {
+        return Err(invalid("Frame data is shorter than its dimensions"));
+    }
+
+    dst.clear();
+    dst.reserve(header.frame_len());
+    match format {
+        FourCC::YUYV | FourCC::UYVY => {
+            // YUYV: Y0 U Y1 V；UYVY: U Y0 V Y1
+            let (y0, u, y1, v) = if format == FourCC::YUYV {
+                (0, 1, 2, 3)
+            } else {
+                (1, 0, 3, 2)
+            };
+            let rows = || (0..h).map(|r| &src[r * stride..r * stride + w * 2]);
+            for row in rows() {
+                for px in row.chunks_exact(4) {
+                    dst.extend_from_slice(&[px[y0], px[y1]]);
+                }
+            }
+            for row in rows() {
+                dst.extend(row.chunks_exact(4).map(|px| px[u]));
+            }
+            for row in rows() {
+                dst.extend(row.chunks_exact(4).map(|px| px[v]));
+            }
+        }
+        FourCC::GREY => {
+            for r in 0..h {
+                dst.extend_from_slice(&src[r * stride..r * stride + w]);
+            }
+        }
+        FourCC::NV12 => {
+            for r in 0..h {
+                dst.extend_from_slice(&src[r * stride..r * stride + w]);
+            }
+            let uv = &src[stride * h..];
+            for offset in [0, 1] {
+                for r in 0..ch {
+                    let row = &uv[r * stride..r * stride + w];
+                    dst.extend(row.iter().skip(offset).step_by(2));
+                }
+            }
+        }
+        _ => {
+            // I420 / YV12：色度平面跨距为 Y 的一半
+            for r in 0..h {
+                dst.extend_from_slice(&src[r * stride..r * stride + w]);
+            }
+            let first = &src[stride * h..];
+            let second = &first[cstride * ch..];
+            let (u, v) = if format == FourCC::YV12 {
+                (second, first)
+            } else {
+                (first, second)
+            };
+            for plane in [u, v] {
+                for r in 0..ch {
+                    dst.extend_from_slice(&plane[r * cstride..r * cstride + cw]);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Y4M 平面 -> 紧凑排列的 `format`
+fn from_planar(src: &[u8], header: &Y4mHeader, format: FourCC, dst: &mut Vec<u8>) {
+    let (w, h) = (header.width as usize, header.height as usize);
+    let (y, chroma) = src.split_at(w * h);
+    dst.clear();
+    dst.reserve(header.frame_len());
+
+    match format {
+        FourCC::YUYV | FourCC::UYVY => {
+            let cw = w.div_ceil(2);
+            let (u, v) = chroma.split_at(cw * h);
+            for r in 0..h {
+                for c in 0..w / 2 {
+                    let (y0, y1) = (y[r * w + 2 * c], y[r * w + 2 * c + 1]);
+                    let (u, v) = (u[r * cw + c], v[r * cw + c]);
+                    if format == FourCC::YUYV {
+                        dst.extend_from_slice(&[y0, u, y1, v]);
+                    } else {
+                        dst.extend_from_slice(&[u, y0, v, y1]);
+                    }
+                }
+            }
+        }
+        FourCC::NV12 => {
+            dst.extend_from_slice(y);
+            let plane = w.div_ceil(2) * h.div_ceil(2);
+            let (u, v) = chroma.split_at(plane);
+            for (&u, &v) in u.iter().zip(v) {
+                dst.extend_from_slice(&[u, v]);
+            }
+        }
+        FourCC::YV12 => {
+            let plane = w.div_ceil(2) * h.div_ceil(2);
+            let (u, v) = chroma.split_at(plane);
+            dst.extend_from_slice(y);
+            dst.extend_from_slice(v);
+            dst.extend_from_slice(u);
+        }
+        // I420 / GREY 与 Y4M 布局相同
+        _ => dst.extend_from_slice(src),
+    }
+}
+
+/// Y4M 文件回放流
+///
+/// 每次 `start()` 从头读起，时间戳按文件头的帧率合成 (第 n 帧为 n * 帧间隔)。
+/// 读完后 `next_frame` 返回错误。
+#[derive(Debug)]
+pub struct Y4mStream {
+    path: PathBuf,
+    format: FourCC,
+    pace: ReplayPace,
+    reader: Y4mReader<BufReader<File>>,
+    data: Vec<u8>,
+    sequence: u64,
+    is_streaming: bool,
+    origin: Option<TokioInstant>,
+}
+
+impl Y4mStream {
+    /// 打开文件，`format` 为 `None` 时使用色彩空间的默认格式
+    pub fn open(path: impl AsRef<Path>, format: Option<FourCC>, pace: ReplayPace) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let reader = Y4mReader::open(&path)?;
+        let colorspace = reader.header().colorspace;
+        let format = format.unwrap_or(colorspace.native_format());
+        if !colorspace.supports(format) {
+            return Err(CameraError::FormatNotSupported);
+        }
+        check_size(format, reader.header().width, reader.header().height)?;
+        Ok(Self {
+            path,
+            format,
+            pace,
+            reader,
+            data: vec![],
+            sequence: 0,
+            is_streaming: false,
+            origin: None,
+        })
+    }
+
+    pub fn header(&self) -> &Y4mHeader {
+        self.reader.header()
+    }
+}
+
+#[async_trait]
+impl Stream for Y4mStream {
+    async fn start(&mut self) -> Result<()> {
+        self.reader = Y4mReader::open(&self.path)?;
+        self.sequence = 0;
+        self.origin = None;
+        self.is_streaming = true;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.is_streaming = false;
+        Ok(())
+    }
+
+    async fn next_frame(&mut self) -> Result<Frame<'_>> {
+        if !self.is_streaming {
+            return Err(CameraError::SimulationError("Stream not started".into()));
+        }
+        if !self.reader.read_frame(self.format, &mut self.data)? {
+            return Err(CameraError::SimulationError("End of Y4M file".into()));
+        }
+
+        let header = self.reader.header();
+        let offset = header.frame_time(self.sequence);
+        match self.origin {
+            None => self.origin = Some(TokioInstant::now()),
+            Some(start) if self.pace == ReplayPace::Original => {
+                tokio::time::sleep_until(start + offset).await;
+            }
+            Some(_) => {}
+        }
+
+        let sequence = self.sequence;
+        self.sequence += 1;
+        Ok(Frame {
+            data: &self.data,
+            width: header.width,
+            height: header.height,
+            stride: packed_stride(self.format, header.width),
+            format: PixelFormat::Known(self.format),
+            sequence,
+            timestamp: Timestamp {
+                hw_raw_ns: offset.as_nanos() as u64,
+                system_synced: offset,
+            },
+            metadata: FrameMetadata::default(),
+            backend_handle: &Y4M_HANDLE_INSTANCE,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inject::InjectedFrame;
+    use std::io::Cursor;
+
+    /// 每个字节都不同的测试数据，确保重排没有错位
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 251) as u8).collect()
+    }
+
+    fn frame_of(data: &[u8], format: FourCC, w: u32, h: u32, stride: usize) -> InjectedFrame {
+        InjectedFrame {
+            data: data.to_vec(),
+            width: w,
+            height: h,
+            stride,
+            format: PixelFormat::Known(format),
+            ..InjectedFrame::empty()
+        }
+    }
+
+    fn round_trip(format: FourCC, len: usize, w: u32, h: u32) {
+        let original = pattern(len);
+        let header = Y4mHeader::for_format(w, h, format).unwrap().fps(25, 1);
+        let mut writer = Y4mWriter::new(vec![], header.clone()).unwrap();
+        let frame = frame_of(&original, format, w, h, 0);
+        for _ in 0..2 {
+            writer.write_frame(&frame.as_frame(&())).unwrap();
+        }
+        let file = writer.into_inner().unwrap();
+        assert_eq!(file.len(), header.to_string().len() + 1 + 2 * (6 + len));
+
+        let mut reader = Y4mReader::new(Cursor::new(file)).unwrap();
+        assert_eq!(reader.header(), &header);
+        let mut got = vec![];
+        for _ in 0..2 {
+            assert!(reader.read_frame(format, &mut got).unwrap());
+            assert_eq!(got, original, "{}", format);
+        }
+        assert!(!reader.read_frame(format, &mut got).unwrap());
+    }
+
+    #[test]
+    fn camera_formats_round_trip_losslessly() {
+        let (w, h) = (8u32, 6u32);
+        let px = (w * h) as usize;
+        round_trip(FourCC::YUYV, px * 2, w, h);
+        round_trip(FourCC::UYVY, px * 2, w, h);
+        round_trip(FourCC::NV12, px * 3 / 2, w, h);
+        round_trip(FourCC::I420, px * 3 / 2, w, h);
+        round_trip(FourCC::YV12, px * 3 / 2, w, h);
+        round_trip(FourCC::GREY, px, w, h);
+    }
+
+    #[test]
+    fn nv12_and_i420_share_planar_layout() {
+        // 2x2 NV12: Y = 1 2 3 4, UV = (5, 6) -> Y4M: 1 2 3 4 | 5 | 6
+        let header = Y4mHeader::new(2, 2, Y4mColorspace::C420Mpeg2);
+        let mut writer = Y4mWriter::new(vec![], header).unwrap();
+        let nv12 = frame_of(&[1, 2, 3, 4, 5, 6], FourCC::NV12, 2, 2, 0);
+        writer.write_frame(&nv12.as_frame(&())).unwrap();
+        let file = writer.into_inner().unwrap();
+        assert!(file.ends_with(b"FRAME\n\x01\x02\x03\x04\x05\x06"));
+
+        // YUYV 不能写入 4:2:0 文件
+        let mut writer =
+            Y4mWriter::new(vec![], Y4mHeader::new(2, 2, Y4mColorspace::C420Jpeg)).unwrap();
+        let yuyv = frame_of(&[0; 8], FourCC::YUYV, 2, 2, 0);
+        assert!(writer.write_frame(&yuyv.as_frame(&())).is_err());
+
+        let mut reader = Y4mReader::new(Cursor::new(file)).unwrap();
+        let mut i420 = vec![];
+        assert!(reader.read_frame(FourCC::I420, &mut i420).unwrap());
+        assert_eq!(i420, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn strided_frames_are_packed() {
+        // 4x2 YUYV，每行填充 4 字节
+        let rows = [
+            [10, 20, 11, 30, 12, 21, 13, 31],
+            [14, 22, 15, 32, 16, 23, 17, 33],
+        ];
+        let mut data = vec![];
+        for row in &rows {
+            data.extend_from_slice(row);
+            data.extend_from_slice(&[0xEE; 4]);
+        }
+        let header = Y4mHeader::new(4, 2, Y4mColorspace::C422);
+        let mut writer = Y4mWriter::new(vec![], header).unwrap();
+        let frame = frame_of(&data, FourCC::YUYV, 4, 2, 12);
+        writer.write_frame(&frame.as_frame(&())).unwrap();
+
+        let mut reader = Y4mReader::new(Cursor::new(writer.into_inner().unwrap())).unwrap();
+        let mut planar = vec![];
+        assert!(reader.read_planar(&mut planar).unwrap());
+        assert_eq!(
+            planar,
+            [10, 11, 12, 13, 14, 15, 16, 17, 20, 21, 22, 23, 30, 31, 32, 33]
+        );
+    }
+
+    #[test]
+    fn parses_header_tags() {
+        let line = "YUV4MPEG2 W1920 H1080 F30000:1001 It A10:11 C420paldv XYSCSS=420PALDV XCOLORRANGE=FULL";
+        let header = Y4mHeader::parse(line).unwrap();
+        assert_eq!((header.width, header.height), (1920, 1080));
+        assert_eq!(header.fps, (30000, 1001));
+        assert_eq!(header.interlace, Y4mInterlace::TopFieldFirst);
+        assert_eq!(header.aspect, (10, 11));
+        assert_eq!(header.colorspace, Y4mColorspace::C420PalDv);
+        assert_eq!(header.extensions, ["YSCSS=420PALDV", "COLORRANGE=FULL"]);
+        assert_eq!(header.to_string(), line);
+
+        // 省略 C 标签时为 4:2:0
+        let header = Y4mHeader::parse("YUV4MPEG2 W4 H2 F25:1").unwrap();
+        assert_eq!(header.colorspace, Y4mColorspace::C420Jpeg);
+        assert_eq!(header.frame_len(), 12);
+        assert!(Y4mHeader::parse("YUV4MPEG2 W4 H2 C444").is_err());
+        assert!(Y4mHeader::parse("RIFF W4 H2").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_frame_sizes() {
+        // 奇数宽无法还原为 YUYV / NV12
+        for (line, format) in [
+            ("YUV4MPEG2 W3 H2 C422", FourCC::YUYV),
+            ("YUV4MPEG2 W3 H2 C420jpeg", FourCC::NV12),
+            ("YUV4MPEG2 W4 H3 C420jpeg", FourCC::I420),
+        ] {
+            let file = format!("{}\nFRAME\n{}", line, "\0".repeat(32));
+            let mut reader = Y4mReader::new(Cursor::new(file.into_bytes())).unwrap();
+            assert!(reader.read_frame(format, &mut vec![]).is_err(), "{}", line);
+        }
+        let file = "YUV4MPEG2 W3 H2 Cmono\nFRAME\n\0\0\0\0\0\0";
+        let mut reader = Y4mReader::new(Cursor::new(file.as_bytes())).unwrap();
+        let mut grey = vec![];
+        assert!(reader.read_frame(FourCC::GREY, &mut grey).unwrap());
+        assert_eq!(grey.len(), 6);
+
+        // 尺寸巨大的文件头不会溢出或预先分配整帧
+        let file = "YUV4MPEG2 W4294967295 H4294967295 C420jpeg\nFRAME\n";
+        assert!(Y4mReader::new(Cursor::new(file.as_bytes())).is_err());
+        let file = "YUV4MPEG2 W4000000000 H2 Cmono\nFRAME\n\0\0";
+        let mut reader = Y4mReader::new(Cursor::new(file.as_bytes())).unwrap();
+        assert!(!reader.read_planar(&mut vec![]).unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_replays_at_file_frame_rate() {
+        let path = std::env::temp_dir().join(format!("rustcv-y4m-{}.y4m", std::process::id()));
+        let header = Y4mHeader::new(4, 2, Y4mColorspace::C422).fps(20, 1);
+        let mut writer = Y4mWriter::create(&path, header).unwrap();
+        let frames: Vec<_> = (0..3u8).map(|i| vec![i; 16]).collect();
+        for data in &frames {
+            let frame = frame_of(data, FourCC::YUYV, 4, 2, 0);
+            writer.write_frame(&frame.as_frame(&())).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        assert!(Y4mStream::open(&path, Some(FourCC::NV12), ReplayPace::Original).is_err());
+        let mut stream = Y4mStream::open(&path, None, ReplayPace::Original).unwrap();
+        stream.start().await.unwrap();
+        let begin = TokioInstant::now();
+        for (i, want) in frames.iter().enumerate() {
+            let frame = stream.next_frame().await.unwrap();
+            assert_eq!(frame.format, PixelFormat::Known(FourCC::YUYV));
+            assert_eq!((frame.sequence, frame.stride), (i as u64, 8));
+            assert_eq!(
+                frame.timestamp.system_synced,
+                Duration::from_millis(50) * i as u32
+            );
+            assert_eq!(frame.data, &want[..]);
+        }
+        assert_eq!(begin.elapsed(), Duration::from_millis(100));
+        assert!(stream.next_frame().await.is_err());
+
+        stream.start().await.unwrap();
+        assert_eq!(stream.next_frame().await.unwrap().data, &frames[0][..]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
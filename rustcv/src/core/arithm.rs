@@ -0,0 +1,653 @@
+//! 逐元素运算与统计 (对应 OpenCV core 模块中的 arithm / stat 部分)
+//!
+//! 输入可以是 `Mat` 或 ROI 视图，输出 `dst` 按需分配 (语义同 `Mat::create`)。
+//! 整数深度的结果会饱和到取值范围，例如 8 位图像 `200 + 100 = 255`、`50 - 100 = 0`。
+//! `mask` 必须是与输入同尺寸的 `CV_8UC1` 图像，值为 0 的像素不参与运算，
+//! 对应的输出像素保持原值。
+//!
+//! ```no_run
+//! use rustcv::core::{absdiff, count_non_zero, in_range, Scalar};
+//! # let (prev, frame) = (rustcv::Mat::empty(), rustcv::Mat::empty());
+//! let mut diff = rustcv::Mat::empty();
+//! absdiff(&prev, &frame, &mut diff)?;
+//! // 灰度帧差大于 30 的像素视为运动
+//! let mut moving = rustcv::Mat::empty();
+//! in_range(&diff, Scalar::all(31), Scalar::all(255), &mut moving)?;
+//! let changed = count_non_zero(&moving)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::mat::{dispatch_depth, type_name, Mat, Sample, CV_32F, CV_8UC1};
+use super::types::{Point, Scalar};
+use super::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+
+/// 最大绝对值
+pub const NORM_INF: i32 = 1;
+/// 绝对值之和
+pub const NORM_L1: i32 = 2;
+/// 平方和的平方根
+pub const NORM_L2: i32 = 4;
+/// 平方和
+pub const NORM_L2SQR: i32 = 5;
+/// 与其他类型组合用于 `norm_diff`，返回 `‖src1 - src2‖ / ‖src2‖`
+pub const NORM_RELATIVE: i32 = 8;
+
+// --- 算术运算 ---
+
+/// `dst = saturate(src1 + src2)`
+pub fn add<A, B>(src1: &A, src2: &B, dst: &mut Mat, mask: Option<&dyn AsMatView>) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("add", src1, src2)?;
+    let mask = check_mask(mask, &a)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, mask, |x, y| {
+            Sample::from_f64(x.to_f64() + y.to_f64())
+        })
+    )
+}
+
+/// `dst = saturate(src1 - src2)`
+pub fn subtract<A, B>(src1: &A, src2: &B, dst: &mut Mat, mask: Option<&dyn AsMatView>) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("subtract", src1, src2)?;
+    let mask = check_mask(mask, &a)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, mask, |x, y| {
+            Sample::from_f64(x.to_f64() - y.to_f64())
+        })
+    )
+}
+
+/// `dst = saturate(scale * src1 * src2)`
+pub fn multiply<A, B>(src1: &A, src2: &B, dst: &mut Mat, scale: f64) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("multiply", src1, src2)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, None, |x, y| {
+            Sample::from_f64(scale * x.to_f64() * y.to_f64())
+        })
+    )
+}
+
+/// `dst = saturate(scale * src1 / src2)`
+///
+/// 整数深度下除数为 0 时结果为 0 (与 OpenCV 一致)，`CV_32F` 按 IEEE 754 得到 ±inf / NaN。
+pub fn divide<A, B>(src1: &A, src2: &B, dst: &mut Mat, scale: f64) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("divide", src1, src2)?;
+    dispatch_depth!(a.depth, binary(a, b, dst, None, div_op(scale)))
+}
+
+fn div_op<T: Sample>(scale: f64) -> impl Fn(T, T) -> T {
+    move |x, y| {
+        let y = y.to_f64();
+        if y == 0.0 && T::DEPTH != CV_32F {
+            T::from_f64(0.0)
+        } else {
+            T::from_f64(scale * x.to_f64() / y)
+        }
+    }
+}
+
+/// `dst = saturate(|src1 - src2|)`，常用于帧差法检测运动
+pub fn absdiff<A, B>(src1: &A, src2: &B, dst: &mut Mat) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("absdiff", src1, src2)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, None, |x, y| {
+            Sample::from_f64((x.to_f64() - y.to_f64()).abs())
+        })
+    )
+}
+
+/// `dst = saturate(src1 * alpha + src2 * beta + gamma)`
+pub fn add_weighted<A, B>(
+    src1: &A,
+    alpha: f64,
+    src2: &B,
+    beta: f64,
+    gamma: f64,
+    dst: &mut Mat,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("add_weighted", src1, src2)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, None, |x, y| {
+            Sample::from_f64(x.to_f64() * alpha + y.to_f64() * beta + gamma)
+        })
+    )
+}
+
+/// 逐元素取较小值
+pub fn min<A, B>(src1: &A, src2: &B, dst: &mut Mat) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("min", src1, src2)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, None, |x, y| if y < x { y } else { x })
+    )
+}
+
+/// 逐元素取较大值
+pub fn max<A, B>(src1: &A, src2: &B, dst: &mut Mat) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("max", src1, src2)?;
+    dispatch_depth!(
+        a.depth,
+        binary(a, b, dst, None, |x, y| if y > x { y } else { x })
+    )
+}
+
+// --- 位运算 (按字节进行，与深度无关) ---
+
+pub fn bitwise_and<A, B>(
+    src1: &A,
+    src2: &B,
+    dst: &mut Mat,
+    mask: Option<&dyn AsMatView>,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("bitwise_and", src1, src2)?;
+    let mask = check_mask(mask, &a)?;
+    bitwise(a, b, dst, mask, |x, y| x & y);
+    Ok(())
+}
+
+pub fn bitwise_or<A, B>(
+    src1: &A,
+    src2: &B,
+    dst: &mut Mat,
+    mask: Option<&dyn AsMatView>,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("bitwise_or", src1, src2)?;
+    let mask = check_mask(mask, &a)?;
+    bitwise(a, b, dst, mask, |x, y| x | y);
+    Ok(())
+}
+
+pub fn bitwise_xor<A, B>(
+    src1: &A,
+    src2: &B,
+    dst: &mut Mat,
+    mask: Option<&dyn AsMatView>,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("bitwise_xor", src1, src2)?;
+    let mask = check_mask(mask, &a)?;
+    bitwise(a, b, dst, mask, |x, y| x ^ y);
+    Ok(())
+}
+
+pub fn bitwise_not<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    mask: Option<&dyn AsMatView>,
+) -> Result<()> {
+    let a = src.view();
+    let mask = check_mask(mask, &a)?;
+    bitwise(a, a, dst, mask, |x, _| !x);
+    Ok(())
+}
+
+// --- 比较与统计 ---
+
+/// 所有通道都落在 `[lowerb, upperb]` 内的像素输出 255，否则输出 0，`dst` 为 `CV_8UC1`
+pub fn in_range<A: AsMatView + ?Sized>(
+    src: &A,
+    lowerb: Scalar,
+    upperb: Scalar,
+    dst: &mut Mat,
+) -> Result<()> {
+    let a = src.view();
+    dst.create(a.rows, a.cols, CV_8UC1);
+    let (lo, hi) = (lowerb.to_array(), upperb.to_array());
+    dispatch_depth!(a.depth, in_range_impl(a, lo, hi, dst));
+    Ok(())
+}
+
+fn in_range_impl<T: Sample>(a: MatView<'_>, lo: [f64; 4], hi: [f64; 4], dst: &mut Mat) {
+    let cn = a.channels as usize;
+    for r in 0..a.rows {
+        let src = a.row::<T>(r);
+        for (d, px) in dst.row_bytes_mut(r).iter_mut().zip(src.chunks_exact(cn)) {
+            let inside = px.iter().zip(lo.iter().zip(&hi)).all(|(&v, (&lo, &hi))| {
+                let v = v.to_f64();
+                lo <= v && v <= hi
+            });
+            *d = if inside { 255 } else { 0 };
+        }
+    }
+}
+
+/// 非零元素个数，只支持单通道图像
+pub fn count_non_zero<A: AsMatView + ?Sized>(src: &A) -> Result<i32> {
+    let a = src.view();
+    single_channel("count_non_zero", &a)?;
+    Ok(dispatch_depth!(a.depth, count_non_zero_impl(a)))
+}
+
+fn count_non_zero_impl<T: Sample>(a: MatView<'_>) -> i32 {
+    (0..a.rows)
+        .map(|r| a.row::<T>(r).iter().filter(|v| v.to_f64() != 0.0).count() as i32)
+        .sum()
+}
+
+/// 各通道的均值，`mask` 全为 0 时返回 0
+pub fn mean<A: AsMatView + ?Sized>(src: &A, mask: Option<&dyn AsMatView>) -> Result<Scalar> {
+    Ok(mean_std_dev(src, mask)?.0)
+}
+
+/// 各通道的均值与 (总体) 标准差
+pub fn mean_std_dev<A: AsMatView + ?Sized>(
+    src: &A,
+    mask: Option<&dyn AsMatView>,
+) -> Result<(Scalar, Scalar)> {
+    let a = src.view();
+    let mask = check_mask(mask, &a)?;
+    let (count, sum, sqsum) = dispatch_depth!(a.depth, moments(a, mask));
+    let (mut mean, mut stddev) = ([0.0; 4], [0.0; 4]);
+    if count > 0 {
+        let n = count as f64;
+        for k in 0..a.channels as usize {
+            mean[k] = sum[k] / n;
+            stddev[k] = (sqsum[k] / n - mean[k] * mean[k]).max(0.0).sqrt();
+        }
+    }
+    Ok((mean.into(), stddev.into()))
+}
+
+/// 返回 (像素数, 各通道之和, 各通道平方和)
+fn moments<T: Sample>(a: MatView<'_>, mask: Option<MatView<'_>>) -> (usize, [f64; 4], [f64; 4]) {
+    let cn = a.channels as usize;
+    let (mut count, mut sum, mut sqsum) = (0, [0.0; 4], [0.0; 4]);
+    for r in 0..a.rows {
+        let m = mask.as_ref().map(|m| m.row_bytes(r));
+        for (c, px) in a.row::<T>(r).chunks_exact(cn).enumerate() {
+            if m.is_some_and(|m| m[c] == 0) {
+                continue;
+            }
+            count += 1;
+            for (k, &v) in px.iter().enumerate() {
+                let v = v.to_f64();
+                sum[k] += v;
+                sqsum[k] += v * v;
+            }
+        }
+    }
+    (count, sum, sqsum)
+}
+
+/// 单通道图像的 (最小值, 最大值, 最小值位置, 最大值位置)
+///
+/// 有多个极值时取行优先顺序下的第一个；`mask` 全为 0 时位置为 `(-1, -1)`。
+pub fn min_max_loc<A: AsMatView + ?Sized>(
+    src: &A,
+    mask: Option<&dyn AsMatView>,
+) -> Result<(f64, f64, Point, Point)> {
+    let a = src.view();
+    single_channel("min_max_loc", &a)?;
+    let mask = check_mask(mask, &a)?;
+    Ok(dispatch_depth!(a.depth, min_max_loc_impl(a, mask)))
+}
+
+fn min_max_loc_impl<T: Sample>(
+    a: MatView<'_>,
+    mask: Option<MatView<'_>>,
+) -> (f64, f64, Point, Point) {
+    let mut found: Option<(f64, f64, Point, Point)> = None;
+    for r in 0..a.rows {
+        let m = mask.as_ref().map(|m| m.row_bytes(r));
+        for (c, &v) in a.row::<T>(r).iter().enumerate() {
+            if m.is_some_and(|m| m[c] == 0) {
+                continue;
+            }
+            let (v, loc) = (v.to_f64(), Point::new(c as i32, r));
+            match &mut found {
+                None => found = Some((v, v, loc, loc)),
+                Some((min, max, min_loc, max_loc)) => {
+                    if v < *min {
+                        (*min, *min_loc) = (v, loc);
+                    }
+                    if v > *max {
+                        (*max, *max_loc) = (v, loc);
+                    }
+                }
+            }
+        }
+    }
+    found.unwrap_or((0.0, 0.0, Point::new(-1, -1), Point::new(-1, -1)))
+}
+
+/// 图像的范数 (`NORM_INF` / `NORM_L1` / `NORM_L2` / `NORM_L2SQR`)，所有通道一起计算
+pub fn norm<A: AsMatView + ?Sized>(
+    src: &A,
+    norm_type: i32,
+    mask: Option<&dyn AsMatView>,
+) -> Result<f64> {
+    let a = src.view();
+    check_norm_type(norm_type, false)?;
+    let mask = check_mask(mask, &a)?;
+    Ok(dispatch_depth!(
+        a.depth,
+        norm_impl(a, None, norm_type, mask)
+    ))
+}
+
+/// 差值 `src1 - src2` 的范数，`norm_type` 可以组合 `NORM_RELATIVE`
+pub fn norm_diff<A, B>(
+    src1: &A,
+    src2: &B,
+    norm_type: i32,
+    mask: Option<&dyn AsMatView>,
+) -> Result<f64>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = binary_inputs("norm_diff", src1, src2)?;
+    check_norm_type(norm_type, true)?;
+    let mask = check_mask(mask, &a)?;
+    let kind = norm_type & !NORM_RELATIVE;
+    let diff = dispatch_depth!(a.depth, norm_impl(a, Some(b), kind, mask));
+    if norm_type & NORM_RELATIVE != 0 {
+        let base = dispatch_depth!(b.depth, norm_impl(b, None, kind, mask));
+        Ok(diff / (base + f64::EPSILON))
+    } else {
+        Ok(diff)
+    }
+}
+
+fn norm_impl<T: Sample>(
+    a: MatView<'_>,
+    b: Option<MatView<'_>>,
+    norm_type: i32,
+    mask: Option<MatView<'_>>,
+) -> f64 {
+    let cn = a.channels as usize;
+    let (mut inf, mut l1, mut l2) = (0.0f64, 0.0, 0.0);
+    for r in 0..a.rows {
+        let y = b.as_ref().map(|b| b.row::<T>(r));
+        let m = mask.as_ref().map(|m| m.row_bytes(r));
+        for (i, &x) in a.row::<T>(r).iter().enumerate() {
+            if m.is_some_and(|m| m[i / cn] == 0) {
+                continue;
+            }
+            let v = (x.to_f64() - y.map_or(0.0, |y| y[i].to_f64())).abs();
+            inf = inf.max(v);
+            l1 += v;
+            l2 += v * v;
+        }
+    }
+    match norm_type {
+        NORM_INF => inf,
+        NORM_L1 => l1,
+        NORM_L2SQR => l2,
+        _ => l2.sqrt(),
+    }
+}
+
+// --- 内部辅助 ---
+
+/// 对两幅图像逐通道计算 `op`，`mask` 为 0 的像素保持 dst 原值
+fn binary<T: Sample>(
+    a: MatView<'_>,
+    b: MatView<'_>,
+    dst: &mut Mat,
+    mask: Option<MatView<'_>>,
+    op: impl Fn(T, T) -> T,
+) -> Result<()> {
+    dst.create(a.rows, a.cols, a.typ());
+    let cn = a.channels as usize;
+    for r in 0..a.rows {
+        let (x, y) = (a.row::<T>(r), b.row::<T>(r));
+        let m = mask.as_ref().map(|m| m.row_bytes(r));
+        for (i, d) in dst.row_mut::<T>(r).iter_mut().enumerate() {
+            if m.is_none_or(|m| m[i / cn] != 0) {
+                *d = op(x[i], y[i]);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按字节计算 `op`，`mask` 为 0 的像素保持 dst 原值
+fn bitwise(
+    a: MatView<'_>,
+    b: MatView<'_>,
+    dst: &mut Mat,
+    mask: Option<MatView<'_>>,
+    op: impl Fn(u8, u8) -> u8,
+) {
+    dst.create(a.rows, a.cols, a.typ());
+    let size = a.elem_size();
+    for r in 0..a.rows {
+        let (x, y) = (a.row_bytes(r), b.row_bytes(r));
+        let m = mask.as_ref().map(|m| m.row_bytes(r));
+        for (i, d) in dst.row_bytes_mut(r).iter_mut().enumerate() {
+            if m.is_none_or(|m| m[i / size] != 0) {
+                *d = op(x[i], y[i]);
+            }
+        }
+    }
+}
+
+fn binary_inputs<'a, A, B>(
+    name: &str,
+    src1: &'a A,
+    src2: &'a B,
+) -> Result<(MatView<'a>, MatView<'a>)>
+where
+    A: AsMatView + ?Sized,
+    B: AsMatView + ?Sized,
+{
+    let (a, b) = (src1.view(), src2.view());
+    if (a.rows, a.cols, a.typ()) != (b.rows, b.cols, b.typ()) {
+        return Err(anyhow!(
+            "{}: inputs differ in size or type ({}x{} {} vs {}x{} {})",
+            name,
+            a.cols,
+            a.rows,
+            type_name(a.typ()),
+            b.cols,
+            b.rows,
+            type_name(b.typ())
+        ));
+    }
+    Ok((a, b))
+}
+
+fn check_mask<'a>(
+    mask: Option<&'a dyn AsMatView>,
+    src: &MatView<'_>,
+) -> Result<Option<MatView<'a>>> {
+    let Some(mask) = mask else {
+        return Ok(None);
+    };
+    let mask = mask.view();
+    if mask.typ() != CV_8UC1 || (mask.rows, mask.cols) != (src.rows, src.cols) {
+        return Err(anyhow!(
+            "Mask must be a {}x{} CV_8UC1 image, got {}x{} {}",
+            src.cols,
+            src.rows,
+            mask.cols,
+            mask.rows,
+            type_name(mask.typ())
+        ));
+    }
+    Ok(Some(mask))
+}
+
+fn single_channel(name: &str, src: &MatView<'_>) -> Result<()> {
+    if src.channels != 1 {
+        return Err(anyhow!(
+            "{}: expected a single-channel image, got {}",
+            name,
+            type_name(src.typ())
+        ));
+    }
+    Ok(())
+}
+
+fn check_norm_type(norm_type: i32, allow_relative: bool) -> Result<()> {
+    let relative = norm_type & NORM_RELATIVE != 0;
+    match norm_type & !NORM_RELATIVE {
+        NORM_INF | NORM_L1 | NORM_L2 | NORM_L2SQR if allow_relative || !relative => Ok(()),
+        _ => Err(anyhow!("Unsupported norm type: {}", norm_type)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16UC1, CV_32FC1, CV_8UC3};
+
+    fn gray(rows: i32, cols: i32, data: &[u8]) -> Mat {
+        Mat::from_slice(rows, cols, CV_8UC1, data).unwrap()
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        let a = gray(1, 4, &[10, 200, 50, 255]);
+        let b = gray(1, 4, &[20, 100, 100, 0]);
+        let mut dst = Mat::empty();
+
+        add(&a, &b, &mut dst, None).unwrap();
+        assert_eq!(dst.data, [30, 255, 150, 255]);
+        subtract(&a, &b, &mut dst, None).unwrap();
+        assert_eq!(dst.data, [0, 100, 0, 255]);
+        absdiff(&a, &b, &mut dst).unwrap();
+        assert_eq!(dst.data, [10, 100, 50, 255]);
+        multiply(&a, &b, &mut dst, 0.5).unwrap();
+        assert_eq!(dst.data, [100, 255, 255, 0]);
+        divide(&a, &b, &mut dst, 1.0).unwrap();
+        assert_eq!(dst.data, [0, 2, 0, 0]);
+        add_weighted(&a, 0.5, &b, 0.5, 1.0, &mut dst).unwrap();
+        assert_eq!(dst.data, [16, 151, 76, 128]);
+        min(&a, &b, &mut dst).unwrap();
+        assert_eq!(dst.data, [10, 100, 50, 0]);
+        max(&a, &b, &mut dst).unwrap();
+        assert_eq!(dst.data, [20, 200, 100, 255]);
+
+        let f = Mat::from_slice(1, 2, CV_32FC1, &[1.0f32, -1.0]).unwrap();
+        let zero = Mat::zeros(1, 2, CV_32FC1);
+        divide(&f, &zero, &mut dst, 1.0).unwrap();
+        assert_eq!(dst.row::<f32>(0), [f32::INFINITY, f32::NEG_INFINITY]);
+        subtract(&zero, &f, &mut dst, None).unwrap();
+        assert_eq!(dst.row::<f32>(0), [-1.0, 1.0]);
+
+        assert!(add(&a, &f, &mut dst, None).is_err());
+    }
+
+    #[test]
+    fn masked_ops_keep_dst() {
+        let a = Mat::from_slice(1, 2, CV_8UC3, &[[1u8, 2, 3], [4, 5, 6]]).unwrap();
+        let mask = gray(1, 2, &[0, 1]);
+        let mut dst = Mat::from_slice(1, 2, CV_8UC3, &[[9u8; 3]; 2]).unwrap();
+        add(&a, &a, &mut dst, Some(&mask)).unwrap();
+        assert_eq!(dst.row::<[u8; 3]>(0), [[9, 9, 9], [8, 10, 12]]);
+
+        bitwise_not(&a, &mut dst, Some(&mask)).unwrap();
+        assert_eq!(dst.row::<[u8; 3]>(0), [[9, 9, 9], [251, 250, 249]]);
+        bitwise_xor(&a, &a, &mut dst, None).unwrap();
+        assert_eq!(dst.data, [0; 6]);
+
+        // 16 位按字节运算
+        let w = Mat::from_slice(1, 2, CV_16UC1, &[0x1234u16, 0xFF00]).unwrap();
+        let v = Mat::from_slice(1, 2, CV_16UC1, &[0x00FFu16, 0x0FF0]).unwrap();
+        bitwise_and(&w, &v, &mut dst, None).unwrap();
+        assert_eq!(dst.row::<u16>(0), [0x0034, 0x0F00]);
+        bitwise_or(&w, &v, &mut dst, Some(&gray(1, 2, &[255, 0]))).unwrap();
+        assert_eq!(dst.row::<u16>(0), [0x12FF, 0x0F00]);
+
+        assert!(add(&a, &a, &mut dst, Some(&a)).is_err());
+    }
+
+    #[test]
+    fn range_and_statistics() {
+        let img = Mat::from_slice(
+            2,
+            2,
+            CV_8UC3,
+            &[[0u8, 10, 20], [5, 50, 200], [6, 60, 100], [7, 70, 90]],
+        )
+        .unwrap();
+        let mut dst = Mat::empty();
+        in_range(
+            &img,
+            Scalar::new(5, 50, 90),
+            Scalar::new(10, 100, 150),
+            &mut dst,
+        )
+        .unwrap();
+        assert_eq!(
+            (dst.typ(), dst.data.as_slice()),
+            (CV_8UC1, &[0, 0, 255, 255][..])
+        );
+        assert_eq!(count_non_zero(&dst).unwrap(), 2);
+        assert!(count_non_zero(&img).is_err());
+
+        let m = mean(&img, None).unwrap();
+        assert_eq!(m, Scalar::new(4.5, 47.5, 102.5));
+        let (m, sd) = mean_std_dev(&img, Some(&dst)).unwrap();
+        assert_eq!((m.v0, m.v1, m.v2), (6.5, 65.0, 95.0));
+        assert_eq!((sd.v0, sd.v1, sd.v2), (0.5, 5.0, 5.0));
+
+        let g = gray(2, 3, &[5, 1, 9, 9, 1, 3]);
+        let (lo, hi, lo_loc, hi_loc) = min_max_loc(&g, None).unwrap();
+        assert_eq!(
+            (lo, hi, lo_loc, hi_loc),
+            (1.0, 9.0, Point::new(1, 0), Point::new(2, 0))
+        );
+        let (_, _, lo_loc, _) = min_max_loc(&g, Some(&gray(2, 3, &[0, 0, 0, 1, 0, 1]))).unwrap();
+        assert_eq!(lo_loc, Point::new(2, 1));
+
+        let h = gray(2, 3, &[5, 1, 9, 9, 1, 0]);
+        assert_eq!(norm(&g, NORM_INF, None).unwrap(), 9.0);
+        assert_eq!(norm(&g, NORM_L1, None).unwrap(), 28.0);
+        assert_eq!(norm(&g, NORM_L2SQR, None).unwrap(), 198.0);
+        assert_eq!(norm_diff(&g, &h, NORM_L2, None).unwrap(), 3.0);
+        let rel = norm_diff(&g, &h, NORM_L1 | NORM_RELATIVE, None).unwrap();
+        assert!((rel - 3.0 / 25.0).abs() < 1e-12);
+        assert!(norm(&g, NORM_L1 | NORM_RELATIVE, None).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! 通道拆分与合并 (对应 OpenCV 的 `split` / `merge` / `mixChannels` 等)
+//!
+//! 按字节复制通道值，支持所有深度与 1-4 通道，输入可以是带 `step` 的 ROI 视图。
+//! 例如从 BGRA 采集帧中取出 Alpha 平面：`extract_channel(&frame, &mut alpha, 3)`。
+
+use super::mat::{type_name, Mat};
+use super::view::{AsMatView, AsMatViewMut, MatView, MatViewMut};
+use anyhow::{anyhow, Result};
+
+/// 把多通道图像拆成单通道图像
+pub fn split<A: AsMatView + ?Sized>(src: &A) -> Vec<Mat> {
+    let src = src.view();
+    (0..src.channels as usize)
+        .map(|k| {
+            let mut plane = Mat::with_depth(src.rows, src.cols, 1, src.depth);
+            copy_channel(&src, Some(k), &mut plane.view_mut(), 0);
+            plane
+        })
+        .collect()
+}
+
+/// 把若干同尺寸、同深度的图像按顺序合并为一幅多通道图像 (总通道数不超过 4)
+pub fn merge<M: AsMatView>(mv: &[M]) -> Result<Mat> {
+    let first = mv
+        .first()
+        .ok_or_else(|| anyhow!("merge: no input images"))?
+        .view();
+    let channels: usize = mv.iter().map(|m| m.view().channels as usize).sum();
+    if channels > 4 {
+        return Err(anyhow!(
+            "merge: {} channels exceed the limit of 4",
+            channels
+        ));
+    }
+    for m in mv {
+        check_layout("merge", &first, &m.view())?;
+    }
+
+    let mut dst = Mat::with_depth(first.rows, first.cols, channels as u8, first.depth);
+    let mut to = 0;
+    for m in mv {
+        let m = m.view();
+        for k in 0..m.channels as usize {
+            copy_channel(&m, Some(k), &mut dst.view_mut(), to);
+            to += 1;
+        }
+    }
+    Ok(dst)
+}
+
+/// 按 `from_to` 把输入通道复制到输出通道 (对应 OpenCV 的 `mixChannels`)
+///
+/// 通道按所有输入 (输出) 图像依次编号，`from_to[2k]` 号输入通道复制到 `from_to[2k + 1]`
+/// 号输出通道；输入编号为负数时把输出通道填 0。`dst` 需要预先分配，
+/// 尺寸和深度与输入相同，未被写入的通道保持原值。
+///
+/// ```no_run
+/// # use rustcv::core::{mix_channels, mat::{Mat, CV_8UC1, CV_8UC3}};
+/// # let bgra = Mat::empty();
+/// // BGRA -> RGB + Alpha
+/// let mut out = [Mat::zeros(bgra.rows, bgra.cols, CV_8UC3), Mat::zeros(bgra.rows, bgra.cols, CV_8UC1)];
+/// mix_channels(&[bgra], &mut out, &[0, 2, 1, 1, 2, 0, 3, 3])?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn mix_channels<M: AsMatView>(src: &[M], dst: &mut [Mat], from_to: &[i32]) -> Result<()> {
+    let first = src
+        .first()
+        .ok_or_else(|| anyhow!("mix_channels: no input images"))?
+        .view();
+    if !from_to.len().is_multiple_of(2) {
+        return Err(anyhow!("mix_channels: from_to must contain index pairs"));
+    }
+    for m in src {
+        check_layout("mix_channels", &first, &m.view())?;
+    }
+    for m in dst.iter() {
+        check_layout("mix_channels", &first, &m.view())?;
+    }
+
+    for pair in from_to.chunks_exact(2) {
+        let (d, to) = locate(dst.iter().map(|m| m.channels), pair[1])
+            .ok_or_else(|| anyhow!("mix_channels: output channel {} out of range", pair[1]))?;
+        let from = if pair[0] < 0 {
+            None
+        } else {
+            Some(
+                locate(src.iter().map(|m| m.view().channels), pair[0]).ok_or_else(|| {
+                    anyhow!("mix_channels: input channel {} out of range", pair[0])
+                })?,
+            )
+        };
+        let mut out = dst[d].view_mut();
+        match from {
+            Some((s, k)) => copy_channel(&src[s].view(), Some(k), &mut out, to),
+            None => copy_channel(&first, None, &mut out, to),
+        }
+    }
+    Ok(())
+}
+
+/// 取出第 `coi` 个通道，`dst` 为同深度的单通道图像
+pub fn extract_channel<A: AsMatView + ?Sized>(src: &A, dst: &mut Mat, coi: i32) -> Result<()> {
+    let src = src.view();
+    check_coi("extract_channel", &src, coi)?;
+    let mut plane = Mat::with_depth(src.rows, src.cols, 1, src.depth);
+    copy_channel(&src, Some(coi as usize), &mut plane.view_mut(), 0);
+    *dst = plane;
+    Ok(())
+}
+
+/// 用单通道图像 `src` 替换 `dst` 的第 `coi` 个通道，其余通道不变
+pub fn insert_channel<A, D>(src: &A, dst: &mut D, coi: i32) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    D: AsMatViewMut + ?Sized,
+{
+    let src = src.view();
+    let mut dst = dst.view_mut();
+    check_coi("insert_channel", &dst.view(), coi)?;
+    if src.channels != 1 {
+        return Err(anyhow!(
+            "insert_channel: expected a single-channel source, got {}",
+            type_name(src.typ())
+        ));
+    }
+    check_layout("insert_channel", &dst.view(), &src)?;
+    copy_channel(&src, Some(0), &mut dst, coi as usize);
+    Ok(())
+}
+
+/// 把 `src` 的第 `from` 个通道 (为 `None` 时为 0) 写入 `dst` 的第 `to` 个通道
+fn copy_channel(src: &MatView<'_>, from: Option<usize>, dst: &mut MatViewMut<'_>, to: usize) {
+    let size = src.elem_size1();
+    let (src_px, dst_px) = (src.elem_size(), dst.elem_size());
+    for r in 0..dst.rows {
+        let s = src.row_bytes(r);
+        let d = dst.row_bytes_mut(r);
+        for (c, px) in d.chunks_exact_mut(dst_px).enumerate() {
+            let out = &mut px[to * size..(to + 1) * size];
+            match from {
+                Some(k) => out.copy_from_slice(&s[c * src_px + k * size..][..size]),
+                None => out.fill(0),
+            }
+        }
+    }
+}
+
+/// 全局通道编号 -> (图像序号, 图像内通道号)
+fn locate(channels: impl Iterator<Item = u8>, index: i32) -> Option<(usize, usize)> {
+    let mut index = usize::try_from(index).ok()?;
+    for (i, cn) in channels.enumerate() {
+        if index < cn as usize {
+            return Some((i, index));
+        }
+        index -= cn as usize;
+    }
+    None
+}
+
+fn check_layout(name: &str, expected: &MatView<'_>, m: &MatView<'_>) -> Result<()> {
+    if (m.rows, m.cols, m.depth) != (expected.rows, expected.cols, expected.depth) {
+        return Err(anyhow!(
+            "{}: expected {}x{} images of the same depth as {}, got {}x{} {}",
+            name,
+            expected.cols,
+            expected.rows,
+            type_name(expected.typ()),
+            m.cols,
+            m.rows,
+            type_name(m.typ())
+        ));
+    }
+    Ok(())
+}
+
+fn check_coi(name: &str, m: &MatView<'_>, coi: i32) -> Result<()> {
+    if coi < 0 || coi >= m.channels as i32 {
+        return Err(anyhow!(
+            "{}: channel {} out of range for {}",
+            name,
+            coi,
+            type_name(m.typ())
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16UC1, CV_16UC2, CV_8UC1, CV_8UC3, CV_8UC4};
+    use crate::core::types::Rect;
+
+    #[test]
+    fn split_and_merge_round_trip() {
+        let bgra: Vec<[u8; 4]> = (0..12).map(|i| [i, i + 100, i + 200, 255 - i]).collect();
+        let img = Mat::from_slice(3, 4, CV_8UC4, &bgra).unwrap();
+        // ROI 保留父矩阵的 step
+        let roi = img.roi(Rect::new(1, 1, 2, 2)).unwrap();
+        let planes = split(&roi);
+        assert_eq!(planes.len(), 4);
+        assert_eq!(planes[2].data, [205, 206, 209, 210]);
+        assert_eq!(planes[3].data, [250, 249, 246, 245]);
+
+        let merged = merge(&planes).unwrap();
+        assert_eq!(merged.data, roi.to_mat().data);
+
+        let bgr_a = merge(&[merge(&planes[..3]).unwrap(), planes[3].clone()]).unwrap();
+        assert_eq!(bgr_a.data, merged.data);
+
+        assert!(merge(&[merged.clone(), planes[0].clone()]).is_err());
+        assert!(merge(&[planes[0].clone(), Mat::zeros(2, 2, CV_16UC1)]).is_err());
+        assert!(merge::<Mat>(&[]).is_err());
+    }
+
+    #[test]
+    fn mix_extract_insert() {
+        let img = Mat::from_slice(1, 2, CV_8UC4, &[[1u8, 2, 3, 4], [5, 6, 7, 8]]).unwrap();
+        let mut out = [Mat::zeros(1, 2, CV_8UC3), Mat::zeros(1, 2, CV_8UC1)];
+        mix_channels(
+            std::slice::from_ref(&img),
+            &mut out,
+            &[0, 2, 1, 1, 2, 0, 3, 3, -1, 1],
+        )
+        .unwrap();
+        assert_eq!(out[0].data, [3, 0, 1, 7, 0, 5]);
+        assert_eq!(out[1].data, [4, 8]);
+        assert!(mix_channels(std::slice::from_ref(&img), &mut out, &[4, 0]).is_err());
+
+        let mut alpha = Mat::empty();
+        extract_channel(&img, &mut alpha, 3).unwrap();
+        assert_eq!((alpha.typ(), alpha.data.as_slice()), (CV_8UC1, &[4, 8][..]));
+        assert!(extract_channel(&img, &mut alpha, 4).is_err());
+
+        let mut uv = Mat::from_slice(2, 2, CV_16UC2, &[[1u16, 2]; 4]).unwrap();
+        let v = Mat::from_slice(1, 2, CV_16UC1, &[700u16, 800]).unwrap();
+        insert_channel(&v, &mut uv.roi_mut(Rect::new(0, 1, 2, 1)).unwrap(), 1).unwrap();
+        assert_eq!(uv.row::<[u16; 2]>(0), [[1, 2], [1, 2]]);
+        assert_eq!(uv.row::<[u16; 2]>(1), [[1, 700], [1, 800]]);
+        assert!(insert_channel(&alpha, &mut uv, 0).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! 与其他图像类型的互相转换
+//!
+//! - `rustcv_camera::Mat` (feature `camera`)：按值转换时直接移交像素缓冲区，不复制；
+//!   也可以直接作为 `AsMatView` / `AsMatViewMut` 传给 `imgproc`、`highgui`。
+//! - `image::ImageBuffer`：与 `imread` 一致，RGB(A) 与 BGR(A) 之间交换通道，会复制数据。
+//! - `ndarray::ArrayView3` (feature `ndarray`)：形状为 `(rows, cols, channels)`，
+//!   与 Mat 共享同一块内存。
+
+use super::mat::{cv_make_type, type_name, DataType, Mat};
+use super::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+use image::{ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgba};
+use std::ops::Deref;
+
+// --- image::ImageBuffer ---
+
+macro_rules! impl_image_buffer {
+    ($($pixel:ident<$t:ty>),* $(,)?) => {$(
+        impl<C: Deref<Target = [$t]>> From<&ImageBuffer<$pixel<$t>, C>> for Mat {
+            fn from(img: &ImageBuffer<$pixel<$t>, C>) -> Self {
+                let (cols, rows) = img.dimensions();
+                from_samples(rows, cols, <$pixel<$t>>::CHANNEL_COUNT, img.as_raw())
+            }
+        }
+
+        impl TryFrom<&Mat> for ImageBuffer<$pixel<$t>, Vec<$t>> {
+            type Error = anyhow::Error;
+
+            fn try_from(mat: &Mat) -> Result<Self> {
+                Self::try_from(mat.view())
+            }
+        }
+
+        impl TryFrom<MatView<'_>> for ImageBuffer<$pixel<$t>, Vec<$t>> {
+            type Error = anyhow::Error;
+
+            fn try_from(view: MatView<'_>) -> Result<Self> {
+                let samples = to_samples::<$t>(view, <$pixel<$t>>::CHANNEL_COUNT)?;
+                ImageBuffer::from_raw(view.cols as u32, view.rows as u32, samples)
+                    .ok_or_else(|| anyhow!("Sample count does not match the image size"))
+            }
+        }
+    )*};
+}
+
+impl_image_buffer!(
+    Luma<u8>,
+    LumaA<u8>,
+    Rgb<u8>,
+    Rgba<u8>,
+    Luma<u16>,
+    LumaA<u16>,
+    Rgb<u16>,
+    Rgba<u16>,
+    Luma<f32>,
+    LumaA<f32>,
+    Rgb<f32>,
+    Rgba<f32>,
+);
+
+fn from_samples<T: DataType>(rows: u32, cols: u32, channels: u8, samples: &[T]) -> Mat {
+    let mut mat = Mat::zeros(rows as i32, cols as i32, cv_make_type(T::DEPTH, channels));
+    let dst: &mut [T] = bytemuck::cast_slice_mut(&mut mat.data);
+    dst.copy_from_slice(&samples[..dst.len()]);
+    if channels >= 3 {
+        for px in dst.chunks_exact_mut(channels as usize) {
+            px.swap(0, 2);
+        }
+    }
+    mat
+}
+
+fn to_samples<T: DataType>(view: MatView<'_>, channels: u8) -> Result<Vec<T>> {
+    let typ = cv_make_type(T::DEPTH, channels);
+    if view.typ() != typ {
+        return Err(anyhow!(
+            "Cannot convert a {} Mat into a {} image buffer",
+            type_name(view.typ()),
+            type_name(typ)
+        ));
+    }
+    let mut samples =
+        Vec::with_capacity(view.rows as usize * view.cols as usize * channels as usize);
+    for r in 0..view.rows {
+        samples.extend_from_slice(view.row::<T>(r));
+    }
+    if channels >= 3 {
+        for px in samples.chunks_exact_mut(channels as usize) {
+            px.swap(0, 2);
+        }
+    }
+    Ok(samples)
+}
+
+// --- rustcv_camera::Mat ---
+
+#[cfg(feature = "camera")]
+mod camera {
+    use super::*;
+    use crate::core::mat::{CV_8U, CV_8UC3};
+    use crate::core::view::{AsMatViewMut, MatViewMut};
+
+    /// 接管相机帧的 BGR 缓冲区，不复制像素
+    impl From<rustcv_camera::Mat> for Mat {
+        fn from(frame: rustcv_camera::Mat) -> Self {
+            let (rows, cols, step) = (frame.rows() as i32, frame.cols() as i32, frame.step());
+            Mat {
+                data: frame.into_vec().into(),
+                rows,
+                cols,
+                step,
+                channels: 3,
+                depth: CV_8U,
+            }
+        }
+    }
+
+    /// 仅支持 `CV_8UC3`。缓冲区未被共享且没有行尾 Padding 时不复制像素
+    impl TryFrom<Mat> for rustcv_camera::Mat {
+        type Error = anyhow::Error;
+
+        fn try_from(mat: Mat) -> Result<Self> {
+            if mat.typ() != CV_8UC3 {
+                return Err(anyhow!(
+                    "rustcv_camera::Mat only holds CV_8UC3 images, got {}",
+                    type_name(mat.typ())
+                ));
+            }
+            let (rows, cols) = (mat.rows as u32, mat.cols as u32);
+            let mat = if mat.step == mat.cols as usize * 3 {
+                mat
+            } else {
+                mat.view().to_mat()
+            };
+            let mut data = mat.data.into_vec();
+            data.truncate(rows as usize * cols as usize * 3);
+            rustcv_camera::Mat::from_bgr(rows, cols, data)
+                .ok_or_else(|| anyhow!("Mat buffer is smaller than {}x{}x3", cols, rows))
+        }
+    }
+
+    impl AsMatView for rustcv_camera::Mat {
+        fn view(&self) -> MatView<'_> {
+            MatView {
+                data: self.data(),
+                rows: self.rows() as i32,
+                cols: self.cols() as i32,
+                step: self.step(),
+                channels: 3,
+                depth: CV_8U,
+            }
+        }
+    }
+
+    impl AsMatViewMut for rustcv_camera::Mat {
+        fn view_mut(&mut self) -> MatViewMut<'_> {
+            let (rows, cols, step) = (self.rows() as i32, self.cols() as i32, self.step());
+            MatViewMut {
+                data: self.data_mut(),
+                rows,
+                cols,
+                step,
+                channels: 3,
+                depth: CV_8U,
+            }
+        }
+    }
+}
+
+// --- ndarray::ArrayView3 ---
+
+#[cfg(feature = "ndarray")]
+mod array {
+    use super::*;
+    use crate::core::mat::{CV_16S, CV_16U, CV_32F, CV_8U};
+    use ndarray::{ArrayView3, ShapeBuilder};
+
+    impl<'a> MatView<'a> {
+        /// 以 `(rows, cols, channels)` 形状的数组视图访问像素，不复制数据
+        ///
+        /// `T` 为单个通道值的类型 (`u8` / `u16` / `f32`)，必须与 Mat 的深度一致。
+        pub fn as_array<T: DataType>(&self) -> Result<ArrayView3<'a, T>> {
+            let size = std::mem::size_of::<T>();
+            if T::DEPTH != self.depth || T::CHANNELS != 1 {
+                return Err(anyhow!(
+                    "Element type {} does not match Mat type {}",
+                    type_name(cv_make_type(T::DEPTH, T::CHANNELS)),
+                    type_name(self.typ())
+                ));
+            }
+            if !self.step.is_multiple_of(size) {
+                return Err(anyhow!(
+                    "Row step {} is not a multiple of the sample size",
+                    self.step
+                ));
+            }
+            let samples: &'a [T] = bytemuck::try_cast_slice(self.data)
+                .map_err(|e| anyhow!("Mat data cannot be viewed as samples: {:?}", e))?;
+            let cn = self.channels as usize;
+            let shape = (self.rows as usize, self.cols as usize, cn);
+            ArrayView3::from_shape(shape.strides((self.step / size, cn, 1)), samples)
+                .map_err(|e| anyhow!("Invalid array layout: {}", e))
+        }
+    }
+
+    impl<'a, T: DataType> TryFrom<&'a Mat> for ArrayView3<'a, T> {
+        type Error = anyhow::Error;
+
+        fn try_from(mat: &'a Mat) -> Result<Self> {
+            mat.view().as_array()
+        }
+    }
+
+    /// 要求数组为标准 (C 连续) 布局，最后一维为通道，不复制数据
+    impl<'a, T: DataType> TryFrom<ArrayView3<'a, T>> for MatView<'a> {
+        type Error = anyhow::Error;
+
+        fn try_from(array: ArrayView3<'a, T>) -> Result<Self> {
+            let (rows, cols, cn) = array.dim();
+            let depth = sample_depth::<T>(cn)?;
+            let samples = array
+                .to_slice()
+                .ok_or_else(|| anyhow!("Array is not in standard layout"))?;
+            Ok(MatView {
+                data: bytemuck::cast_slice(samples),
+                rows: rows as i32,
+                cols: cols as i32,
+                step: cols * cn * std::mem::size_of::<T>(),
+                channels: cn as u8,
+                depth,
+            })
+        }
+    }
+
+    /// 复制为紧凑排列的 Mat，数组可以是任意布局
+    impl<T: DataType> TryFrom<ArrayView3<'_, T>> for Mat {
+        type Error = anyhow::Error;
+
+        fn try_from(array: ArrayView3<'_, T>) -> Result<Self> {
+            let array = array.as_standard_layout();
+            MatView::try_from(array.view()).map(|view| view.to_mat())
+        }
+    }
+
+    fn sample_depth<T: DataType>(channels: usize) -> Result<i32> {
+        match (T::DEPTH, T::CHANNELS) {
+            (CV_8U | CV_16U | CV_16S | CV_32F, 1) if (1..=4).contains(&channels) => Ok(T::DEPTH),
+            _ => Err(anyhow!(
+                "Cannot build a Mat from {} samples with {} channels",
+                type_name(cv_make_type(T::DEPTH, T::CHANNELS)),
+                channels
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16UC3, CV_8UC3, CV_8UC4};
+    use image::{RgbImage, RgbaImage};
+
+    #[test]
+    fn image_buffer_round_trip() {
+        let img = RgbImage::from_fn(3, 2, |x, y| Rgb([x as u8, y as u8, 200]));
+        let mat = Mat::from(&img);
+        assert_eq!((mat.rows, mat.cols, mat.typ()), (2, 3, CV_8UC3));
+        assert_eq!(*mat.at::<[u8; 3]>(1, 2), [200, 1, 2]);
+        assert_eq!(RgbImage::try_from(&mat).unwrap(), img);
+
+        // 16 位与 ROI
+        let wide =
+            ImageBuffer::<Rgb<u16>, _>::from_fn(4, 4, |x, y| Rgb([x as u16 * 1000, y as u16, 7]));
+        let mat = Mat::from(&wide);
+        assert_eq!(mat.typ(), CV_16UC3);
+        let roi = mat.roi(crate::core::types::Rect::new(1, 2, 2, 2)).unwrap();
+        let part = ImageBuffer::<Rgb<u16>, Vec<u16>>::try_from(roi).unwrap();
+        assert_eq!(*part.get_pixel(1, 0), Rgb([2000, 2, 7]));
+
+        assert!(RgbaImage::try_from(&mat).is_err());
+        assert_eq!(Mat::from(&RgbaImage::new(1, 1)).typ(), CV_8UC4);
+    }
+
+    #[cfg(feature = "camera")]
+    #[test]
+    fn camera_mat_moves_without_copy() {
+        let frame = rustcv_camera::Mat::from_bgr(2, 2, (0..12).collect()).unwrap();
+        let ptr = frame.data().as_ptr();
+        assert_eq!(*frame.view().at::<[u8; 3]>(1, 0), [6, 7, 8]);
+
+        let mat = Mat::from(frame);
+        assert_eq!(mat.data.as_ptr(), ptr);
+        let frame = rustcv_camera::Mat::try_from(mat).unwrap();
+        assert_eq!(frame.data().as_ptr(), ptr);
+
+        assert!(rustcv_camera::Mat::try_from(Mat::zeros(2, 2, CV_8UC4)).is_err());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn array_views_share_memory() {
+        use crate::core::mat::CV_16UC1;
+        use ndarray::{Array3, ArrayView3};
+
+        let mat = Mat::from_slice(2, 3, CV_16UC1, &[1u16, 2, 3, 4, 5, 6]).unwrap();
+        let array = ArrayView3::<u16>::try_from(&mat).unwrap();
+        assert_eq!(array.dim(), (2, 3, 1));
+        assert_eq!(array[[1, 2, 0]], 6);
+        assert!(ArrayView3::<u8>::try_from(&mat).is_err());
+
+        // ROI 视图保留父矩阵的行步长
+        let roi = mat.roi(crate::core::types::Rect::new(1, 0, 2, 2)).unwrap();
+        assert_eq!(roi.as_array::<u16>().unwrap()[[1, 0, 0]], 5);
+
+        let owned = Array3::from_shape_fn((2, 2, 3), |(r, c, k)| (r * 100 + c * 10 + k) as f32);
+        let view = MatView::try_from(owned.view()).unwrap();
+        assert_eq!(view.data.as_ptr(), owned.as_ptr() as *const u8);
+        assert_eq!(*view.at::<[f32; 3]>(1, 1), [110.0, 111.0, 112.0]);
+
+        // 非标准布局时复制
+        let mat = Mat::try_from(owned.view().reversed_axes()).unwrap();
+        assert_eq!((mat.rows, mat.cols, mat.channels), (3, 2, 2));
+        assert_eq!(*mat.at::<[f32; 2]>(2, 1), [12.0, 112.0]);
+    }
+}
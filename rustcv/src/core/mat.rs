@@ -1,40 +1,501 @@
+use anyhow::{anyhow, Result};
+use bytemuck::Pod;
 use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// 元素深度：8 位无符号 (对应 OpenCV 的 `CV_8U`)
+pub const CV_8U: i32 = 0;
+/// 元素深度：16 位无符号，按本机字节序存放 (对应 OpenCV 的 `CV_16U`)
+pub const CV_16U: i32 = 2;
+/// 元素深度：16 位有符号，按本机字节序存放 (对应 OpenCV 的 `CV_16S`)，常用于 Sobel 等导数结果
+pub const CV_16S: i32 = 3;
+/// 元素深度：32 位浮点 (对应 OpenCV 的 `CV_32F`)
+pub const CV_32F: i32 = 5;
+
+/// 由深度和通道数组合出类型 (对应 OpenCV 的 `CV_MAKETYPE`)
+pub const fn cv_make_type(depth: i32, channels: u8) -> i32 {
+    depth + ((channels as i32 - 1) << 3)
+}
+
+pub const CV_8UC1: i32 = cv_make_type(CV_8U, 1);
+pub const CV_8UC2: i32 = cv_make_type(CV_8U, 2);
+pub const CV_8UC3: i32 = cv_make_type(CV_8U, 3);
+pub const CV_8UC4: i32 = cv_make_type(CV_8U, 4);
+pub const CV_16UC1: i32 = cv_make_type(CV_16U, 1);
+pub const CV_16UC2: i32 = cv_make_type(CV_16U, 2);
+pub const CV_16UC3: i32 = cv_make_type(CV_16U, 3);
+pub const CV_16UC4: i32 = cv_make_type(CV_16U, 4);
+pub const CV_16SC1: i32 = cv_make_type(CV_16S, 1);
+pub const CV_16SC2: i32 = cv_make_type(CV_16S, 2);
+pub const CV_16SC3: i32 = cv_make_type(CV_16S, 3);
+pub const CV_16SC4: i32 = cv_make_type(CV_16S, 4);
+pub const CV_32FC1: i32 = cv_make_type(CV_32F, 1);
+pub const CV_32FC2: i32 = cv_make_type(CV_32F, 2);
+pub const CV_32FC3: i32 = cv_make_type(CV_32F, 3);
+pub const CV_32FC4: i32 = cv_make_type(CV_32F, 4);
+
+/// 可以从 Mat 中按类型读取的元素：单个通道值 (`u8` / `u16` / `f32`)
+/// 或一个完整像素 (`[u8; 3]` 等，对应 OpenCV 的 `Vec3b`)
+pub trait DataType: Pod {
+    const DEPTH: i32;
+    const CHANNELS: u8;
+}
+
+macro_rules! impl_data_type {
+    ($($t:ty => $depth:expr),*) => {$(
+        impl DataType for $t {
+            const DEPTH: i32 = $depth;
+            const CHANNELS: u8 = 1;
+        }
+        impl<const N: usize> DataType for [$t; N]
+        where
+            [$t; N]: Pod,
+        {
+            const DEPTH: i32 = $depth;
+            const CHANNELS: u8 = N as u8;
+        }
+    )*};
+}
+
+impl_data_type!(u8 => CV_8U, u16 => CV_16U, i16 => CV_16S, f32 => CV_32F);
+
+/// 单个通道值的类型 (`u8` / `u16` / `f32`)，逐元素运算按深度实例化泛型实现
+pub(crate) trait Sample: DataType + PartialOrd {
+    fn to_f64(self) -> f64;
+    /// 饱和转换：整数类型四舍五入 (0.5 取偶) 并截断到取值范围
+    fn from_f64(v: f64) -> Self;
+}
+
+impl Sample for u8 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(v: f64) -> Self {
+        saturate(v, u8::MAX as f64) as u8
+    }
+}
+
+impl Sample for u16 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(v: f64) -> Self {
+        saturate(v, u16::MAX as f64) as u16
+    }
+}
+
+impl Sample for i16 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(v: f64) -> Self {
+        if v.is_nan() {
+            0
+        } else {
+            v.round_ties_even().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+        }
+    }
+}
+
+impl Sample for f32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+/// 按深度调用泛型函数：`dispatch_depth!(mat.depth, func(args...))` 展开为
+/// `func::<u8>(args...)` / `func::<u16>(args...)` / `func::<i16>(args...)` / `func::<f32>(args...)`
+macro_rules! dispatch_depth {
+    ($depth:expr, $func:ident($($arg:expr),* $(,)?)) => {
+        match $depth {
+            $crate::core::mat::CV_8U => $func::<u8>($($arg),*),
+            $crate::core::mat::CV_16U => $func::<u16>($($arg),*),
+            $crate::core::mat::CV_16S => $func::<i16>($($arg),*),
+            _ => $func::<f32>($($arg),*),
+        }
+    };
+}
+
+/// Mat / MatView / MatViewMut 共用的只读访问方法
+///
+/// 要求类型有 `data` (可按字节区间索引)、`rows`、`cols`、`step`、`channels`、`depth` 字段。
+macro_rules! impl_read_access {
+    () => {
+        /// 类型 (深度 + 通道数)，如 `CV_8UC3`
+        pub fn typ(&self) -> i32 {
+            $crate::core::mat::cv_make_type(self.depth, self.channels)
+        }
+
+        /// 单个通道值占用的字节数
+        pub fn elem_size1(&self) -> usize {
+            $crate::core::mat::depth_size(self.depth)
+        }
+
+        /// 单个像素占用的字节数
+        pub fn elem_size(&self) -> usize {
+            self.elem_size1() * self.channels as usize
+        }
+
+        /// 获取像素数据的切片 (考虑 Stride)
+        pub fn row_bytes(&self, row: i32) -> &[u8] {
+            let start = (row as usize) * self.step;
+            let end = start + self.cols as usize * self.elem_size();
+            &self.data[start..end] // 注意：这里我们忽略了行尾的 Padding
+        }
+
+        /// 按类型获取一行：`row::<u16>` 得到所有通道值，`row::<[u8; 3]>` 得到像素
+        ///
+        /// # Panics
+        /// 类型与 Mat 不符或行号越界时 panic
+        pub fn row<T: $crate::core::mat::DataType>(&self, row: i32) -> &[T] {
+            self.check_type::<T>(false);
+            bytemuck::cast_slice(self.row_bytes(row))
+        }
+
+        /// 按类型访问一个像素，单通道用标量类型 (`at::<f32>`)，多通道用数组 (`at::<[u8; 3]>`)
+        ///
+        /// # Panics
+        /// 类型与 Mat 不符或坐标越界时 panic
+        pub fn at<T: $crate::core::mat::DataType>(&self, row: i32, col: i32) -> &T {
+            self.check_type::<T>(true);
+            &self.row::<T>(row)[col as usize]
+        }
+
+        /// 借用一块矩形区域 (零拷贝，与本矩阵共享数据和 Stride)
+        pub fn roi(
+            &self,
+            rect: $crate::core::types::Rect,
+        ) -> anyhow::Result<$crate::core::view::MatView<'_>> {
+            let range = $crate::core::view::roi_range(
+                rect,
+                self.rows,
+                self.cols,
+                self.step,
+                self.elem_size(),
+            )?;
+            Ok($crate::core::view::MatView {
+                data: &self.data[range],
+                rows: rect.height,
+                cols: rect.width,
+                step: self.step,
+                channels: self.channels,
+                depth: self.depth,
+            })
+        }
+
+        /// 转换深度：`dst = saturate(self * alpha + beta)` (对应 OpenCV 的 `convertTo`)
+        ///
+        /// `depth` 为负数时保持原深度。转为整数时四舍五入 (0.5 取偶) 并截断到取值范围。
+        pub fn convert_to(
+            &self,
+            dst: &mut $crate::core::mat::Mat,
+            depth: i32,
+            alpha: f64,
+            beta: f64,
+        ) -> anyhow::Result<()> {
+            use $crate::core::mat::{saturate, Mat, Sample, CV_16S, CV_16U, CV_32F, CV_8U};
+            let depth = if depth < 0 { self.depth } else { depth };
+            if !matches!(depth, CV_8U | CV_16U | CV_16S | CV_32F) {
+                return Err(anyhow::anyhow!("Unsupported depth: {}", depth));
+            }
+
+            let mut out = Mat::with_depth(self.rows, self.cols, self.channels, depth);
+            let mut values = Vec::with_capacity(self.cols as usize * self.channels as usize);
+            for r in 0..self.rows {
+                values.clear();
+                match self.depth {
+                    CV_8U => values.extend(self.row_bytes(r).iter().map(|&v| v as f64)),
+                    CV_16U => values.extend(self.row::<u16>(r).iter().map(|&v| v as f64)),
+                    CV_16S => values.extend(self.row::<i16>(r).iter().map(|&v| v as f64)),
+                    CV_32F => values.extend(self.row::<f32>(r).iter().map(|&v| v as f64)),
+                    d => return Err(anyhow::anyhow!("Unsupported depth: {}", d)),
+                }
+
+                let values = values.iter().map(|&v| v * alpha + beta);
+                match depth {
+                    CV_8U => {
+                        for (d, v) in out.row_bytes_mut(r).iter_mut().zip(values) {
+                            *d = saturate(v, u8::MAX as f64) as u8;
+                        }
+                    }
+                    CV_16U => {
+                        for (d, v) in out.row_mut::<u16>(r).iter_mut().zip(values) {
+                            *d = saturate(v, u16::MAX as f64) as u16;
+                        }
+                    }
+                    CV_16S => {
+                        for (d, v) in out.row_mut::<i16>(r).iter_mut().zip(values) {
+                            *d = i16::from_f64(v);
+                        }
+                    }
+                    _ => {
+                        for (d, v) in out.row_mut::<f32>(r).iter_mut().zip(values) {
+                            *d = v as f32;
+                        }
+                    }
+                }
+            }
+            *dst = out;
+            Ok(())
+        }
+
+        fn check_type<T: $crate::core::mat::DataType>(&self, pixel: bool) {
+            use $crate::core::mat::{cv_make_type, type_name};
+            assert!(
+                T::DEPTH == self.depth
+                    && (T::CHANNELS == self.channels || (!pixel && T::CHANNELS == 1)),
+                "Element type {} does not match Mat type {}",
+                type_name(cv_make_type(T::DEPTH, T::CHANNELS)),
+                type_name(self.typ())
+            );
+        }
+    };
+}
+
+/// Mat / MatViewMut 共用的可写访问方法
+macro_rules! impl_write_access {
+    () => {
+        /// 获取可写的像素数据切片 (不含行尾 Padding)
+        pub fn row_bytes_mut(&mut self, row: i32) -> &mut [u8] {
+            let start = (row as usize) * self.step;
+            let end = start + self.cols as usize * self.elem_size();
+            &mut self.data[start..end]
+        }
+
+        pub fn row_mut<T: $crate::core::mat::DataType>(&mut self, row: i32) -> &mut [T] {
+            self.check_type::<T>(false);
+            bytemuck::cast_slice_mut(self.row_bytes_mut(row))
+        }
+
+        pub fn at_mut<T: $crate::core::mat::DataType>(&mut self, row: i32, col: i32) -> &mut T {
+            self.check_type::<T>(true);
+            &mut self.row_mut::<T>(row)[col as usize]
+        }
+
+        /// 可写地借用一块矩形区域 (零拷贝，写入直接反映到本矩阵)
+        pub fn roi_mut(
+            &mut self,
+            rect: $crate::core::types::Rect,
+        ) -> anyhow::Result<$crate::core::view::MatViewMut<'_>> {
+            let range = $crate::core::view::roi_range(
+                rect,
+                self.rows,
+                self.cols,
+                self.step,
+                self.elem_size(),
+            )?;
+            Ok($crate::core::view::MatViewMut {
+                data: &mut self.data[range],
+                rows: rect.height,
+                cols: rect.width,
+                step: self.step,
+                channels: self.channels,
+                depth: self.depth,
+            })
+        }
+    };
+}
+
+pub(crate) use {dispatch_depth, impl_read_access, impl_write_access};
+
+/// Mat 的像素缓冲
+///
+/// 多个 Mat 通过引用计数共享同一块内存，`clone` 不复制像素；
+/// 可变访问时若缓冲仍被共享，先复制一份再写 (Copy-On-Write)，因此不会影响其他持有者。
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct MatData(Arc<Vec<u8>>);
+
+impl MatData {
+    /// 是否还有其他 Mat 共享这块缓冲
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    /// 两者是否指向同一块缓冲
+    pub fn ptr_eq(&self, other: &MatData) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// 可写切片 (被共享时先复制)
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        Arc::<Vec<u8>>::make_mut(&mut self.0)
+    }
+
+    /// 取出 Vec，未被共享时不复制
+    pub fn into_vec(self) -> Vec<u8> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl Deref for MatData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for MatData {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl From<Vec<u8>> for MatData {
+    fn from(data: Vec<u8>) -> Self {
+        Self(Arc::new(data))
+    }
+}
+
+impl FromIterator<u8> for MatData {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<u8>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a MatData {
+    type Item = &'a u8;
+    type IntoIter = std::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl fmt::Debug for MatData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl PartialEq<[u8]> for MatData {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl PartialEq<Vec<u8>> for MatData {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for MatData {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
 
 /// OpenCV-like Matrix structure.
-/// Owns its data (Vec<u8>) and supports strided memory layout.
+/// Shares its data (reference-counted, copy-on-write) and supports strided memory layout.
+///
+/// 与 OpenCV 一样，`clone` (赋值) 只增加引用计数，适合把同一帧分发给多个线程；
+/// 需要独立副本时用 `deep_clone`。
+///
+/// 多字节深度的元素按本机字节序存放。`step` 需是单个通道值大小的整数倍，
+/// 按类型访问 (`at` / `row`) 时要求数据按元素对齐 (全局分配器分配的内存总是满足)。
 #[derive(Clone)]
 pub struct Mat {
-    pub data: Vec<u8>,
+    pub data: MatData,
     pub rows: i32,
     pub cols: i32,
     /// 每一行占用的字节数 (Stride)
-    /// 对于 Packed 图像，step = cols * channels
-    /// 对于 Padded 图像，step > cols * channels
+    /// 对于 Packed 图像，step = cols * channels * 元素字节数
+    /// 对于 Padded 图像，step 更大
     pub step: usize,
     pub channels: u8,
+    /// 元素深度 (`CV_8U` / `CV_16U` / `CV_16S` / `CV_32F`)
+    pub depth: i32,
 }
 
 impl Mat {
     pub fn new(rows: i32, cols: i32, channels: u8) -> Self {
-        let step = (cols * channels as i32) as usize;
+        Self::with_depth(rows, cols, channels, CV_8U)
+    }
+
+    /// 创建指定深度的 Mat，例如 16 位深度图 `Mat::with_depth(h, w, 1, CV_16U)`
+    pub fn with_depth(rows: i32, cols: i32, channels: u8, depth: i32) -> Self {
+        let step = cols as usize * channels as usize * depth_size(depth);
         let size = (rows as usize) * step;
         Self {
-            data: vec![0; size],
+            data: vec![0; size].into(),
             rows,
             cols,
             step,
             channels,
+            depth,
         }
     }
 
+    /// 创建全 0 的 Mat，`typ` 为 `CV_8UC3` 等类型
+    ///
+    /// # Panics
+    /// `typ` 不是受支持的类型时 panic
+    pub fn zeros(rows: i32, cols: i32, typ: i32) -> Self {
+        let (depth, channels) = split_type(typ);
+        Self::with_depth(rows, cols, channels, depth)
+    }
+
+    /// 创建每个像素第一个通道为 1、其余通道为 0 的 Mat (与 OpenCV 一致)
+    pub fn ones(rows: i32, cols: i32, typ: i32) -> Self {
+        let mut mat = Self::zeros(rows, cols, typ);
+        let (cn, size) = (mat.channels as usize, mat.elem_size1());
+        let one: &[u8] = match mat.depth {
+            CV_8U => &[1],
+            CV_16U => &const { 1u16.to_ne_bytes() },
+            CV_16S => &const { 1i16.to_ne_bytes() },
+            _ => &const { 1f32.to_ne_bytes() },
+        };
+        for px in mat.data.chunks_exact_mut(cn * size) {
+            px[..size].copy_from_slice(one);
+        }
+        mat
+    }
+
+    /// 复制一段紧凑排列的数据，例如 `Mat::from_slice(h, w, CV_16UC1, &depth)`
+    ///
+    /// `data` 可以是通道值 (`&[u16]`) 也可以是像素 (`&[[u8; 3]]`)，深度必须与 `typ` 一致。
+    pub fn from_slice<T: DataType>(rows: i32, cols: i32, typ: i32, data: &[T]) -> Result<Self> {
+        let (depth, channels) = split_type(typ);
+        if T::DEPTH != depth || (T::CHANNELS != 1 && T::CHANNELS != channels) {
+            return Err(anyhow!(
+                "Element type does not match Mat type {}",
+                type_name(typ)
+            ));
+        }
+        let mut mat = Self::with_depth(rows, cols, channels, depth);
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        if bytes.len() != mat.data.len() {
+            return Err(anyhow!(
+                "Expected {} bytes for a {}x{} {} Mat, got {}",
+                mat.data.len(),
+                cols,
+                rows,
+                type_name(typ),
+                bytes.len()
+            ));
+        }
+        mat.data.copy_from_slice(bytes);
+        Ok(mat)
+    }
+
     /// 创建一个空的 Mat (通常用于作为输出 buffer)
     pub fn empty() -> Self {
         Self {
-            data: vec![],
+            data: MatData::default(),
             rows: 0,
             cols: 0,
             step: 0,
             channels: 0,
+            depth: CV_8U,
         }
     }
 
@@ -43,14 +504,31 @@ impl Mat {
         self.data.is_empty() || self.rows == 0 || self.cols == 0
     }
 
-    /// 获取像素数据的切片 (考虑 Stride)
-    pub fn row_bytes(&self, row: i32) -> &[u8] {
-        let start = (row as usize) * self.step;
-        let end = start + (self.cols as usize * self.channels as usize);
-        &self.data[start..end] // 注意：这里我们忽略了行尾的 Padding
+    /// 按需分配 (对应 OpenCV 的 `Mat::create`)
+    ///
+    /// 尺寸和类型与当前一致时保留现有缓冲区，否则重新分配为全 0。
+    ///
+    /// # Panics
+    /// `typ` 不是受支持的类型时 panic
+    pub fn create(&mut self, rows: i32, cols: i32, typ: i32) {
+        let step = cols as usize * depth_size(typ & 7) * ((typ >> 3) + 1) as usize;
+        if (self.rows, self.cols, self.typ(), self.step) != (rows, cols, typ, step)
+            || self.data.len() != rows as usize * step
+        {
+            *self = Self::zeros(rows, cols, typ);
+        }
+    }
+
+    /// 复制出一个不共享缓冲的 Mat (对应 OpenCV 的 `Mat::clone`)
+    pub fn deep_clone(&self) -> Mat {
+        Mat {
+            data: self.data.to_vec().into(),
+            ..self.clone()
+        }
     }
 
-    // TODO: 实现 row_bytes_mut, at<T> 等
+    impl_read_access!();
+    impl_write_access!();
 }
 
 impl fmt::Debug for Mat {
@@ -59,7 +537,143 @@ impl fmt::Debug for Mat {
             .field("rows", &self.rows)
             .field("cols", &self.cols)
             .field("channels", &self.channels)
+            .field("depth", &self.depth)
             .field("step", &self.step)
             .finish()
     }
 }
+
+pub(crate) fn depth_size(depth: i32) -> usize {
+    match depth {
+        CV_16U | CV_16S => 2,
+        CV_32F => 4,
+        _ => 1,
+    }
+}
+
+/// 类型 -> (深度, 通道数)
+fn split_type(typ: i32) -> (i32, u8) {
+    let (depth, channels) = (typ & 7, (typ >> 3) + 1);
+    assert!(
+        matches!(depth, CV_8U | CV_16U | CV_16S | CV_32F) && (1..=4).contains(&channels),
+        "Unsupported Mat type: {}",
+        typ
+    );
+    (depth, channels as u8)
+}
+
+/// 如 `CV_8UC3`，用于错误信息
+pub(crate) fn type_name(typ: i32) -> String {
+    let depth = match typ & 7 {
+        CV_8U => "8U",
+        CV_16U => "16U",
+        CV_16S => "16S",
+        CV_32F => "32F",
+        _ => "?",
+    };
+    format!("CV_{}C{}", depth, (typ >> 3) + 1)
+}
+
+/// 四舍五入 (0.5 取偶，与 OpenCV 的 `saturate_cast` 一致) 并截断到 [0, max]，NaN 变为 0
+pub(crate) fn saturate(v: f64, max: f64) -> f64 {
+    if v.is_nan() {
+        0.0
+    } else {
+        v.round_ties_even().clamp(0.0, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_and_constructors() {
+        assert_eq!((CV_8UC1, CV_8UC3, CV_16UC1, CV_32FC4), (0, 16, 2, 29));
+
+        let mat = Mat::zeros(2, 3, CV_32FC3);
+        assert_eq!((mat.depth, mat.channels, mat.step), (CV_32F, 3, 36));
+        assert_eq!(mat.typ(), CV_32FC3);
+
+        let ones = Mat::ones(2, 2, CV_16UC2);
+        assert_eq!(*ones.at::<[u16; 2]>(1, 1), [1, 0]);
+
+        let depth = Mat::from_slice(2, 2, CV_16UC1, &[1u16, 2, 3, 65535]).unwrap();
+        assert_eq!(*depth.at::<u16>(1, 1), 65535);
+        let bgr = Mat::from_slice(1, 2, CV_8UC3, &[[1u8, 2, 3], [4, 5, 6]]).unwrap();
+        assert_eq!(bgr.row_bytes(0), [1, 2, 3, 4, 5, 6]);
+        assert!(Mat::from_slice(2, 2, CV_16UC1, &[0u8; 8]).is_err());
+        assert!(Mat::from_slice(2, 2, CV_16UC1, &[0u16; 3]).is_err());
+    }
+
+    #[test]
+    fn typed_access() {
+        let mut mat = Mat::zeros(3, 4, CV_8UC3);
+        *mat.at_mut::<[u8; 3]>(2, 1) = [10, 20, 30];
+        mat.row_bytes_mut(0)[0] = 7;
+        assert_eq!(mat.row::<u8>(2)[3..6], [10, 20, 30]);
+        assert_eq!(mat.row::<[u8; 3]>(0)[0], [7, 0, 0]);
+
+        let mut f = Mat::zeros(2, 2, CV_32FC1);
+        *f.at_mut::<f32>(1, 0) = -1.5;
+        assert_eq!(f.row::<f32>(1), [-1.5, 0.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match Mat type CV_8UC3")]
+    fn at_rejects_wrong_type() {
+        Mat::zeros(1, 1, CV_8UC3).at::<u8>(0, 0);
+    }
+
+    #[test]
+    fn convert_to_saturates() {
+        let src = Mat::from_slice(1, 4, CV_32FC1, &[-3.0f32, 2.5, 3.5, 300.0]).unwrap();
+        let mut dst = Mat::empty();
+        src.convert_to(&mut dst, CV_8U, 1.0, 0.0).unwrap();
+        assert_eq!(dst.data, [0, 2, 4, 255]);
+
+        src.convert_to(&mut dst, CV_16U, 1000.0, 0.0).unwrap();
+        assert_eq!(dst.row::<u16>(0), [0, 2500, 3500, 65535]);
+        src.convert_to(&mut dst, CV_16S, -1000.0, 0.0).unwrap();
+        assert_eq!(dst.row::<i16>(0), [3000, -2500, -3500, -32768]);
+
+        let bytes = Mat::from_slice(1, 2, CV_8UC1, &[0u8, 255]).unwrap();
+        bytes
+            .convert_to(&mut dst, CV_32F, 1.0 / 255.0, -0.5)
+            .unwrap();
+        assert_eq!(dst.row::<f32>(0), [-0.5, 0.5]);
+        bytes.convert_to(&mut dst, -1, 2.0, 1.0).unwrap();
+        assert_eq!((dst.depth, &dst.data[..]), (CV_8U, &[1u8, 255][..]));
+    }
+
+    #[test]
+    fn clones_share_until_written() {
+        let mut a = Mat::zeros(2, 2, CV_8UC1);
+        let b = a.clone();
+        assert!(a.data.ptr_eq(&b.data) && a.data.is_shared());
+
+        // 写入时复制，b 不受影响
+        *a.at_mut::<u8>(0, 0) = 7;
+        assert!(!a.data.ptr_eq(&b.data));
+        assert_eq!((a.data[0], b.data[0]), (7, 0));
+        assert!(!a.data.is_shared());
+
+        let c = a.deep_clone();
+        assert!(!c.data.ptr_eq(&a.data));
+        assert_eq!(c.data, a.data);
+    }
+
+    #[test]
+    fn shared_frames_fan_out_to_threads() {
+        let frame = Mat::from_slice(1, 3, CV_8UC1, &[1u8, 2, 3]).unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let frame = frame.clone();
+                std::thread::spawn(move || frame.data.as_ptr() as usize)
+            })
+            .collect();
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), frame.data.as_ptr() as usize);
+        }
+    }
+}
@@ -1,4 +1,17 @@
+pub mod arithm;
+pub mod channels;
+mod interop;
 pub mod mat;
 pub mod tick_meter;
+pub mod types;
+pub mod view;
 
+pub use arithm::{
+    absdiff, add, add_weighted, bitwise_and, bitwise_not, bitwise_or, bitwise_xor, count_non_zero,
+    divide, in_range, max, mean, mean_std_dev, min, min_max_loc, multiply, norm, norm_diff,
+    subtract, NORM_INF, NORM_L1, NORM_L2, NORM_L2SQR, NORM_RELATIVE,
+};
+pub use channels::{extract_channel, insert_channel, merge, mix_channels, split};
 pub use tick_meter::TickMeter;
+pub use types::{Point, Point2f, Rect, Scalar, Size};
+pub use view::{AsMatView, AsMatViewMut, MatView, MatViewMut};
@@ -0,0 +1,99 @@
+//! 基础类型 (对应 OpenCV 的 `cv::Point` / `cv::Point2f` / `cv::Rect` / `cv::Size` / `cv::Scalar`)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// 浮点坐标点，用于亚像素位置 (如透视变换的角点)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point2f {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point2f {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Size {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height }
+    }
+}
+
+/// 最多 4 个通道的值，颜色按 B、G、R、A 的顺序存放
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Scalar {
+    pub v0: f64, // Blue
+    pub v1: f64, // Green
+    pub v2: f64, // Red
+    pub v3: f64, // Alpha
+}
+
+impl Scalar {
+    /// BGR 颜色，第 4 个通道为 0
+    pub fn new(b: impl Into<f64>, g: impl Into<f64>, r: impl Into<f64>) -> Self {
+        Self {
+            v0: b.into(),
+            v1: g.into(),
+            v2: r.into(),
+            v3: 0.0,
+        }
+    }
+
+    /// 4 个通道都为 `v`
+    pub fn all(v: impl Into<f64>) -> Self {
+        let v = v.into();
+        Self {
+            v0: v,
+            v1: v,
+            v2: v,
+            v3: v,
+        }
+    }
+
+    pub fn to_array(self) -> [f64; 4] {
+        [self.v0, self.v1, self.v2, self.v3]
+    }
+}
+
+impl From<[f64; 4]> for Scalar {
+    fn from([v0, v1, v2, v3]: [f64; 4]) -> Self {
+        Self { v0, v1, v2, v3 }
+    }
+}
@@ -0,0 +1,234 @@
+//! 零拷贝的 ROI 视图
+//!
+//! `Mat::roi` / `Mat::roi_mut` 返回借用父矩阵数据的视图，视图沿用父矩阵的 `step`，
+//! 因此裁剪检测框等操作不需要复制像素。视图可以继续取子区域。
+
+use super::mat::{impl_read_access, impl_write_access, Mat};
+use super::types::Rect;
+use anyhow::{anyhow, Result};
+use std::ops::Range;
+
+/// 只读视图 (对应 OpenCV 中 `Mat(parent, rect)` 的只读用法)
+#[derive(Clone, Copy)]
+pub struct MatView<'a> {
+    /// 从左上角像素开始的数据，最后一行不含 Padding
+    pub data: &'a [u8],
+    pub rows: i32,
+    pub cols: i32,
+    pub step: usize,
+    pub channels: u8,
+    pub depth: i32,
+}
+
+/// 可写视图，写入直接反映到父矩阵
+pub struct MatViewMut<'a> {
+    pub data: &'a mut [u8],
+    pub rows: i32,
+    pub cols: i32,
+    pub step: usize,
+    pub channels: u8,
+    pub depth: i32,
+}
+
+impl MatView<'_> {
+    impl_read_access!();
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0 || self.cols == 0
+    }
+
+    /// 复制为紧凑排列的 Mat
+    pub fn to_mat(&self) -> Mat {
+        to_mat(self)
+    }
+}
+
+impl MatViewMut<'_> {
+    impl_read_access!();
+    impl_write_access!();
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0 || self.cols == 0
+    }
+
+    /// 复制为紧凑排列的 Mat
+    pub fn to_mat(&self) -> Mat {
+        to_mat(self)
+    }
+
+    /// 把同尺寸、同类型的图像复制到本区域 (对应 OpenCV 的 `src.copyTo(dst(rect))`)
+    pub fn copy_from<M: AsMatView + ?Sized>(&mut self, src: &M) -> Result<()> {
+        let src = src.view();
+        if (src.rows, src.cols, src.typ()) != (self.rows, self.cols, self.typ()) {
+            return Err(anyhow!(
+                "Cannot copy {}x{} (type {}) into {}x{} (type {})",
+                src.cols,
+                src.rows,
+                src.typ(),
+                self.cols,
+                self.rows,
+                self.typ()
+            ));
+        }
+        for r in 0..self.rows {
+            self.row_bytes_mut(r).copy_from_slice(src.row_bytes(r));
+        }
+        Ok(())
+    }
+}
+
+/// 可以作为只读图像输入的类型：`Mat`、`MatView`、`MatViewMut`
+pub trait AsMatView {
+    fn view(&self) -> MatView<'_>;
+}
+
+/// 可以作为可写图像输出的类型：`Mat`、`MatViewMut`
+pub trait AsMatViewMut: AsMatView {
+    fn view_mut(&mut self) -> MatViewMut<'_>;
+}
+
+impl AsMatView for Mat {
+    fn view(&self) -> MatView<'_> {
+        MatView {
+            data: &self.data,
+            rows: self.rows,
+            cols: self.cols,
+            step: self.step,
+            channels: self.channels,
+            depth: self.depth,
+        }
+    }
+}
+
+impl AsMatViewMut for Mat {
+    fn view_mut(&mut self) -> MatViewMut<'_> {
+        MatViewMut {
+            data: &mut self.data,
+            rows: self.rows,
+            cols: self.cols,
+            step: self.step,
+            channels: self.channels,
+            depth: self.depth,
+        }
+    }
+}
+
+impl AsMatView for MatView<'_> {
+    fn view(&self) -> MatView<'_> {
+        *self
+    }
+}
+
+impl AsMatView for MatViewMut<'_> {
+    fn view(&self) -> MatView<'_> {
+        MatView {
+            data: &*self.data,
+            rows: self.rows,
+            cols: self.cols,
+            step: self.step,
+            channels: self.channels,
+            depth: self.depth,
+        }
+    }
+}
+
+impl AsMatViewMut for MatViewMut<'_> {
+    fn view_mut(&mut self) -> MatViewMut<'_> {
+        MatViewMut {
+            data: &mut *self.data,
+            rows: self.rows,
+            cols: self.cols,
+            step: self.step,
+            channels: self.channels,
+            depth: self.depth,
+        }
+    }
+}
+
+/// 计算 ROI 在父数据中的字节区间 (从左上角像素到最后一行的最后一个像素)
+pub(crate) fn roi_range(
+    rect: Rect,
+    rows: i32,
+    cols: i32,
+    step: usize,
+    elem_size: usize,
+) -> Result<Range<usize>> {
+    if rect.x < 0
+        || rect.y < 0
+        || rect.width < 0
+        || rect.height < 0
+        || rect.x as i64 + rect.width as i64 > cols as i64
+        || rect.y as i64 + rect.height as i64 > rows as i64
+    {
+        return Err(anyhow!(
+            "ROI {:?} is outside the {}x{} image",
+            rect,
+            cols,
+            rows
+        ));
+    }
+    if rect.width == 0 || rect.height == 0 {
+        return Ok(0..0);
+    }
+    let start = rect.y as usize * step + rect.x as usize * elem_size;
+    let end = start + (rect.height as usize - 1) * step + rect.width as usize * elem_size;
+    Ok(start..end)
+}
+
+fn to_mat<M: AsMatView + ?Sized>(src: &M) -> Mat {
+    let src = src.view();
+    let mut mat = Mat::with_depth(src.rows, src.cols, src.channels, src.depth);
+    for r in 0..src.rows {
+        mat.row_bytes_mut(r).copy_from_slice(src.row_bytes(r));
+    }
+    mat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16UC1, CV_8UC3};
+
+    #[test]
+    fn roi_shares_parent_data() {
+        let mut mat = Mat::zeros(6, 8, CV_8UC3);
+        {
+            let mut roi = mat.roi_mut(Rect::new(2, 1, 4, 3)).unwrap();
+            assert_eq!((roi.rows, roi.cols, roi.step), (3, 4, 24));
+            *roi.at_mut::<[u8; 3]>(0, 0) = [1, 2, 3];
+            // 子区域的子区域
+            let mut inner = roi.roi_mut(Rect::new(3, 2, 1, 1)).unwrap();
+            *inner.at_mut::<[u8; 3]>(0, 0) = [9, 9, 9];
+        }
+        assert_eq!(*mat.at::<[u8; 3]>(1, 2), [1, 2, 3]);
+        assert_eq!(*mat.at::<[u8; 3]>(3, 5), [9, 9, 9]);
+
+        let roi = mat.roi(Rect::new(2, 1, 4, 3)).unwrap();
+        let copy = roi.to_mat();
+        assert_eq!((copy.step, copy.data.len()), (12, 36));
+        assert_eq!(copy.row_bytes(2)[9..], [9, 9, 9]);
+
+        assert!(mat.roi(Rect::new(5, 0, 4, 1)).is_err());
+        assert!(mat.roi(Rect::new(-1, 0, 1, 1)).is_err());
+        assert!(mat.roi(Rect::new(1, 0, i32::MAX, 1)).is_err());
+        assert!(mat.roi(Rect::new(0, 1, 1, i32::MAX)).is_err());
+        assert!(mat.roi(Rect::new(8, 6, 0, 0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn copy_from_fills_region() {
+        let mut dst = Mat::zeros(4, 4, CV_16UC1);
+        let src = Mat::from_slice(2, 2, CV_16UC1, &[1u16, 2, 3, 4]).unwrap();
+        dst.roi_mut(Rect::new(1, 2, 2, 2))
+            .unwrap()
+            .copy_from(&src)
+            .unwrap();
+        assert_eq!(dst.row::<u16>(2), [0, 1, 2, 0]);
+        assert_eq!(dst.row::<u16>(3), [0, 3, 4, 0]);
+        assert!(dst
+            .roi_mut(Rect::new(0, 0, 3, 2))
+            .unwrap()
+            .copy_from(&src)
+            .is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::core::mat::Mat;
+use crate::core::mat::CV_8U;
+use crate::core::view::{AsMatView, MatView};
 use anyhow::{anyhow, Result};
 use minifb::{Key, Window, WindowOptions}; // 去掉了 KeyRepeat，根据版本可能不需要
 use once_cell::sync::Lazy;
@@ -21,9 +22,13 @@ static WINDOW_MANAGER: Lazy<Mutex<HashMap<String, SendWindow>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// 在指定窗口中显示图像
-pub fn imshow(winname: &str, mat: &Mat) -> Result<()> {
+///
+/// 支持 8 位的灰度、BGR、BGRA 图像，可以传入 `Mat`、ROI 视图或其他实现了
+/// `AsMatView` 的图像 (如 `rustcv_camera::Mat`)。
+pub fn imshow<M: AsMatView + ?Sized>(winname: &str, mat: &M) -> Result<()> {
+    let mat = mat.view();
     // 1. 格式转换
-    let buffer = mat_to_u32_buffer(mat)?;
+    let buffer = mat_to_u32_buffer(&mat)?;
     let width = mat.cols as usize;
     let height = mat.rows as usize;
 
@@ -122,19 +127,35 @@ pub fn destroy_all_windows() -> Result<()> {
 }
 
 // --- 内部辅助函数 (保持不变) ---
-fn mat_to_u32_buffer(mat: &Mat) -> Result<Vec<u32>> {
+fn mat_to_u32_buffer(mat: &MatView<'_>) -> Result<Vec<u32>> {
+    if mat.depth != CV_8U || !matches!(mat.channels, 1 | 3 | 4) {
+        return Err(anyhow!(
+            "imshow supports 8-bit images with 1, 3 or 4 channels, got depth {} with {} channels",
+            mat.depth,
+            mat.channels
+        ));
+    }
     let pixel_count = (mat.rows * mat.cols) as usize;
     // 提前分配并初始化，避免 debug 模式下的 capacity/push 检查开销
     let mut buffer = vec![0u32; pixel_count];
+    let cn = mat.channels as usize;
+    // 灰度图三个分量都取第 0 通道
+    let (gi, ri) = if cn == 1 { (0, 0) } else { (1, 2) };
 
+    // 逐行处理以跳过 ROI 的行尾 Padding；
     // 使用 chunks_exact 配合 zip，编译器会自动进行 SIMD 展开和去边界检查
-    for (src_chunk, dst_pixel) in mat.data.chunks_exact(3).zip(buffer.iter_mut()) {
-        let b = src_chunk[0] as u32;
-        let g = src_chunk[1] as u32;
-        let r = src_chunk[2] as u32;
-
-        // Pack: 00 | R | G | B
-        *dst_pixel = (r << 16) | (g << 8) | b;
+    for (r, dst_row) in buffer
+        .chunks_exact_mut(mat.cols.max(1) as usize)
+        .enumerate()
+    {
+        for (src_chunk, dst_pixel) in mat.row_bytes(r as i32).chunks_exact(cn).zip(dst_row) {
+            let b = src_chunk[0] as u32;
+            let g = src_chunk[gi] as u32;
+            let r = src_chunk[ri] as u32;
+
+            // Pack: 00 | R | G | B
+            *dst_pixel = (r << 16) | (g << 8) | b;
+        }
     }
 
     Ok(buffer)
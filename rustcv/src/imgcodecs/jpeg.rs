@@ -0,0 +1,684 @@
+//! JPEG 编码器
+//!
+//! image crate 的 JpegEncoder 只能输出 4:4:4 基线 JPEG，无法满足 OpenCV 的
+//! `IMWRITE_JPEG_PROGRESSIVE` / `IMWRITE_JPEG_SAMPLING_FACTOR`，因此这里自己实现：
+//! - 质量因子按 libjpeg 的方式缩放 Annex K 标准量化表
+//! - 色度采样 4:4:4 / 4:2:2 / 4:2:0 / 4:4:0 / 4:1:1
+//! - 基线 (SOF0) 与渐进式 (SOF2，只做频谱选择，不做逐次逼近)
+//! - 每个扫描都使用按实际符号频率生成的最优哈夫曼表
+
+use crate::core::mat::{Mat, CV_8U};
+use anyhow::{anyhow, Result};
+
+/// 编码参数
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JpegOptions {
+    /// 1-100
+    pub quality: u8,
+    pub progressive: bool,
+    /// 亮度分量的 (水平, 垂直) 采样因子，色度分量固定为 1x1
+    pub sampling: (u8, u8),
+}
+
+impl Default for JpegOptions {
+    /// 与 OpenCV 默认值一致：质量 95，基线，4:2:0
+    fn default() -> Self {
+        Self {
+            quality: 95,
+            progressive: false,
+            sampling: (2, 2),
+        }
+    }
+}
+
+/// zigzag 序号 -> 自然 (行优先) 序号
+const ZIGZAG: [usize; 64] = zigzag();
+
+const fn zigzag() -> [usize; 64] {
+    let mut order = [0; 64];
+    let mut k = 0;
+    let mut s: usize = 0;
+    // 沿反对角线 row + col = s 扫描，偶数条向右上、奇数条向左下
+    while s < 15 {
+        let lo = s.saturating_sub(7);
+        let hi = if s < 7 { s } else { 7 };
+        let mut i = 0;
+        while i <= hi - lo {
+            let row = if s & 1 == 0 { hi - i } else { lo + i };
+            order[k] = row * 8 + (s - row);
+            k += 1;
+            i += 1;
+        }
+        s += 1;
+    }
+    order
+}
+
+/// Annex K.1 亮度量化表 (自然顺序)
+const LUMA_QUANT: [u16; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61, //
+    12, 12, 14, 19, 26, 58, 60, 55, //
+    14, 13, 16, 24, 40, 57, 69, 56, //
+    14, 17, 22, 29, 51, 87, 80, 62, //
+    18, 22, 37, 56, 68, 109, 103, 77, //
+    24, 35, 55, 64, 81, 104, 113, 92, //
+    49, 64, 78, 87, 103, 121, 120, 101, //
+    72, 92, 95, 98, 112, 100, 103, 99,
+];
+
+/// Annex K.1 色度量化表 (自然顺序)
+const CHROMA_QUANT: [u16; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99, //
+    18, 21, 26, 66, 99, 99, 99, 99, //
+    24, 26, 56, 99, 99, 99, 99, 99, //
+    47, 66, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+/// libjpeg 的 `jpeg_quality_scaling` + `jpeg_add_quant_table` (baseline 限制到 255)
+fn scale_quant(base: &[u16; 64], quality: u8) -> [u16; 64] {
+    let quality = quality.clamp(1, 100) as u32;
+    let scale = if quality < 50 {
+        5000 / quality
+    } else {
+        200 - quality * 2
+    };
+    base.map(|q| ((q as u32 * scale + 50) / 100).clamp(1, 255) as u16)
+}
+
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+    /// 量化表 / 哈夫曼表编号 (0 亮度，1 色度)
+    table: usize,
+    /// 不含 MCU 填充的块列数 / 块行数 (非交织扫描只编码这些块)
+    blocks_w: usize,
+    blocks_h: usize,
+    /// 含 MCU 填充的每行块数
+    stride: usize,
+    /// 量化后的系数，zigzag 顺序
+    blocks: Vec<[i16; 64]>,
+}
+
+/// 一次扫描：分量列表与频谱范围 [ss, se]
+struct Scan {
+    comps: Vec<usize>,
+    ss: usize,
+    se: usize,
+}
+
+/// 编码 1 通道 (灰度)、3 通道 (BGR) 或 4 通道 (BGRA，忽略 Alpha) 的 8 位图像
+pub(crate) fn encode(mat: &Mat, options: &JpegOptions) -> Result<Vec<u8>> {
+    let (width, height) = (mat.cols as usize, mat.rows as usize);
+    if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(anyhow!("Invalid JPEG size: {}x{}", width, height));
+    }
+    if mat.depth != CV_8U {
+        return Err(anyhow!("JPEG supports only 8-bit images"));
+    }
+    let gray = match mat.channels {
+        1 => true,
+        3 | 4 => false,
+        n => return Err(anyhow!("Unsupported channel count for JPEG: {}", n)),
+    };
+    let (hmax, vmax) = if gray {
+        (1, 1)
+    } else {
+        (options.sampling.0 as usize, options.sampling.1 as usize)
+    };
+    if !matches!((hmax, vmax), (1, 1) | (2, 1) | (2, 2) | (1, 2) | (4, 1)) {
+        return Err(anyhow!(
+            "Unsupported JPEG sampling factor {}x{}",
+            hmax,
+            vmax
+        ));
+    }
+
+    let quant = [
+        scale_quant(&LUMA_QUANT, options.quality),
+        scale_quant(&CHROMA_QUANT, options.quality),
+    ];
+    let comps = planes(mat, hmax, vmax)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (plane, plane_w))| {
+            let (h, v) = if i == 0 { (hmax, vmax) } else { (1, 1) };
+            let table = i.min(1);
+            let stride = plane_w / 8;
+            Component {
+                id: i as u8 + 1,
+                h,
+                v,
+                table,
+                blocks_w: (width * h).div_ceil(hmax).div_ceil(8),
+                blocks_h: (height * v).div_ceil(vmax).div_ceil(8),
+                stride,
+                blocks: transform(&plane, plane_w, &quant[table]),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mcus_x = width.div_ceil(8 * hmax);
+    let mcus_y = height.div_ceil(8 * vmax);
+
+    let mut out = Vec::with_capacity(width * height / 4 + 1024);
+    out.extend_from_slice(&[0xFF, 0xD8]);
+    // JFIF APP0：版本 1.01，无单位，像素宽高比 1:1，无缩略图
+    segment(
+        &mut out,
+        0xE0,
+        b"JFIF\0\x01\x01\x00\x00\x01\x00\x01\x00\x00",
+    );
+
+    let mut dqt = vec![];
+    for (id, table) in quant.iter().enumerate().take(comps.len().min(2)) {
+        dqt.push(id as u8);
+        dqt.extend(ZIGZAG.iter().map(|&k| table[k] as u8));
+    }
+    segment(&mut out, 0xDB, &dqt);
+
+    let mut sof = vec![8];
+    sof.extend_from_slice(&(height as u16).to_be_bytes());
+    sof.extend_from_slice(&(width as u16).to_be_bytes());
+    sof.push(comps.len() as u8);
+    for c in &comps {
+        sof.extend_from_slice(&[c.id, (c.h << 4 | c.v) as u8, c.table as u8]);
+    }
+    segment(
+        &mut out,
+        if options.progressive { 0xC2 } else { 0xC0 },
+        &sof,
+    );
+
+    let all: Vec<usize> = (0..comps.len()).collect();
+    let scans = if options.progressive {
+        // 先传所有分量的 DC，再逐分量传低频、高频 AC
+        let mut scans = vec![Scan {
+            comps: all,
+            ss: 0,
+            se: 0,
+        }];
+        for c in 0..comps.len() {
+            scans.push(Scan {
+                comps: vec![c],
+                ss: 1,
+                se: 5,
+            });
+            scans.push(Scan {
+                comps: vec![c],
+                ss: 6,
+                se: 63,
+            });
+        }
+        scans
+    } else {
+        vec![Scan {
+            comps: all,
+            ss: 0,
+            se: 63,
+        }]
+    };
+
+    for scan in &scans {
+        let order = scan_order(&comps, scan, mcus_x, mcus_y);
+        let symbols = symbols(&comps, scan, &order, options.progressive);
+        write_scan(&mut out, &comps, scan, &symbols);
+    }
+    out.extend_from_slice(&[0xFF, 0xD9]);
+    Ok(out)
+}
+
+/// 生成 Y (或 Y, Cb, Cr) 平面，并按采样因子降采样
+///
+/// 平面尺寸填充到整数个 MCU，边缘像素复制填充。返回 (平面, 平面宽度)。
+fn planes(mat: &Mat, hmax: usize, vmax: usize) -> Vec<(Vec<f32>, usize)> {
+    let (width, height) = (mat.cols as usize, mat.rows as usize);
+    let padded_w = width.div_ceil(8 * hmax) * 8 * hmax;
+    let padded_h = height.div_ceil(8 * vmax) * 8 * vmax;
+    let cn = mat.channels as usize;
+
+    let mut ycc = vec![vec![0f32; padded_w * padded_h]; if cn == 1 { 1 } else { 3 }];
+    for y in 0..padded_h {
+        let row = mat.row_bytes(y.min(height - 1) as i32);
+        for x in 0..padded_w {
+            let px = &row[x.min(width - 1) * cn..];
+            let i = y * padded_w + x;
+            if cn == 1 {
+                ycc[0][i] = px[0] as f32;
+                continue;
+            }
+            // JFIF YCbCr (BT.601 全范围)
+            let (b, g, r) = (px[0] as f32, px[1] as f32, px[2] as f32);
+            ycc[0][i] = 0.299 * r + 0.587 * g + 0.114 * b;
+            ycc[1][i] = -0.168_736 * r - 0.331_264 * g + 0.5 * b + 128.0;
+            ycc[2][i] = 0.5 * r - 0.418_688 * g - 0.081_312 * b + 128.0;
+        }
+    }
+
+    ycc.into_iter()
+        .enumerate()
+        .map(|(i, plane)| {
+            if i == 0 || (hmax, vmax) == (1, 1) {
+                return (plane, padded_w);
+            }
+            // 色度按 hmax x vmax 的方块取平均
+            let (w, h) = (padded_w / hmax, padded_h / vmax);
+            let area = (hmax * vmax) as f32;
+            let mut out = vec![0f32; w * h];
+            for y in 0..h {
+                for x in 0..w {
+                    let mut sum = 0.0;
+                    for dy in 0..vmax {
+                        let start = (y * vmax + dy) * padded_w + x * hmax;
+                        sum += plane[start..start + hmax].iter().sum::<f32>();
+                    }
+                    out[y * w + x] = sum / area;
+                }
+            }
+            (out, w)
+        })
+        .collect()
+}
+
+/// 逐块 DCT + 量化，结果按 zigzag 顺序存放
+fn transform(plane: &[f32], plane_w: usize, quant: &[u16; 64]) -> Vec<[i16; 64]> {
+    // c[u][x] = C(u) / 2 * cos((2x + 1) * u * pi / 16)
+    let mut c = [[0f32; 8]; 8];
+    for (u, row) in c.iter_mut().enumerate() {
+        let cu = if u == 0 {
+            std::f32::consts::FRAC_1_SQRT_2
+        } else {
+            1.0
+        };
+        for (x, v) in row.iter_mut().enumerate() {
+            *v = cu / 2.0 * ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0).cos();
+        }
+    }
+
+    let blocks_w = plane_w / 8;
+    let blocks_h = plane.len() / plane_w / 8;
+    let mut blocks = Vec::with_capacity(blocks_w * blocks_h);
+    for by in 0..blocks_h {
+        for bx in 0..blocks_w {
+            // 行变换
+            let mut tmp = [[0f32; 8]; 8];
+            for (y, tmp_row) in tmp.iter_mut().enumerate() {
+                let start = (by * 8 + y) * plane_w + bx * 8;
+                let row = &plane[start..start + 8];
+                for (u, t) in tmp_row.iter_mut().enumerate() {
+                    *t = (0..8).map(|x| c[u][x] * (row[x] - 128.0)).sum();
+                }
+            }
+            // 列变换 + 量化
+            let mut block = [0i16; 64];
+            for (k, &natural) in ZIGZAG.iter().enumerate() {
+                let (v, u) = (natural / 8, natural % 8);
+                let coef: f32 = (0..8).map(|y| c[v][y] * tmp[y][u]).sum();
+                block[k] = (coef / quant[natural] as f32).round() as i16;
+            }
+            blocks.push(block);
+        }
+    }
+    blocks
+}
+
+/// 扫描中块的编码顺序：(分量, 块序号)
+fn scan_order(
+    comps: &[Component],
+    scan: &Scan,
+    mcus_x: usize,
+    mcus_y: usize,
+) -> Vec<(usize, usize)> {
+    let mut order = vec![];
+    if let [c] = scan.comps[..] {
+        // 非交织：只编码覆盖图像的块，按行扫描
+        let comp = &comps[c];
+        for by in 0..comp.blocks_h {
+            for bx in 0..comp.blocks_w {
+                order.push((c, by * comp.stride + bx));
+            }
+        }
+        return order;
+    }
+
+    // 交织：逐 MCU，每个 MCU 内按分量依次输出 h x v 个块
+    for my in 0..mcus_y {
+        for mx in 0..mcus_x {
+            for &c in &scan.comps {
+                let comp = &comps[c];
+                for by in 0..comp.v {
+                    for bx in 0..comp.h {
+                        order.push((c, (my * comp.v + by) * comp.stride + mx * comp.h + bx));
+                    }
+                }
+            }
+        }
+    }
+    order
+}
+
+/// 哈夫曼符号及其附加位
+#[derive(Clone, Copy)]
+struct Symbol {
+    /// 0/1: DC 表，2/3: AC 表
+    table: usize,
+    value: u8,
+    extra: u16,
+    extra_len: u8,
+}
+
+/// 数值的位数类别 (SSSS)
+fn category(value: i32) -> u8 {
+    (32 - value.unsigned_abs().leading_zeros()) as u8
+}
+
+/// 负数按反码存放低 `len` 位
+fn magnitude(value: i32, len: u8) -> u16 {
+    let value = if value < 0 { value - 1 } else { value };
+    (value & ((1 << len) - 1)) as u16
+}
+
+fn symbols(
+    comps: &[Component],
+    scan: &Scan,
+    order: &[(usize, usize)],
+    eob_runs: bool,
+) -> Vec<Symbol> {
+    let mut out = vec![];
+    let mut pred = vec![0i32; comps.len()];
+    let mut eobrun = 0u16;
+    let mut ac_table = 2;
+
+    let flush_eobrun = |out: &mut Vec<Symbol>, eobrun: &mut u16, table: usize| {
+        if *eobrun > 0 {
+            let bits = 15 - eobrun.leading_zeros() as u8;
+            out.push(Symbol {
+                table,
+                value: bits << 4,
+                extra: *eobrun - (1 << bits),
+                extra_len: bits,
+            });
+            *eobrun = 0;
+        }
+    };
+
+    for &(c, index) in order {
+        let block = &comps[c].blocks[index];
+        let table = comps[c].table;
+        ac_table = 2 + table;
+
+        if scan.ss == 0 {
+            let diff = block[0] as i32 - pred[c];
+            pred[c] = block[0] as i32;
+            let len = category(diff);
+            out.push(Symbol {
+                table,
+                value: len,
+                extra: magnitude(diff, len),
+                extra_len: len,
+            });
+        }
+        if scan.se == 0 {
+            continue;
+        }
+
+        let mut run = 0u8;
+        for &coef in &block[scan.ss.max(1)..=scan.se] {
+            if coef == 0 {
+                run += 1;
+                continue;
+            }
+            flush_eobrun(&mut out, &mut eobrun, ac_table);
+            while run > 15 {
+                out.push(Symbol {
+                    table: ac_table,
+                    value: 0xF0,
+                    extra: 0,
+                    extra_len: 0,
+                });
+                run -= 16;
+            }
+            let len = category(coef as i32);
+            out.push(Symbol {
+                table: ac_table,
+                value: run << 4 | len,
+                extra: magnitude(coef as i32, len),
+                extra_len: len,
+            });
+            run = 0;
+        }
+        if run > 0 {
+            if eob_runs {
+                eobrun += 1;
+                if eobrun == 0x7FFF {
+                    flush_eobrun(&mut out, &mut eobrun, ac_table);
+                }
+            } else {
+                out.push(Symbol {
+                    table: ac_table,
+                    value: 0,
+                    extra: 0,
+                    extra_len: 0,
+                });
+            }
+        }
+    }
+    flush_eobrun(&mut out, &mut eobrun, ac_table);
+    out
+}
+
+/// 哈夫曼表：DHT 中的 BITS/HUFFVAL 以及每个符号的 (码字, 码长)
+struct HuffmanTable {
+    bits: [u8; 16],
+    values: Vec<u8>,
+    codes: [(u16, u8); 256],
+}
+
+impl HuffmanTable {
+    /// 按 Annex K.2 生成码长不超过 16 的最优表
+    fn optimal(freq: &[u32; 256]) -> Self {
+        let mut freq: Vec<u64> = freq.iter().map(|&f| f as u64).collect();
+        // 保留一个码字，保证不会出现全 1 的码字
+        freq.push(1);
+        let mut size = [0usize; 257];
+        let mut others = [usize::MAX; 257];
+
+        loop {
+            // 频率最小的两个符号 (相同频率取数值较大者，与 libjpeg 一致)
+            let mut c1 = None;
+            for i in 0..257 {
+                if freq[i] > 0 && c1.is_none_or(|c: usize| freq[i] <= freq[c]) {
+                    c1 = Some(i);
+                }
+            }
+            let mut c2 = None;
+            for i in 0..257 {
+                if freq[i] > 0 && Some(i) != c1 && c2.is_none_or(|c: usize| freq[i] <= freq[c]) {
+                    c2 = Some(i);
+                }
+            }
+            let (Some(mut c1), Some(mut c2)) = (c1, c2) else {
+                break;
+            };
+
+            freq[c1] += freq[c2];
+            freq[c2] = 0;
+            size[c1] += 1;
+            while others[c1] != usize::MAX {
+                c1 = others[c1];
+                size[c1] += 1;
+            }
+            others[c1] = c2;
+            size[c2] += 1;
+            while others[c2] != usize::MAX {
+                c2 = others[c2];
+                size[c2] += 1;
+            }
+        }
+
+        let mut bits = [0u32; 33];
+        for &s in size.iter().filter(|&&s| s > 0) {
+            bits[s] += 1;
+        }
+        // 把超过 16 位的码字挪到较短的长度上
+        for i in (17..=32).rev() {
+            while bits[i] > 0 {
+                let mut j = i - 2;
+                while bits[j] == 0 {
+                    j -= 1;
+                }
+                bits[i] -= 2;
+                bits[i - 1] += 1;
+                bits[j + 1] += 2;
+                bits[j] -= 1;
+            }
+        }
+        // 去掉保留码字 (最长的那个)
+        let longest = (1..=16).rev().find(|&i| bits[i] > 0).unwrap_or(0);
+        bits[longest] -= 1;
+
+        let mut values = vec![];
+        for len in 1..=32 {
+            values.extend((0..256).filter(|&s| size[s] == len).map(|s| s as u8));
+        }
+
+        let mut table = Self {
+            bits: std::array::from_fn(|i| bits[i + 1] as u8),
+            values,
+            codes: [(0, 0); 256],
+        };
+        let mut code = 0u16;
+        let mut k = 0;
+        for len in 1..=16u8 {
+            for _ in 0..table.bits[len as usize - 1] {
+                table.codes[table.values[k] as usize] = (code, len);
+                code += 1;
+                k += 1;
+            }
+            code <<= 1;
+        }
+        table
+    }
+}
+
+/// 写出 DHT + SOS + 熵编码数据
+fn write_scan(out: &mut Vec<u8>, comps: &[Component], scan: &Scan, symbols: &[Symbol]) {
+    let mut freq = [[0u32; 256]; 4];
+    for s in symbols {
+        freq[s.table][s.value as usize] += 1;
+    }
+    let tables: Vec<Option<HuffmanTable>> = freq
+        .iter()
+        .map(|f| f.iter().any(|&n| n > 0).then(|| HuffmanTable::optimal(f)))
+        .collect();
+
+    let mut dht = vec![];
+    for (slot, table) in tables.iter().enumerate() {
+        if let Some(table) = table {
+            // Tc (0 = DC, 1 = AC) << 4 | Th
+            dht.push((((slot / 2) << 4) | (slot % 2)) as u8);
+            dht.extend_from_slice(&table.bits);
+            dht.extend_from_slice(&table.values);
+        }
+    }
+    segment(out, 0xC4, &dht);
+
+    let mut sos = vec![scan.comps.len() as u8];
+    for &c in &scan.comps {
+        let t = comps[c].table as u8;
+        sos.extend_from_slice(&[comps[c].id, t << 4 | t]);
+    }
+    // Ss, Se, Ah/Al
+    sos.extend_from_slice(&[scan.ss as u8, scan.se as u8, 0]);
+    segment(out, 0xDA, &sos);
+
+    let mut writer = BitWriter {
+        out,
+        acc: 0,
+        len: 0,
+    };
+    for s in symbols {
+        let (code, len) =
+            tables[s.table].as_ref().expect("table for symbol").codes[s.value as usize];
+        writer.put(code as u32, len as u32);
+        writer.put(s.extra as u32, s.extra_len as u32);
+    }
+    writer.flush();
+}
+
+/// 带 0xFF 填充的比特写入器
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    acc: u32,
+    len: u32,
+}
+
+impl BitWriter<'_> {
+    fn put(&mut self, bits: u32, len: u32) {
+        if len == 0 {
+            return;
+        }
+        self.acc = (self.acc << len) | (bits & ((1 << len) - 1));
+        self.len += len;
+        while self.len >= 8 {
+            self.len -= 8;
+            let byte = (self.acc >> self.len) as u8;
+            self.out.push(byte);
+            if byte == 0xFF {
+                self.out.push(0);
+            }
+        }
+        self.acc &= (1 << self.len) - 1;
+    }
+
+    /// 用 1 补齐最后一个字节
+    fn flush(&mut self) {
+        if self.len > 0 {
+            let pad = 8 - self.len;
+            self.put((1 << pad) - 1, pad);
+        }
+    }
+}
+
+/// 写出带长度字段的标记段
+fn segment(out: &mut Vec<u8>, marker: u8, payload: &[u8]) {
+    out.extend_from_slice(&[0xFF, marker]);
+    out.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    out.extend_from_slice(payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zigzag_matches_standard_order() {
+        assert_eq!(ZIGZAG[..10], [0, 1, 8, 16, 9, 2, 3, 10, 17, 24]);
+        assert_eq!(ZIGZAG[58..], [61, 54, 47, 55, 62, 63]);
+    }
+
+    #[test]
+    fn huffman_lengths_are_limited() {
+        // 斐波那契频率会产生很深的树
+        let mut freq = [0u32; 256];
+        let (mut a, mut b) = (1u32, 1u32);
+        for f in freq.iter_mut().take(30) {
+            *f = a;
+            (a, b) = (b, a.saturating_add(b));
+        }
+        let table = HuffmanTable::optimal(&freq);
+        assert_eq!(table.bits.iter().map(|&n| n as usize).sum::<usize>(), 30);
+        assert!(table.codes[..30]
+            .iter()
+            .all(|&(_, len)| (1..=16).contains(&len)));
+        // Kraft 不等式严格成立 (保留了全 1 码字)
+        let kraft: f64 = table.codes[..30]
+            .iter()
+            .map(|&(_, l)| 0.5f64.powi(l as i32))
+            .sum();
+        assert!(kraft < 1.0);
+    }
+}
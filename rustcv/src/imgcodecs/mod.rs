@@ -1,76 +1,532 @@
-use crate::core::mat::Mat;
+use crate::core::mat::{Mat, CV_16U, CV_8U};
 use anyhow::{anyhow, Result};
+use image::DynamicImage;
+use std::io::Cursor;
 use std::path::Path;
 
+mod jpeg;
+mod png;
+mod pnm;
+
+// --- imread / imdecode 标志 (与 OpenCV 取值一致) ---
+
+/// 按原样读取 (保留通道数、Alpha 与 16 位深度)
+pub const IMREAD_UNCHANGED: i32 = -1;
+/// 转为单通道灰度
+pub const IMREAD_GRAYSCALE: i32 = 0;
+/// 转为 3 通道 BGR (默认)
+pub const IMREAD_COLOR: i32 = 1;
+/// 保留 16 位深度 (否则转为 8 位)，可与其他标志按位或
+pub const IMREAD_ANYDEPTH: i32 = 2;
+/// 灰度图保持单通道、彩色图为 BGR，可与其他标志按位或
+pub const IMREAD_ANYCOLOR: i32 = 4;
+
+// --- imwrite / imencode 参数 (与 OpenCV 取值一致) ---
+
+/// JPEG 质量 0-100，默认 95
+pub const IMWRITE_JPEG_QUALITY: i32 = 1;
+/// 非 0 时输出渐进式 JPEG，默认 0
+pub const IMWRITE_JPEG_PROGRESSIVE: i32 = 2;
+/// JPEG 色度采样，取值见 `IMWRITE_JPEG_SAMPLING_FACTOR_*`，默认 4:2:0
+pub const IMWRITE_JPEG_SAMPLING_FACTOR: i32 = 7;
+/// PNG zlib 压缩级别 0-9，默认 1
+pub const IMWRITE_PNG_COMPRESSION: i32 = 16;
+/// WebP 质量 1-100 (有损，需要 `webp` feature)，大于 100 或不指定时为无损
+pub const IMWRITE_WEBP_QUALITY: i32 = 64;
+
+pub const IMWRITE_JPEG_SAMPLING_FACTOR_411: i32 = 0x411111;
+pub const IMWRITE_JPEG_SAMPLING_FACTOR_420: i32 = 0x221111;
+pub const IMWRITE_JPEG_SAMPLING_FACTOR_422: i32 = 0x211111;
+pub const IMWRITE_JPEG_SAMPLING_FACTOR_440: i32 = 0x121111;
+pub const IMWRITE_JPEG_SAMPLING_FACTOR_444: i32 = 0x111111;
+
 /// 读取图像文件
 ///
 /// 支持 JPG, PNG, BMP 等常见格式。
 /// 注意：这将强制转换为 BGR 格式以匹配 OpenCV 默认行为。
 pub fn imread<P: AsRef<Path>>(path: P) -> Result<Mat> {
-    // 1. 使用 image crate 加载
-    let img = image::open(path).map_err(|e| anyhow!("Failed to open image: {}", e))?;
-
-    // 2. 转换为 RGB8 (统一格式)
-    let rgb = img.to_rgb8();
-    let (width, height) = (rgb.width() as i32, rgb.height() as i32);
-
-    // 3. 转换为 BGR (OpenCV 默认)
-    // 这是一个内存拷贝过程，但对于文件 IO 来说，性能瓶颈通常在磁盘读取，而不是这里的内存重排。
-    let pixel_count = (width * height) as usize;
-    let mut bgr_data = Vec::with_capacity(pixel_count * 3);
-
-    for pixel in rgb.pixels() {
-        let [r, g, b] = pixel.0;
-        bgr_data.push(b); // Blue first
-        bgr_data.push(g);
-        bgr_data.push(r); // Red last
-    }
+    imread_with_flags(path, IMREAD_COLOR)
+}
 
-    // 4. 构造 Mat (Packed layout, so step = width * 3)
-    let mut mat = Mat::new(height, width, 3);
-    mat.data = bgr_data;
+/// 按 `IMREAD_*` 标志读取图像文件
+///
+/// 例如 `IMREAD_UNCHANGED` 读取 16 位 PNG 深度图得到单通道 `CV_16U` Mat。
+pub fn imread_with_flags<P: AsRef<Path>>(path: P, flags: i32) -> Result<Mat> {
+    let img = image::open(path).map_err(|e| anyhow!("Failed to open image: {}", e))?;
+    Ok(to_mat(img, flags))
+}
 
-    Ok(mat)
+/// 从内存解码图像 (格式由数据头自动识别)
+///
+/// `flags` 含义同 [`imread_with_flags`]：
+/// - `IMREAD_COLOR`: 8 位 BGR
+/// - `IMREAD_GRAYSCALE`: 8 位单通道
+/// - `IMREAD_UNCHANGED`: 保持原样，灰度为 1 通道，带 Alpha 时为 4 通道 BGRA，16 位保持 `CV_16U`
+/// - `IMREAD_ANYDEPTH` / `IMREAD_ANYCOLOR`: 分别保留深度 / 颜色类型
+pub fn imdecode(buf: &[u8], flags: i32) -> Result<Mat> {
+    let img = image::load_from_memory(buf).map_err(|e| anyhow!("Failed to decode image: {}", e))?;
+    Ok(to_mat(img, flags))
 }
 
 /// 保存图像文件
 ///
 /// 根据文件扩展名自动决定格式。
 pub fn imwrite<P: AsRef<Path>>(path: P, mat: &Mat) -> Result<()> {
-    if mat.channels != 3 {
-        return Err(anyhow!(
-            "Only 3-channel (BGR) images are supported for saving currently"
-        ));
+    imwrite_with_params(path, mat, &[])
+}
+
+/// 保存图像文件，`params` 含义同 [`imencode`]
+pub fn imwrite_with_params<P: AsRef<Path>>(path: P, mat: &Mat, params: &[i32]) -> Result<()> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .ok_or_else(|| anyhow!("Cannot determine image format of {}", path.display()))?;
+    let buf = imencode(ext, mat, params)?;
+    std::fs::write(path, buf).map_err(|e| anyhow!("Failed to save image: {}", e))
+}
+
+/// 把图像编码到内存
+///
+/// * `ext`: 格式扩展名，如 `".jpg"`、`"png"`、`".webp"`
+/// * `mat`: 1 通道灰度、3 通道 BGR 或 4 通道 BGRA
+/// * `params`: OpenCV 风格的 `[参数ID, 值, 参数ID, 值, ...]`，未知参数被忽略
+///
+/// 16 位 (`CV_16U`) 图像可保存为 PNG、TIFF 与 PGM/PPM。
+/// 有损 WebP (`IMWRITE_WEBP_QUALITY` 不大于 100) 需要启用 `webp` feature，否则返回错误。
+pub fn imencode(ext: &str, mat: &Mat, params: &[i32]) -> Result<Vec<u8>> {
+    if mat.is_empty() {
+        return Err(anyhow!("Cannot encode an empty image"));
+    }
+    if !params.len().is_multiple_of(2) {
+        return Err(anyhow!("Encoder params must be (id, value) pairs"));
+    }
+    let param = |id: i32| {
+        params
+            .chunks_exact(2)
+            .rev()
+            .find(|p| p[0] == id)
+            .map(|p| p[1])
+    };
+
+    let ext = ext.trim_start_matches('.').to_ascii_lowercase();
+    match ext.as_str() {
+        "jpg" | "jpeg" | "jpe" => {
+            let mut options = jpeg::JpegOptions::default();
+            if let Some(q) = param(IMWRITE_JPEG_QUALITY) {
+                options.quality = q.clamp(1, 100) as u8;
+            }
+            if let Some(p) = param(IMWRITE_JPEG_PROGRESSIVE) {
+                options.progressive = p != 0;
+            }
+            if let Some(s) = param(IMWRITE_JPEG_SAMPLING_FACTOR) {
+                options.sampling = match s {
+                    IMWRITE_JPEG_SAMPLING_FACTOR_411 => (4, 1),
+                    IMWRITE_JPEG_SAMPLING_FACTOR_420 => (2, 2),
+                    IMWRITE_JPEG_SAMPLING_FACTOR_422 => (2, 1),
+                    IMWRITE_JPEG_SAMPLING_FACTOR_440 => (1, 2),
+                    IMWRITE_JPEG_SAMPLING_FACTOR_444 => (1, 1),
+                    _ => return Err(anyhow!("Unknown JPEG sampling factor: {:#x}", s)),
+                };
+            }
+            jpeg::encode(mat, &options)
+        }
+        "png" => png::encode(
+            mat,
+            param(IMWRITE_PNG_COMPRESSION).unwrap_or(1).clamp(0, 9) as u32,
+        ),
+        "pgm" | "ppm" | "pnm" => pnm::encode(&ext, mat),
+        "webp" => {
+            if mat.depth != CV_8U {
+                return Err(anyhow!("WebP supports only 8-bit images"));
+            }
+            if let Some(q) = param(IMWRITE_WEBP_QUALITY).filter(|&q| q <= 100) {
+                return encode_lossy_webp(mat, q.max(1));
+            }
+            let (pixels, color) = to_rgb(mat)?;
+            let mut out = vec![];
+            image::codecs::webp::WebPEncoder::new_lossless(&mut out)
+                .encode(&pixels, mat.cols as u32, mat.rows as u32, color)
+                .map_err(|e| anyhow!("Failed to encode image: {}", e))?;
+            Ok(out)
+        }
+        _ => {
+            let format = image::ImageFormat::from_extension(&ext)
+                .ok_or_else(|| anyhow!("Unsupported image format: {}", ext))?;
+            let (pixels, color) = to_rgb(mat)?;
+            let mut out = Cursor::new(vec![]);
+            image::write_buffer_with_format(
+                &mut out,
+                &pixels,
+                mat.cols as u32,
+                mat.rows as u32,
+                color,
+                format,
+            )
+            .map_err(|e| anyhow!("Failed to encode image: {}", e))?;
+            Ok(out.into_inner())
+        }
+    }
+}
+
+#[cfg(feature = "webp")]
+fn encode_lossy_webp(mat: &Mat, quality: i32) -> Result<Vec<u8>> {
+    let (mut pixels, color) = to_rgb(mat)?;
+    // libwebp 只接受 RGB / RGBA
+    if color == image::ColorType::L8 {
+        pixels = pixels.iter().flat_map(|&v| [v; 3]).collect();
     }
+    let (w, h) = (mat.cols as u32, mat.rows as u32);
+    let encoder = if color == image::ColorType::Rgba8 {
+        webp::Encoder::from_rgba(&pixels, w, h)
+    } else {
+        webp::Encoder::from_rgb(&pixels, w, h)
+    };
+    let out = encoder
+        .encode_simple(false, quality as f32)
+        .map_err(|e| anyhow!("Failed to encode image: {:?}", e))?;
+    Ok(out.to_vec())
+}
 
-    // 1. BGR -> RGB 转换
-    // image crate save 需要 RGB
-    let pixel_count = (mat.rows * mat.cols) as usize;
-    let mut rgb_data = Vec::with_capacity(pixel_count * 3);
+#[cfg(not(feature = "webp"))]
+fn encode_lossy_webp(_mat: &Mat, quality: i32) -> Result<Vec<u8>> {
+    Err(anyhow!(
+        "Lossy WebP (quality {}) requires the `webp` feature, use a quality above 100 for lossless",
+        quality
+    ))
+}
 
+/// BGR(A) Mat -> 紧凑排列的 RGB(A) / 灰度 (image crate 的输入格式，16 位为本机字节序)
+fn to_rgb(mat: &Mat) -> Result<(Vec<u8>, image::ColorType)> {
+    use image::ColorType::*;
+    let color = match (mat.channels, mat.depth) {
+        (1, CV_8U) => L8,
+        (3, CV_8U) => Rgb8,
+        (4, CV_8U) => Rgba8,
+        (1, CV_16U) => L16,
+        (3, CV_16U) => Rgb16,
+        (4, CV_16U) => Rgba16,
+        (n, d) => {
+            return Err(anyhow!(
+                "Unsupported image type for saving: {} channels, depth {}",
+                n,
+                d
+            ))
+        }
+    };
+    let size = mat.elem_size1();
+    let mut pixels = Vec::with_capacity(mat.rows as usize * mat.cols as usize * mat.elem_size());
     for r in 0..mat.rows {
-        let row = mat.row_bytes(r);
-        for c in 0..mat.cols as usize {
-            let offset = c * 3;
-            let b = row[offset];
-            let g = row[offset + 1];
-            let r = row[offset + 2];
-
-            rgb_data.push(r);
-            rgb_data.push(g);
-            rgb_data.push(b);
+        pixels.extend_from_slice(mat.row_bytes(r));
+    }
+    if mat.channels >= 3 {
+        for px in pixels.chunks_exact_mut(mat.elem_size()) {
+            for k in 0..size {
+                px.swap(k, 2 * size + k);
+            }
         }
     }
+    Ok((pixels, color))
+}
 
-    // 2. 保存
-    image::save_buffer(
-        path,
-        &rgb_data,
-        mat.cols as u32,
-        mat.rows as u32,
-        image::ColorType::Rgb8,
-    )
-    .map_err(|e| anyhow!("Failed to save image: {}", e))?;
+/// 按 `IMREAD_*` 标志把解码结果转为 Mat
+fn to_mat(img: DynamicImage, flags: i32) -> Mat {
+    use image::ColorType::*;
+    let color = img.color();
+    let unchanged = flags < 0;
+    let wide = matches!(color, L16 | La16 | Rgb16 | Rgba16);
+    let depth = if wide && (unchanged || flags & IMREAD_ANYDEPTH != 0) {
+        CV_16U
+    } else {
+        CV_8U
+    };
+    let channels = if unchanged && color.has_alpha() {
+        4
+    } else if (unchanged || flags & IMREAD_ANYCOLOR != 0) && !color.has_color() {
+        1
+    } else if unchanged || flags & (IMREAD_COLOR | IMREAD_ANYCOLOR) != 0 {
+        3
+    } else {
+        1
+    };
 
-    Ok(())
+    let mut mat = Mat::with_depth(img.height() as i32, img.width() as i32, channels, depth);
+    mat.data = if wide {
+        let samples = match channels {
+            1 if !color.has_color() => img.to_luma16().into_raw(),
+            1 => rgb_to_gray(&img.to_rgb16().into_raw()),
+            3 => swap_rb(img.to_rgb16().into_raw(), 3),
+            _ => swap_rb(img.to_rgba16().into_raw(), 4),
+        };
+        if depth == CV_16U {
+            samples.iter().flat_map(|v| v.to_ne_bytes()).collect()
+        } else {
+            // 与 OpenCV 一致，16 位转 8 位直接取高字节
+            samples.iter().map(|v| (v >> 8) as u8).collect()
+        }
+    } else {
+        match channels {
+            1 if !color.has_color() => img.to_luma8().into_raw(),
+            1 => rgb_to_gray(&img.to_rgb8().into_raw()),
+            3 => swap_rb(img.to_rgb8().into_raw(), 3),
+            _ => swap_rb(img.to_rgba8().into_raw(), 4),
+        }
+        .into()
+    };
+    mat
+}
+
+/// RGB(A) -> BGR(A)
+fn swap_rb<T>(mut samples: Vec<T>, channels: usize) -> Vec<T> {
+    for px in samples.chunks_exact_mut(channels) {
+        px.swap(0, 2);
+    }
+    samples
+}
+
+/// 与 OpenCV 相同的 BT.601 定点系数 (image crate 的 to_luma 使用 BT.709)
+fn rgb_to_gray<T: Copy + Into<u32> + TryFrom<u32>>(rgb: &[T]) -> Vec<T> {
+    rgb.chunks_exact(3)
+        .map(|px| {
+            let [r, g, b] = [px[0].into(), px[1].into(), px[2].into()];
+            let y = (r * 4899 + g * 9617 + b * 1868 + (1 << 13)) >> 14;
+            T::try_from(y)
+                .ok()
+                .expect("gray value fits the sample type")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 平滑渐变 + 一块高频棋盘格，尺寸不是 16 的倍数
+    fn test_image(channels: u8) -> Mat {
+        let mut mat = Mat::new(37, 45, channels);
+        let cn = channels as usize;
+        for y in 0..37 {
+            for x in 0..45 {
+                let checker = if x > 30 && y > 20 && (x + y) % 2 == 0 {
+                    60
+                } else {
+                    0
+                };
+                let px = [x * 4 + checker, y * 5 + checker, 200 - x * 2, 128 + y];
+                let at = y * mat.step + x * cn;
+                for (dst, &v) in mat.data[at..at + cn].iter_mut().zip(&px) {
+                    *dst = v as u8;
+                }
+            }
+        }
+        mat
+    }
+
+    fn mean_abs_diff(a: &Mat, b: &Mat) -> f64 {
+        assert_eq!((a.rows, a.cols, a.channels), (b.rows, b.cols, b.channels));
+        let sum: u64 = a
+            .data
+            .iter()
+            .zip(&b.data)
+            .map(|(&x, &y)| x.abs_diff(y) as u64)
+            .sum();
+        sum as f64 / a.data.len() as f64
+    }
+
+    /// SOF 标记及其中各分量的采样因子
+    fn sof(jpeg: &[u8]) -> (u8, Vec<u8>) {
+        let at = jpeg
+            .windows(2)
+            .position(|w| w[0] == 0xFF && (w[1] == 0xC0 || w[1] == 0xC2))
+            .unwrap();
+        let n = jpeg[at + 9] as usize;
+        (
+            jpeg[at + 1],
+            (0..n).map(|i| jpeg[at + 11 + 3 * i]).collect(),
+        )
+    }
+
+    #[test]
+    fn jpeg_quality_trades_size_for_error() {
+        let mat = test_image(3);
+        let mut last: Option<(usize, f64)> = None;
+        for q in [20, 60, 95] {
+            let buf = imencode(".jpg", &mat, &[IMWRITE_JPEG_QUALITY, q]).unwrap();
+            let decoded = imdecode(&buf, IMREAD_COLOR).unwrap();
+            let err = mean_abs_diff(&mat, &decoded);
+            if let Some((size, last_err)) = last {
+                assert!(buf.len() > size, "q{} is not larger", q);
+                assert!(err < last_err, "q{} is not more accurate", q);
+            }
+            last = Some((buf.len(), err));
+        }
+        assert!(last.unwrap().1 < 3.0);
+    }
+
+    #[test]
+    fn jpeg_sampling_and_progressive() {
+        let mat = test_image(3);
+        let cases = [
+            (IMWRITE_JPEG_SAMPLING_FACTOR_444, 0x11),
+            (IMWRITE_JPEG_SAMPLING_FACTOR_422, 0x21),
+            (IMWRITE_JPEG_SAMPLING_FACTOR_420, 0x22),
+            (IMWRITE_JPEG_SAMPLING_FACTOR_440, 0x12),
+            (IMWRITE_JPEG_SAMPLING_FACTOR_411, 0x41),
+        ];
+        for (factor, luma) in cases {
+            for progressive in [0, 1] {
+                let params = [
+                    IMWRITE_JPEG_SAMPLING_FACTOR,
+                    factor,
+                    IMWRITE_JPEG_PROGRESSIVE,
+                    progressive,
+                ];
+                let buf = imencode("jpeg", &mat, &params).unwrap();
+                let marker = if progressive == 1 { 0xC2 } else { 0xC0 };
+                assert_eq!(sof(&buf), (marker, vec![luma, 0x11, 0x11]));
+                let decoded = imdecode(&buf, IMREAD_COLOR).unwrap();
+                assert!(mean_abs_diff(&mat, &decoded) < 4.0, "{:#x}", factor);
+            }
+        }
+        assert!(imencode(".jpg", &mat, &[IMWRITE_JPEG_SAMPLING_FACTOR, 0x331111]).is_err());
+
+        // 灰度只有一个分量
+        let gray = test_image(1);
+        let buf = imencode(".jpg", &gray, &[IMWRITE_JPEG_PROGRESSIVE, 1]).unwrap();
+        assert_eq!(sof(&buf), (0xC2, vec![0x11]));
+        let decoded = imdecode(&buf, IMREAD_UNCHANGED).unwrap();
+        assert!(mean_abs_diff(&gray, &decoded) < 2.0);
+    }
+
+    #[test]
+    fn png_levels_are_lossless() {
+        let mat = test_image(3);
+        let stored = imencode(".png", &mat, &[IMWRITE_PNG_COMPRESSION, 0]).unwrap();
+        let best = imencode(".png", &mat, &[IMWRITE_PNG_COMPRESSION, 9]).unwrap();
+        assert!(best.len() < stored.len() / 2);
+        for buf in [stored, best] {
+            assert_eq!(imdecode(&buf, IMREAD_COLOR).unwrap().data, mat.data);
+        }
+
+        // 1 / 4 通道在 IMREAD_UNCHANGED 下保持通道数
+        for channels in [1, 4] {
+            let mat = test_image(channels);
+            let buf = imencode("png", &mat, &[]).unwrap();
+            let decoded = imdecode(&buf, IMREAD_UNCHANGED).unwrap();
+            assert_eq!(decoded.channels, channels);
+            assert_eq!(decoded.data, mat.data);
+        }
+    }
+
+    #[test]
+    fn webp_quality() {
+        let mat = test_image(4);
+        let buf = imencode(".webp", &mat, &[]).unwrap();
+        assert_eq!(imdecode(&buf, IMREAD_UNCHANGED).unwrap().data, mat.data);
+        assert!(imencode(".webp", &mat, &[IMWRITE_WEBP_QUALITY, 101]).is_ok());
+
+        let lossy = imencode(".webp", &mat, &[IMWRITE_WEBP_QUALITY, 80]);
+        if cfg!(feature = "webp") {
+            let decoded = imdecode(&lossy.unwrap(), IMREAD_UNCHANGED).unwrap();
+            assert_eq!((decoded.rows, decoded.cols), (mat.rows, mat.cols));
+            assert_eq!(decoded.channels, 4);
+            let gray = imencode(".webp", &test_image(1), &[IMWRITE_WEBP_QUALITY, 50]);
+            assert_eq!(
+                imdecode(&gray.unwrap(), IMREAD_GRAYSCALE).unwrap().channels,
+                1
+            );
+        } else {
+            assert!(lossy.is_err());
+        }
+    }
+
+    #[test]
+    fn decode_flags_and_other_formats() {
+        let mat = test_image(3);
+        let buf = imencode(".bmp", &mat, &[]).unwrap();
+        assert_eq!(imdecode(&buf, IMREAD_COLOR).unwrap().data, mat.data);
+
+        let gray = imdecode(&buf, IMREAD_GRAYSCALE).unwrap();
+        assert_eq!(gray.channels, 1);
+        // BGR (10, 200, 30) -> 0.114 * 10 + 0.587 * 200 + 0.299 * 30 = 127.5
+        let mut px = Mat::new(1, 1, 3);
+        px.data.copy_from_slice(&[10, 200, 30]);
+        let buf = imencode(".png", &px, &[]).unwrap();
+        assert_eq!(imdecode(&buf, IMREAD_GRAYSCALE).unwrap().data, [128]);
+
+        assert!(imencode(".xyz", &mat, &[]).is_err());
+        assert!(imencode(".png", &mat, &[IMWRITE_PNG_COMPRESSION]).is_err());
+        assert!(imencode(".png", &Mat::empty(), &[]).is_err());
+        assert!(imdecode(b"not an image", IMREAD_COLOR).is_err());
+    }
+
+    /// 覆盖完整 16 位范围的测试图
+    fn wide_image(channels: u8) -> Mat {
+        let mut mat = Mat::with_depth(5, 7, channels, CV_16U);
+        let samples = (0..5 * 7 * channels as u32).map(|i| (i * 1871 % 65536) as u16);
+        mat.data = samples.flat_map(|v| v.to_ne_bytes()).collect();
+        mat
+    }
+
+    #[test]
+    fn wide_images_round_trip_through_files() {
+        let dir = std::env::temp_dir().join(format!("rustcv-imgcodecs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cases = [
+            ("png", 1),
+            ("png", 3),
+            ("png", 4),
+            ("tiff", 1),
+            ("tiff", 3),
+            ("tiff", 4),
+            ("pgm", 1),
+            ("ppm", 3),
+        ];
+        for (ext, channels) in cases {
+            for mat in [test_image(channels), wide_image(channels)] {
+                let path = dir.join(format!("img.{}", ext));
+                imwrite(&path, &mat).unwrap();
+                let read = imread_with_flags(&path, IMREAD_UNCHANGED).unwrap();
+                let desc = format!("{} {}C depth {}", ext, channels, mat.depth);
+                assert_eq!(
+                    (read.channels, read.depth),
+                    (channels, mat.depth),
+                    "{}",
+                    desc
+                );
+                assert_eq!(read.data, mat.data, "{}", desc);
+            }
+        }
+        assert!(imwrite(dir.join("img.pgm"), &test_image(3)).is_err());
+        assert!(imwrite(dir.join("img.jpg"), &wide_image(1)).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_flags_select_depth_and_channels() {
+        let gray = imencode(".png", &wide_image(1), &[]).unwrap();
+        let color = imencode(".png", &wide_image(4), &[]).unwrap();
+        let shape = |buf: &[u8], flags| {
+            let mat = imdecode(buf, flags).unwrap();
+            (mat.channels, mat.depth)
+        };
+
+        assert_eq!(shape(&gray, IMREAD_COLOR), (3, CV_8U));
+        assert_eq!(shape(&gray, IMREAD_GRAYSCALE), (1, CV_8U));
+        assert_eq!(shape(&gray, IMREAD_ANYDEPTH), (1, CV_16U));
+        assert_eq!(shape(&gray, IMREAD_ANYCOLOR), (1, CV_8U));
+        assert_eq!(shape(&gray, IMREAD_ANYDEPTH | IMREAD_COLOR), (3, CV_16U));
+        assert_eq!(shape(&color, IMREAD_UNCHANGED), (4, CV_16U));
+        assert_eq!(
+            shape(&color, IMREAD_ANYCOLOR | IMREAD_ANYDEPTH),
+            (3, CV_16U)
+        );
+        assert_eq!(shape(&color, IMREAD_GRAYSCALE), (1, CV_8U));
+
+        // 16 位转 8 位取高字节
+        let mut mat = Mat::with_depth(1, 1, 1, CV_16U);
+        mat.data = 0xABFFu16.to_ne_bytes().to_vec().into();
+        let buf = imencode(".png", &mat, &[]).unwrap();
+        assert_eq!(imdecode(&buf, IMREAD_GRAYSCALE).unwrap().data, [0xAB]);
+    }
 }
@@ -0,0 +1,141 @@
+//! PNG 编码器
+//!
+//! image crate 只提供 Fast/Default/Best 三档压缩，这里直接用 zlib 以实现
+//! OpenCV `IMWRITE_PNG_COMPRESSION` 的 0-9 级别。每行自适应选择滤波器
+//! (与 libpng 的默认策略相同：取滤波后绝对值之和最小者)。
+//! 支持 8 位与 16 位 (`CV_16U`) 图像。
+
+use crate::core::mat::{Mat, CV_16U, CV_8U};
+use anyhow::{anyhow, Result};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// 编码 1 通道 (灰度)、3 通道 (BGR) 或 4 通道 (BGRA) 的 8 / 16 位图像
+///
+/// `level` 为 zlib 压缩级别 0-9，0 表示不压缩 (此时也不做滤波)。
+pub(crate) fn encode(mat: &Mat, level: u32) -> Result<Vec<u8>> {
+    let color_type = match mat.channels {
+        1 => 0,
+        3 => 2,
+        4 => 6,
+        n => return Err(anyhow!("Unsupported channel count for PNG: {}", n)),
+    };
+    let bit_depth = match mat.depth {
+        CV_8U => 8,
+        CV_16U => 16,
+        d => return Err(anyhow!("Unsupported depth for PNG: {}", d)),
+    };
+    if mat.is_empty() {
+        return Err(anyhow!("Cannot encode an empty image"));
+    }
+    let level = level.min(9);
+
+    let mut ihdr = vec![];
+    ihdr.extend_from_slice(&(mat.cols as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(mat.rows as u32).to_be_bytes());
+    // 压缩/滤波/隔行方法均为 0
+    ihdr.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+
+    let size = mat.elem_size1();
+    let bpp = mat.elem_size();
+    let row_len = mat.cols as usize * bpp;
+    let mut zlib = ZlibEncoder::new(vec![], Compression::new(level));
+    let mut prev = vec![0u8; row_len];
+    let mut cur = Vec::with_capacity(row_len);
+    let mut filtered = vec![0u8; row_len + 1];
+    let mut best = vec![0u8; row_len + 1];
+
+    for r in 0..mat.rows {
+        // BGR(A) -> RGB(A)，16 位采样转为大端序
+        cur.clear();
+        cur.extend_from_slice(mat.row_bytes(r));
+        if mat.channels >= 3 {
+            for px in cur.chunks_exact_mut(bpp) {
+                for k in 0..size {
+                    px.swap(k, 2 * size + k);
+                }
+            }
+        }
+        if size == 2 {
+            for sample in cur.chunks_exact_mut(2) {
+                let v = u16::from_ne_bytes([sample[0], sample[1]]);
+                sample.copy_from_slice(&v.to_be_bytes());
+            }
+        }
+
+        if level == 0 {
+            best[0] = 0;
+            best[1..].copy_from_slice(&cur);
+        } else {
+            let mut best_cost = u64::MAX;
+            for filter in 0..5 {
+                apply_filter(filter, &cur, &prev, bpp, &mut filtered);
+                let cost = filtered[1..]
+                    .iter()
+                    .map(|&b| (b as i8).unsigned_abs() as u64)
+                    .sum();
+                if cost < best_cost {
+                    best_cost = cost;
+                    std::mem::swap(&mut best, &mut filtered);
+                }
+            }
+        }
+        zlib.write_all(&best)?;
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    let idat = zlib.finish()?;
+
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &ihdr);
+    chunk(&mut out, b"IDAT", &idat);
+    chunk(&mut out, b"IEND", &[]);
+    Ok(out)
+}
+
+/// 按 PNG 滤波类型处理一行，`out[0]` 为滤波类型字节
+fn apply_filter(filter: u8, cur: &[u8], prev: &[u8], bpp: usize, out: &mut [u8]) {
+    out[0] = filter;
+    for i in 0..cur.len() {
+        let a = if i >= bpp { cur[i - bpp] } else { 0 };
+        let b = prev[i];
+        let c = if i >= bpp { prev[i - bpp] } else { 0 };
+        let predictor = match filter {
+            0 => 0,
+            1 => a,
+            2 => b,
+            3 => ((a as u16 + b as u16) / 2) as u8,
+            _ => paeth(a, b, c),
+        };
+        out[i + 1] = cur[i].wrapping_sub(predictor);
+    }
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// 写出 长度 + 类型 + 数据 + CRC
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    out.extend_from_slice(&crc.finalize().to_be_bytes());
+}
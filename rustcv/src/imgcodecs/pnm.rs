@@ -0,0 +1,50 @@
+//! PGM / PPM 编码器 (二进制 P5 / P6)
+//!
+//! image crate 按扩展名保存 `.pgm` 时实际写出的是 PAM (P7)，且不支持 16 位 PGM，
+//! 这里直接写出标准的 P5 / P6，16 位采样按规范使用大端序。
+
+use crate::core::mat::{Mat, CV_16U, CV_8U};
+use anyhow::{anyhow, Result};
+
+/// `ext` 为 `pgm` / `ppm` / `pnm`，`pnm` 时按通道数选择
+pub(crate) fn encode(ext: &str, mat: &Mat) -> Result<Vec<u8>> {
+    let magic = match (ext, mat.channels) {
+        ("pgm" | "pnm", 1) => "P5",
+        ("ppm" | "pnm", 3) => "P6",
+        (_, n) => {
+            return Err(anyhow!(
+                "Unsupported channel count for {}: {}",
+                ext.to_ascii_uppercase(),
+                n
+            ))
+        }
+    };
+    let maxval = match mat.depth {
+        CV_8U => 255,
+        CV_16U => 65535,
+        d => return Err(anyhow!("Unsupported depth for PNM: {}", d)),
+    };
+
+    let mut out = format!("{}\n{} {}\n{}\n", magic, mat.cols, mat.rows, maxval).into_bytes();
+    let size = mat.elem_size1();
+    for r in 0..mat.rows {
+        let start = out.len();
+        out.extend_from_slice(mat.row_bytes(r));
+        let row = &mut out[start..];
+        if mat.channels == 3 {
+            // BGR -> RGB
+            for px in row.chunks_exact_mut(3 * size) {
+                for k in 0..size {
+                    px.swap(k, 2 * size + k);
+                }
+            }
+        }
+        if size == 2 {
+            for sample in row.chunks_exact_mut(2) {
+                let v = u16::from_ne_bytes([sample[0], sample[1]]);
+                sample.copy_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,1110 @@
+//! 颜色空间转换 (对应 OpenCV 的 `cvtColor`)
+//!
+//! 8 位图像的灰度、YCrCb、HSV 与 YUV 输入沿用 OpenCV 的定点算法，其余转换按相同公式
+//! 用浮点计算后饱和取整，结果与 OpenCV 相差不超过 1。
+//!
+//! - 通道重排、灰度、YCrCb、XYZ：支持 `CV_8U` / `CV_16U` / `CV_32F` (不支持 `CV_16S`)
+//! - HSV、HLS、Lab、Luv：支持 `CV_8U` / `CV_32F`；浮点图像的色调范围为 0-360，
+//!   Lab / Luv 输入的 BGR 取值范围为 0-1
+//! - YUV 输入：只支持 `CV_8U`。4:2:0 格式 (NV12 / NV21 / I420 / YV12) 为高 `rows * 3 / 2`
+//!   的单通道图像，4:2:2 格式 (YUYV / UYVY / YVYU) 为双通道图像
+
+// 常量名与 OpenCV 保持一致 (如 COLOR_BGR2YCrCb)
+#![allow(non_upper_case_globals)]
+
+use crate::core::mat::{
+    cv_make_type, dispatch_depth, type_name, Mat, Sample, CV_16S, CV_16U, CV_32F, CV_8U,
+};
+use crate::core::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+
+pub const COLOR_BGR2BGRA: i32 = 0;
+pub const COLOR_RGB2RGBA: i32 = COLOR_BGR2BGRA;
+pub const COLOR_BGRA2BGR: i32 = 1;
+pub const COLOR_RGBA2RGB: i32 = COLOR_BGRA2BGR;
+pub const COLOR_BGR2RGBA: i32 = 2;
+pub const COLOR_RGB2BGRA: i32 = COLOR_BGR2RGBA;
+pub const COLOR_RGBA2BGR: i32 = 3;
+pub const COLOR_BGRA2RGB: i32 = COLOR_RGBA2BGR;
+pub const COLOR_BGR2RGB: i32 = 4;
+pub const COLOR_RGB2BGR: i32 = COLOR_BGR2RGB;
+pub const COLOR_BGRA2RGBA: i32 = 5;
+pub const COLOR_RGBA2BGRA: i32 = COLOR_BGRA2RGBA;
+
+pub const COLOR_BGR2GRAY: i32 = 6;
+pub const COLOR_RGB2GRAY: i32 = 7;
+pub const COLOR_GRAY2BGR: i32 = 8;
+pub const COLOR_GRAY2RGB: i32 = COLOR_GRAY2BGR;
+pub const COLOR_GRAY2BGRA: i32 = 9;
+pub const COLOR_GRAY2RGBA: i32 = COLOR_GRAY2BGRA;
+pub const COLOR_BGRA2GRAY: i32 = 10;
+pub const COLOR_RGBA2GRAY: i32 = 11;
+
+pub const COLOR_BGR2XYZ: i32 = 32;
+pub const COLOR_RGB2XYZ: i32 = 33;
+pub const COLOR_XYZ2BGR: i32 = 34;
+pub const COLOR_XYZ2RGB: i32 = 35;
+
+pub const COLOR_BGR2YCrCb: i32 = 36;
+pub const COLOR_RGB2YCrCb: i32 = 37;
+pub const COLOR_YCrCb2BGR: i32 = 38;
+pub const COLOR_YCrCb2RGB: i32 = 39;
+
+pub const COLOR_BGR2HSV: i32 = 40;
+pub const COLOR_RGB2HSV: i32 = 41;
+pub const COLOR_BGR2Lab: i32 = 44;
+pub const COLOR_RGB2Lab: i32 = 45;
+pub const COLOR_BGR2Luv: i32 = 50;
+pub const COLOR_RGB2Luv: i32 = 51;
+pub const COLOR_BGR2HLS: i32 = 52;
+pub const COLOR_RGB2HLS: i32 = 53;
+
+pub const COLOR_HSV2BGR: i32 = 54;
+pub const COLOR_HSV2RGB: i32 = 55;
+pub const COLOR_Lab2BGR: i32 = 56;
+pub const COLOR_Lab2RGB: i32 = 57;
+pub const COLOR_Luv2BGR: i32 = 58;
+pub const COLOR_Luv2RGB: i32 = 59;
+pub const COLOR_HLS2BGR: i32 = 60;
+pub const COLOR_HLS2RGB: i32 = 61;
+
+/// `_FULL` 版本的 8 位色调使用 0-255 的完整范围，而不是 0-180
+pub const COLOR_BGR2HSV_FULL: i32 = 66;
+pub const COLOR_RGB2HSV_FULL: i32 = 67;
+pub const COLOR_BGR2HLS_FULL: i32 = 68;
+pub const COLOR_RGB2HLS_FULL: i32 = 69;
+pub const COLOR_HSV2BGR_FULL: i32 = 70;
+pub const COLOR_HSV2RGB_FULL: i32 = 71;
+pub const COLOR_HLS2BGR_FULL: i32 = 72;
+pub const COLOR_HLS2RGB_FULL: i32 = 73;
+
+// YUV 4:2:0
+pub const COLOR_YUV2RGB_NV12: i32 = 90;
+pub const COLOR_YUV2BGR_NV12: i32 = 91;
+pub const COLOR_YUV2RGB_NV21: i32 = 92;
+pub const COLOR_YUV2BGR_NV21: i32 = 93;
+pub const COLOR_YUV420sp2RGB: i32 = COLOR_YUV2RGB_NV21;
+pub const COLOR_YUV420sp2BGR: i32 = COLOR_YUV2BGR_NV21;
+
+pub const COLOR_YUV2RGBA_NV12: i32 = 94;
+pub const COLOR_YUV2BGRA_NV12: i32 = 95;
+pub const COLOR_YUV2RGBA_NV21: i32 = 96;
+pub const COLOR_YUV2BGRA_NV21: i32 = 97;
+
+pub const COLOR_YUV2RGB_YV12: i32 = 98;
+pub const COLOR_YUV2BGR_YV12: i32 = 99;
+pub const COLOR_YUV2RGB_IYUV: i32 = 100;
+pub const COLOR_YUV2BGR_IYUV: i32 = 101;
+pub const COLOR_YUV2RGB_I420: i32 = COLOR_YUV2RGB_IYUV;
+pub const COLOR_YUV2BGR_I420: i32 = COLOR_YUV2BGR_IYUV;
+
+pub const COLOR_YUV2RGBA_YV12: i32 = 102;
+pub const COLOR_YUV2BGRA_YV12: i32 = 103;
+pub const COLOR_YUV2RGBA_IYUV: i32 = 104;
+pub const COLOR_YUV2BGRA_IYUV: i32 = 105;
+pub const COLOR_YUV2RGBA_I420: i32 = COLOR_YUV2RGBA_IYUV;
+pub const COLOR_YUV2BGRA_I420: i32 = COLOR_YUV2BGRA_IYUV;
+
+pub const COLOR_YUV2GRAY_420: i32 = 106;
+pub const COLOR_YUV2GRAY_NV21: i32 = COLOR_YUV2GRAY_420;
+pub const COLOR_YUV2GRAY_NV12: i32 = COLOR_YUV2GRAY_420;
+pub const COLOR_YUV2GRAY_YV12: i32 = COLOR_YUV2GRAY_420;
+pub const COLOR_YUV2GRAY_IYUV: i32 = COLOR_YUV2GRAY_420;
+pub const COLOR_YUV2GRAY_I420: i32 = COLOR_YUV2GRAY_420;
+
+// YUV 4:2:2
+pub const COLOR_YUV2RGB_UYVY: i32 = 107;
+pub const COLOR_YUV2BGR_UYVY: i32 = 108;
+pub const COLOR_YUV2RGBA_UYVY: i32 = 111;
+pub const COLOR_YUV2BGRA_UYVY: i32 = 112;
+
+pub const COLOR_YUV2RGB_YUY2: i32 = 115;
+pub const COLOR_YUV2BGR_YUY2: i32 = 116;
+pub const COLOR_YUV2RGB_YVYU: i32 = 117;
+pub const COLOR_YUV2BGR_YVYU: i32 = 118;
+pub const COLOR_YUV2RGB_YUYV: i32 = COLOR_YUV2RGB_YUY2;
+pub const COLOR_YUV2BGR_YUYV: i32 = COLOR_YUV2BGR_YUY2;
+
+pub const COLOR_YUV2RGBA_YUY2: i32 = 119;
+pub const COLOR_YUV2BGRA_YUY2: i32 = 120;
+pub const COLOR_YUV2RGBA_YVYU: i32 = 121;
+pub const COLOR_YUV2BGRA_YVYU: i32 = 122;
+pub const COLOR_YUV2RGBA_YUYV: i32 = COLOR_YUV2RGBA_YUY2;
+pub const COLOR_YUV2BGRA_YUYV: i32 = COLOR_YUV2BGRA_YUY2;
+
+pub const COLOR_YUV2GRAY_UYVY: i32 = 123;
+pub const COLOR_YUV2GRAY_YUY2: i32 = 124;
+pub const COLOR_YUV2GRAY_YUYV: i32 = COLOR_YUV2GRAY_YUY2;
+pub const COLOR_YUV2GRAY_YVYU: i32 = COLOR_YUV2GRAY_YUY2;
+
+/// 转换颜色空间，`code` 为 `COLOR_BGR2GRAY` 等常量，`dst` 按需重新分配
+///
+/// ```no_run
+/// # use rustcv::core::mat::Mat;
+/// # use rustcv::imgproc::{cvt_color, COLOR_BGR2HSV};
+/// # let frame = Mat::empty();
+/// let mut hsv = Mat::empty();
+/// cvt_color(&frame, &mut hsv, COLOR_BGR2HSV)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn cvt_color<A: AsMatView + ?Sized>(src: &A, dst: &mut Mat, code: i32) -> Result<()> {
+    let src = src.view();
+    let conversion =
+        conversion(code).ok_or_else(|| anyhow!("cvt_color: unknown conversion code {}", code))?;
+    conversion.check(&src, code)?;
+
+    match conversion {
+        Conversion::Reorder { dcn, swap, .. } => {
+            dispatch_depth!(src.depth, reorder(&src, dst, dcn, swap))
+        }
+        Conversion::ToGray { bidx, .. } => dispatch_depth!(src.depth, to_gray(&src, dst, bidx)),
+        Conversion::FromGray { dcn } => dispatch_depth!(src.depth, from_gray(&src, dst, dcn)),
+        Conversion::ToSpace { space, bidx } => {
+            dispatch_depth!(src.depth, to_space(&src, dst, space, bidx))
+        }
+        Conversion::FromSpace { space, bidx } => {
+            dispatch_depth!(src.depth, from_space(&src, dst, space, bidx))
+        }
+        Conversion::Yuv420 { planes, dcn, bidx } => yuv420(&src, dst, planes, dcn, bidx),
+        Conversion::Yuv422 { order, dcn, bidx } => yuv422(&src, dst, order, dcn, bidx),
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Space {
+    Xyz,
+    YCrCb,
+    /// `full` 为 true 时 8 位色调范围为 0-255，否则为 0-180
+    Hsv {
+        full: bool,
+    },
+    Hls {
+        full: bool,
+    },
+    Lab,
+    Luv,
+}
+
+/// 4:2:0 色度平面的排列方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Planes {
+    /// Y 平面后接交错的 UV
+    Nv12,
+    /// Y 平面后接交错的 VU
+    Nv21,
+    /// Y、U、V 三个平面
+    I420,
+    /// Y、V、U 三个平面
+    Yv12,
+}
+
+/// 4:2:2 宏像素 (2 个像素 4 字节) 中 Y0、U、V 的位置，Y1 在 Y0 之后 2 字节
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Order {
+    y: usize,
+    u: usize,
+    v: usize,
+}
+
+const YUYV: Order = Order { y: 0, u: 1, v: 3 };
+const UYVY: Order = Order { y: 1, u: 0, v: 2 };
+const YVYU: Order = Order { y: 0, u: 3, v: 1 };
+
+/// 转换种类；`bidx` 为彩色图像中蓝色通道的下标 (BGR 为 0，RGB 为 2)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Conversion {
+    /// 交换红蓝通道和 / 或增删 Alpha 通道
+    Reorder {
+        scn: u8,
+        dcn: u8,
+        swap: bool,
+    },
+    ToGray {
+        scn: u8,
+        bidx: usize,
+    },
+    FromGray {
+        dcn: u8,
+    },
+    ToSpace {
+        space: Space,
+        bidx: usize,
+    },
+    FromSpace {
+        space: Space,
+        bidx: usize,
+    },
+    /// `dcn` 为 1 时只取亮度
+    Yuv420 {
+        planes: Planes,
+        dcn: u8,
+        bidx: usize,
+    },
+    Yuv422 {
+        order: Order,
+        dcn: u8,
+        bidx: usize,
+    },
+}
+
+// 转换码对照表，每行一项
+#[rustfmt::skip]
+fn conversion(code: i32) -> Option<Conversion> {
+    use Conversion::*;
+    use Planes::*;
+    use Space::*;
+
+    let hsv = Hsv { full: false };
+    let hsv_full = Hsv { full: true };
+    let hls = Hls { full: false };
+    let hls_full = Hls { full: true };
+
+    Some(match code {
+        COLOR_BGR2BGRA => Reorder { scn: 3, dcn: 4, swap: false },
+        COLOR_BGRA2BGR => Reorder { scn: 4, dcn: 3, swap: false },
+        COLOR_BGR2RGBA => Reorder { scn: 3, dcn: 4, swap: true },
+        COLOR_RGBA2BGR => Reorder { scn: 4, dcn: 3, swap: true },
+        COLOR_BGR2RGB => Reorder { scn: 3, dcn: 3, swap: true },
+        COLOR_BGRA2RGBA => Reorder { scn: 4, dcn: 4, swap: true },
+
+        COLOR_BGR2GRAY => ToGray { scn: 3, bidx: 0 },
+        COLOR_RGB2GRAY => ToGray { scn: 3, bidx: 2 },
+        COLOR_BGRA2GRAY => ToGray { scn: 4, bidx: 0 },
+        COLOR_RGBA2GRAY => ToGray { scn: 4, bidx: 2 },
+        COLOR_GRAY2BGR => FromGray { dcn: 3 },
+        COLOR_GRAY2BGRA => FromGray { dcn: 4 },
+
+        COLOR_BGR2XYZ => ToSpace { space: Xyz, bidx: 0 },
+        COLOR_RGB2XYZ => ToSpace { space: Xyz, bidx: 2 },
+        COLOR_XYZ2BGR => FromSpace { space: Xyz, bidx: 0 },
+        COLOR_XYZ2RGB => FromSpace { space: Xyz, bidx: 2 },
+        COLOR_BGR2YCrCb => ToSpace { space: YCrCb, bidx: 0 },
+        COLOR_RGB2YCrCb => ToSpace { space: YCrCb, bidx: 2 },
+        COLOR_YCrCb2BGR => FromSpace { space: YCrCb, bidx: 0 },
+        COLOR_YCrCb2RGB => FromSpace { space: YCrCb, bidx: 2 },
+
+        COLOR_BGR2HSV => ToSpace { space: hsv, bidx: 0 },
+        COLOR_RGB2HSV => ToSpace { space: hsv, bidx: 2 },
+        COLOR_BGR2HSV_FULL => ToSpace { space: hsv_full, bidx: 0 },
+        COLOR_RGB2HSV_FULL => ToSpace { space: hsv_full, bidx: 2 },
+        COLOR_HSV2BGR => FromSpace { space: hsv, bidx: 0 },
+        COLOR_HSV2RGB => FromSpace { space: hsv, bidx: 2 },
+        COLOR_HSV2BGR_FULL => FromSpace { space: hsv_full, bidx: 0 },
+        COLOR_HSV2RGB_FULL => FromSpace { space: hsv_full, bidx: 2 },
+
+        COLOR_BGR2HLS => ToSpace { space: hls, bidx: 0 },
+        COLOR_RGB2HLS => ToSpace { space: hls, bidx: 2 },
+        COLOR_BGR2HLS_FULL => ToSpace { space: hls_full, bidx: 0 },
+        COLOR_RGB2HLS_FULL => ToSpace { space: hls_full, bidx: 2 },
+        COLOR_HLS2BGR => FromSpace { space: hls, bidx: 0 },
+        COLOR_HLS2RGB => FromSpace { space: hls, bidx: 2 },
+        COLOR_HLS2BGR_FULL => FromSpace { space: hls_full, bidx: 0 },
+        COLOR_HLS2RGB_FULL => FromSpace { space: hls_full, bidx: 2 },
+
+        COLOR_BGR2Lab => ToSpace { space: Lab, bidx: 0 },
+        COLOR_RGB2Lab => ToSpace { space: Lab, bidx: 2 },
+        COLOR_Lab2BGR => FromSpace { space: Lab, bidx: 0 },
+        COLOR_Lab2RGB => FromSpace { space: Lab, bidx: 2 },
+        COLOR_BGR2Luv => ToSpace { space: Luv, bidx: 0 },
+        COLOR_RGB2Luv => ToSpace { space: Luv, bidx: 2 },
+        COLOR_Luv2BGR => FromSpace { space: Luv, bidx: 0 },
+        COLOR_Luv2RGB => FromSpace { space: Luv, bidx: 2 },
+
+        COLOR_YUV2RGB_NV12 => Yuv420 { planes: Nv12, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_NV12 => Yuv420 { planes: Nv12, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGB_NV21 => Yuv420 { planes: Nv21, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_NV21 => Yuv420 { planes: Nv21, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGBA_NV12 => Yuv420 { planes: Nv12, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_NV12 => Yuv420 { planes: Nv12, dcn: 4, bidx: 0 },
+        COLOR_YUV2RGBA_NV21 => Yuv420 { planes: Nv21, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_NV21 => Yuv420 { planes: Nv21, dcn: 4, bidx: 0 },
+        COLOR_YUV2RGB_YV12 => Yuv420 { planes: Yv12, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_YV12 => Yuv420 { planes: Yv12, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGB_IYUV => Yuv420 { planes: I420, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_IYUV => Yuv420 { planes: I420, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGBA_YV12 => Yuv420 { planes: Yv12, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_YV12 => Yuv420 { planes: Yv12, dcn: 4, bidx: 0 },
+        COLOR_YUV2RGBA_IYUV => Yuv420 { planes: I420, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_IYUV => Yuv420 { planes: I420, dcn: 4, bidx: 0 },
+        COLOR_YUV2GRAY_420 => Yuv420 { planes: I420, dcn: 1, bidx: 0 },
+
+        COLOR_YUV2RGB_UYVY => Yuv422 { order: UYVY, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_UYVY => Yuv422 { order: UYVY, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGBA_UYVY => Yuv422 { order: UYVY, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_UYVY => Yuv422 { order: UYVY, dcn: 4, bidx: 0 },
+        COLOR_YUV2RGB_YUY2 => Yuv422 { order: YUYV, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_YUY2 => Yuv422 { order: YUYV, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGB_YVYU => Yuv422 { order: YVYU, dcn: 3, bidx: 2 },
+        COLOR_YUV2BGR_YVYU => Yuv422 { order: YVYU, dcn: 3, bidx: 0 },
+        COLOR_YUV2RGBA_YUY2 => Yuv422 { order: YUYV, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_YUY2 => Yuv422 { order: YUYV, dcn: 4, bidx: 0 },
+        COLOR_YUV2RGBA_YVYU => Yuv422 { order: YVYU, dcn: 4, bidx: 2 },
+        COLOR_YUV2BGRA_YVYU => Yuv422 { order: YVYU, dcn: 4, bidx: 0 },
+        COLOR_YUV2GRAY_UYVY => Yuv422 { order: UYVY, dcn: 1, bidx: 0 },
+        COLOR_YUV2GRAY_YUY2 => Yuv422 { order: YUYV, dcn: 1, bidx: 0 },
+
+        _ => return None,
+    })
+}
+
+impl Conversion {
+    /// 检查输入的通道数、深度与尺寸
+    fn check(&self, src: &MatView<'_>, code: i32) -> Result<()> {
+        let (scn, depth_ok) = match *self {
+            Conversion::Reorder { scn, .. } | Conversion::ToGray { scn, .. } => (scn, true),
+            Conversion::FromGray { .. } => (1, true),
+            Conversion::ToSpace { space, .. } | Conversion::FromSpace { space, .. } => {
+                let any_depth = matches!(space, Space::Xyz | Space::YCrCb);
+                (3, any_depth || src.depth != CV_16U)
+            }
+            Conversion::Yuv420 { .. } => (1, src.depth == CV_8U),
+            Conversion::Yuv422 { .. } => (2, src.depth == CV_8U),
+        };
+        if src.channels != scn || !depth_ok || src.depth == CV_16S {
+            return Err(anyhow!(
+                "cvt_color: conversion code {} does not accept {} input",
+                code,
+                type_name(src.typ())
+            ));
+        }
+
+        match self {
+            Conversion::Yuv420 { .. } if src.rows % 3 != 0 || src.cols % 2 != 0 => Err(anyhow!(
+                "cvt_color: 4:2:0 input must have an even width and a height divisible by 3, got {}x{}",
+                src.cols,
+                src.rows
+            )),
+            Conversion::Yuv422 { .. } if src.cols % 2 != 0 => Err(anyhow!(
+                "cvt_color: 4:2:2 input must have an even width, got {}",
+                src.cols
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 8 位 / 16 位的白色取 255 / 65535，浮点为 1.0
+fn max_value<T: Sample>() -> f64 {
+    match T::DEPTH {
+        CV_8U => 255.0,
+        CV_16U => 65535.0,
+        _ => 1.0,
+    }
+}
+
+/// 色度分量的零点：8 位为 128，16 位为 32768，浮点为 0.5
+fn half_value<T: Sample>() -> f64 {
+    match T::DEPTH {
+        CV_8U => 128.0,
+        CV_16U => 32768.0,
+        _ => 0.5,
+    }
+}
+
+/// 逐像素转换到 `dcn` 通道的同深度图像
+fn map_pixels<T: Sample>(src: &MatView<'_>, dst: &mut Mat, dcn: u8, f: impl Fn(&[T], &mut [T])) {
+    dst.create(src.rows, src.cols, cv_make_type(src.depth, dcn));
+    let scn = src.channels as usize;
+    for r in 0..src.rows {
+        let s = src.row::<T>(r);
+        let d = dst.row_mut::<T>(r);
+        for (s, d) in s.chunks_exact(scn).zip(d.chunks_exact_mut(dcn as usize)) {
+            f(s, d);
+        }
+    }
+}
+
+/// 读取像素的 (b, g, r)
+fn bgr<T: Sample>(px: &[T], bidx: usize) -> [f64; 3] {
+    [px[bidx].to_f64(), px[1].to_f64(), px[bidx ^ 2].to_f64()]
+}
+
+/// 写出像素的 (b, g, r)，按输出深度饱和取整
+fn put_bgr<T: Sample>(px: &mut [T], bidx: usize, [b, g, r]: [f64; 3]) {
+    px[bidx] = T::from_f64(b);
+    px[1] = T::from_f64(g);
+    px[bidx ^ 2] = T::from_f64(r);
+}
+
+fn reorder<T: Sample>(src: &MatView<'_>, dst: &mut Mat, dcn: u8, swap: bool) {
+    let alpha = T::from_f64(max_value::<T>());
+    let scn = src.channels;
+    let (bi, ri) = if swap { (2, 0) } else { (0, 2) };
+    map_pixels::<T>(src, dst, dcn, |s, d| {
+        d[0] = s[bi];
+        d[1] = s[1];
+        d[2] = s[ri];
+        if dcn == 4 {
+            d[3] = if scn == 4 { s[3] } else { alpha };
+        }
+    });
+}
+
+// BT.601 亮度的 14 位定点系数
+const YUV_SHIFT: u32 = 14;
+const R2Y: i64 = 4899;
+const G2Y: i64 = 9617;
+const B2Y: i64 = 1868;
+
+fn descale(x: i64, n: u32) -> i64 {
+    (x + (1 << (n - 1))) >> n
+}
+
+fn to_gray<T: Sample>(src: &MatView<'_>, dst: &mut Mat, bidx: usize) {
+    map_pixels::<T>(src, dst, 1, |s, d| {
+        let [b, g, r] = bgr(s, bidx);
+        d[0] = T::from_f64(if T::DEPTH == CV_32F {
+            b * 0.114 + g * 0.587 + r * 0.299
+        } else {
+            descale(b as i64 * B2Y + g as i64 * G2Y + r as i64 * R2Y, YUV_SHIFT) as f64
+        });
+    });
+}
+
+fn from_gray<T: Sample>(src: &MatView<'_>, dst: &mut Mat, dcn: u8) {
+    let alpha = T::from_f64(max_value::<T>());
+    map_pixels::<T>(src, dst, dcn, |s, d| {
+        d[..3].fill(s[0]);
+        if dcn == 4 {
+            d[3] = alpha;
+        }
+    });
+}
+
+fn to_space<T: Sample>(src: &MatView<'_>, dst: &mut Mat, space: Space, bidx: usize) {
+    match space {
+        Space::YCrCb => to_ycrcb::<T>(src, dst, bidx),
+        Space::Hsv { full } if T::DEPTH == CV_8U => to_hsv_8u(src, dst, bidx, full),
+        _ => {
+            // XYZ 直接作用于原值；HLS / Lab / Luv 的 8 位输入先归一化到 0-1，
+            // 输出再按各空间的 8 位编码缩放
+            let scale = if T::DEPTH == CV_8U { 255.0 } else { 1.0 };
+            map_pixels::<T>(src, dst, 3, |s, d| {
+                let [b, g, r] = bgr(s, bidx);
+                let out = match space {
+                    Space::Xyz => mul(&RGB2XYZ, [r, g, b]),
+                    Space::Hsv { .. } => rgb_to_hsv(r, g, b),
+                    Space::Hls { full } => {
+                        let [h, l, s] = rgb_to_hls(r / scale, g / scale, b / scale);
+                        if T::DEPTH == CV_8U {
+                            [h * hue_range(full) / 360.0, l * 255.0, s * 255.0]
+                        } else {
+                            [h, l, s]
+                        }
+                    }
+                    Space::Lab => {
+                        let [l, a, b] = rgb_to_lab(r / scale, g / scale, b / scale);
+                        if T::DEPTH == CV_8U {
+                            [l * 255.0 / 100.0, a + 128.0, b + 128.0]
+                        } else {
+                            [l, a, b]
+                        }
+                    }
+                    Space::Luv => {
+                        let [l, u, v] = rgb_to_luv(r / scale, g / scale, b / scale);
+                        if T::DEPTH == CV_8U {
+                            [
+                                l * 255.0 / 100.0,
+                                (u + 134.0) * 255.0 / 354.0,
+                                (v + 140.0) * 255.0 / 262.0,
+                            ]
+                        } else {
+                            [l, u, v]
+                        }
+                    }
+                    Space::YCrCb => unreachable!(),
+                };
+                for (d, v) in d.iter_mut().zip(out) {
+                    *d = T::from_f64(v);
+                }
+            });
+        }
+    }
+}
+
+fn from_space<T: Sample>(src: &MatView<'_>, dst: &mut Mat, space: Space, bidx: usize) {
+    if space == Space::YCrCb {
+        return from_ycrcb::<T>(src, dst, bidx);
+    }
+    let scale = if T::DEPTH == CV_8U { 255.0 } else { 1.0 };
+    map_pixels::<T>(src, dst, 3, |s, d| {
+        let [c0, c1, c2] = [s[0].to_f64(), s[1].to_f64(), s[2].to_f64()];
+        // XYZ 是线性变换，直接作用于原值；其余空间先得到 0-1 的 BGR
+        let ([r, g, b], k) = match space {
+            Space::Xyz => (mul(&XYZ2RGB, [c0, c1, c2]), 1.0),
+            Space::Hsv { full } => {
+                let hrange = if T::DEPTH == CV_8U {
+                    inverse_hue_range(full)
+                } else {
+                    360.0
+                };
+                (hsv_to_rgb(c0 * 6.0 / hrange, c1 / scale, c2 / scale), scale)
+            }
+            Space::Hls { full } => {
+                let hrange = if T::DEPTH == CV_8U {
+                    inverse_hue_range(full)
+                } else {
+                    360.0
+                };
+                (hls_to_rgb(c0 * 6.0 / hrange, c1 / scale, c2 / scale), scale)
+            }
+            Space::Lab if T::DEPTH == CV_8U => (
+                lab_to_rgb(c0 * 100.0 / 255.0, c1 - 128.0, c2 - 128.0),
+                scale,
+            ),
+            Space::Lab => (lab_to_rgb(c0, c1, c2), scale),
+            Space::Luv if T::DEPTH == CV_8U => (
+                luv_to_rgb(
+                    c0 * 100.0 / 255.0,
+                    c1 * 354.0 / 255.0 - 134.0,
+                    c2 * 262.0 / 255.0 - 140.0,
+                ),
+                scale,
+            ),
+            Space::Luv => (luv_to_rgb(c0, c1, c2), scale),
+            Space::YCrCb => unreachable!(),
+        };
+        put_bgr(d, bidx, [b * k, g * k, r * k]);
+    });
+}
+
+/// 8 位 BGR -> HSV / HLS 的色调范围
+fn hue_range(full: bool) -> f64 {
+    if full {
+        256.0
+    } else {
+        180.0
+    }
+}
+
+/// 8 位 HSV / HLS -> BGR 的色调范围：OpenCV 的 `_FULL` 逆变换按 255 而不是 256 缩放
+fn inverse_hue_range(full: bool) -> f64 {
+    if full {
+        255.0
+    } else {
+        180.0
+    }
+}
+
+fn mul(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn to_ycrcb<T: Sample>(src: &MatView<'_>, dst: &mut Mat, bidx: usize) {
+    let half = half_value::<T>();
+    map_pixels::<T>(src, dst, 3, |s, d| {
+        let [b, g, r] = bgr(s, bidx);
+        let out = if T::DEPTH == CV_32F {
+            let y = b * 0.114 + g * 0.587 + r * 0.299;
+            [y, (r - y) * 0.713 + half, (b - y) * 0.564 + half]
+        } else {
+            let (b, g, r) = (b as i64, g as i64, r as i64);
+            let delta = (half as i64) << YUV_SHIFT;
+            let y = descale(b * B2Y + g * G2Y + r * R2Y, YUV_SHIFT);
+            let cr = descale((r - y) * 11682 + delta, YUV_SHIFT);
+            let cb = descale((b - y) * 9241 + delta, YUV_SHIFT);
+            [y as f64, cr as f64, cb as f64]
+        };
+        for (d, v) in d.iter_mut().zip(out) {
+            *d = T::from_f64(v);
+        }
+    });
+}
+
+fn from_ycrcb<T: Sample>(src: &MatView<'_>, dst: &mut Mat, bidx: usize) {
+    let half = half_value::<T>();
+    map_pixels::<T>(src, dst, 3, |s, d| {
+        let [y, cr, cb] = [s[0].to_f64(), s[1].to_f64() - half, s[2].to_f64() - half];
+        let out = if T::DEPTH == CV_32F {
+            [y + cb * 1.773, y - cb * 0.344 - cr * 0.714, y + cr * 1.403]
+        } else {
+            let (y, cr, cb) = (y as i64, cr as i64, cb as i64);
+            [
+                y + descale(cb * 29049, YUV_SHIFT),
+                y + descale(cb * -5636 + cr * -11698, YUV_SHIFT),
+                y + descale(cr * 22987, YUV_SHIFT),
+            ]
+            .map(|v| v as f64)
+        };
+        put_bgr(d, bidx, out);
+    });
+}
+
+/// 8 位 HSV 与 OpenCV 一样使用 12 位定点除法表
+fn to_hsv_8u(src: &MatView<'_>, dst: &mut Mat, bidx: usize, full: bool) {
+    const SHIFT: u32 = 12;
+    let hrange = hue_range(full);
+    let sdiv: Vec<i64> = (0..256)
+        .map(|i| match i {
+            0 => 0,
+            i => ((255 << SHIFT) as f64 / i as f64).round_ties_even() as i64,
+        })
+        .collect();
+    let hdiv: Vec<i64> = (0..256)
+        .map(|i| match i {
+            0 => 0,
+            i => (hrange * (1 << SHIFT) as f64 / (6.0 * i as f64)).round_ties_even() as i64,
+        })
+        .collect();
+
+    map_pixels::<u8>(src, dst, 3, |s, d| {
+        let [b, g, r] = [s[bidx] as i64, s[1] as i64, s[bidx ^ 2] as i64];
+        let v = b.max(g).max(r);
+        let diff = v - b.min(g).min(r);
+        let sat = descale(diff * sdiv[v as usize], SHIFT);
+        let h = if v == r {
+            g - b
+        } else if v == g {
+            b - r + 2 * diff
+        } else {
+            r - g + 4 * diff
+        };
+        let mut h = descale(h * hdiv[diff as usize], SHIFT);
+        if h < 0 {
+            h += hrange as i64;
+        }
+        d[0] = u8::from_f64(h as f64);
+        d[1] = sat as u8;
+        d[2] = v as u8;
+    });
+}
+
+/// 浮点 HSV：色调 0-360，饱和度与明度 0-1 (明度沿用输入的取值范围)
+fn rgb_to_hsv(r: f64, g: f64, b: f64) -> [f64; 3] {
+    let eps = f32::EPSILON as f64;
+    let v = r.max(g).max(b);
+    let diff = v - r.min(g).min(b);
+    let s = diff / (v.abs() + eps);
+    let k = 60.0 / (diff + eps);
+    let mut h = if v == r {
+        (g - b) * k
+    } else if v == g {
+        (b - r) * k + 120.0
+    } else {
+        (r - g) * k + 240.0
+    };
+    if h < 0.0 {
+        h += 360.0;
+    }
+    [h, s, v]
+}
+
+/// 色调的 6 个扇区中 (b, g, r) 分别取 `tab` 的哪一项
+const HUE_SECTORS: [[usize; 3]; 6] = [
+    [1, 3, 0],
+    [1, 0, 2],
+    [3, 0, 1],
+    [0, 2, 1],
+    [0, 1, 3],
+    [2, 1, 0],
+];
+
+/// 色调按扇区 (0-6) 给出；返回 0-1 的 (r, g, b)
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> [f64; 3] {
+    if s == 0.0 {
+        return [v, v, v];
+    }
+    let h = h.rem_euclid(6.0);
+    let sector = (h.floor() as usize).min(5);
+    let f = h - sector as f64;
+    let tab = [
+        v,
+        v * (1.0 - s),
+        v * (1.0 - s * f),
+        v * (1.0 - s * (1.0 - f)),
+    ];
+    let [b, g, r] = HUE_SECTORS[sector].map(|i| tab[i]);
+    [r, g, b]
+}
+
+/// 输入 0-1，返回 (色调 0-360, 亮度 0-1, 饱和度 0-1)
+fn rgb_to_hls(r: f64, g: f64, b: f64) -> [f64; 3] {
+    let eps = f32::EPSILON as f64;
+    let vmax = r.max(g).max(b);
+    let vmin = r.min(g).min(b);
+    let diff = vmax - vmin;
+    let l = (vmax + vmin) * 0.5;
+    if diff <= eps {
+        return [0.0, l, 0.0];
+    }
+    let s = if l < 0.5 {
+        diff / (vmax + vmin)
+    } else {
+        diff / (2.0 - vmax - vmin)
+    };
+    let k = 60.0 / diff;
+    let mut h = if vmax == r {
+        (g - b) * k
+    } else if vmax == g {
+        (b - r) * k + 120.0
+    } else {
+        (r - g) * k + 240.0
+    };
+    if h < 0.0 {
+        h += 360.0;
+    }
+    [h, l, s]
+}
+
+fn hls_to_rgb(h: f64, l: f64, s: f64) -> [f64; 3] {
+    if s == 0.0 {
+        return [l, l, l];
+    }
+    let p2 = if l <= 0.5 {
+        l * (1.0 + s)
+    } else {
+        l + s - l * s
+    };
+    let p1 = 2.0 * l - p2;
+    let h = h.rem_euclid(6.0);
+    let sector = (h.floor() as usize).min(5);
+    let f = h - sector as f64;
+    let tab = [p2, p1, p1 + (p2 - p1) * (1.0 - f), p1 + (p2 - p1) * f];
+    let [b, g, r] = HUE_SECTORS[sector].map(|i| tab[i]);
+    [r, g, b]
+}
+
+// sRGB (D65) 与 CIE XYZ 之间的转换矩阵
+const RGB2XYZ: [[f64; 3]; 3] = [
+    [0.412453, 0.357580, 0.180423],
+    [0.212671, 0.715160, 0.072169],
+    [0.019334, 0.119193, 0.950227],
+];
+const XYZ2RGB: [[f64; 3]; 3] = [
+    [3.240479, -1.53715, -0.498535],
+    [-0.969256, 1.875991, 0.041556],
+    [0.055648, -0.204043, 1.057311],
+];
+// D65 白点
+const XN: f64 = 0.950456;
+const ZN: f64 = 1.088754;
+
+fn srgb_to_linear(x: f64) -> f64 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(x: f64) -> f64 {
+    let x = x.clamp(0.0, 1.0);
+    if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn lab_f(t: f64) -> f64 {
+    if t > 0.008856 {
+        t.cbrt()
+    } else {
+        7.787 * t + 16.0 / 116.0
+    }
+}
+
+/// 输入 0-1 的 sRGB，返回 L (0-100)、a、b
+fn rgb_to_lab(r: f64, g: f64, b: f64) -> [f64; 3] {
+    let [x, y, z] = mul(&RGB2XYZ, [r, g, b].map(srgb_to_linear));
+    let (fx, fy, fz) = (lab_f(x / XN), lab_f(y), lab_f(z / ZN));
+    let l = if y > 0.008856 {
+        116.0 * fy - 16.0
+    } else {
+        903.3 * y
+    };
+    [l, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn lab_to_rgb(l: f64, a: f64, b: f64) -> [f64; 3] {
+    let (y, fy) = if l <= 7.9996 {
+        let y = l / 903.3;
+        (y, 7.787 * y + 16.0 / 116.0)
+    } else {
+        let fy = (l + 16.0) / 116.0;
+        (fy * fy * fy, fy)
+    };
+    let inv_f = |f: f64| {
+        if f > 0.206893 {
+            f * f * f
+        } else {
+            (f - 16.0 / 116.0) / 7.787
+        }
+    };
+    let x = XN * inv_f(fy + a / 500.0);
+    let z = ZN * inv_f(fy - b / 200.0);
+    mul(&XYZ2RGB, [x, y, z]).map(linear_to_srgb)
+}
+
+/// 白点的 (u', v')
+fn luv_white() -> (f64, f64) {
+    let d = 1.0 / (XN + 15.0 + 3.0 * ZN);
+    (4.0 * XN * d, 9.0 * d)
+}
+
+/// 输入 0-1 的 sRGB，返回 L (0-100)、u、v
+fn rgb_to_luv(r: f64, g: f64, b: f64) -> [f64; 3] {
+    let [x, y, z] = mul(&RGB2XYZ, [r, g, b].map(srgb_to_linear));
+    let (un, vn) = luv_white();
+    let l = 116.0 * lab_f(y) - 16.0;
+    let d = 1.0 / (x + 15.0 * y + 3.0 * z).max(f32::EPSILON as f64);
+    [
+        l,
+        13.0 * l * (4.0 * x * d - un),
+        13.0 * l * (9.0 * y * d - vn),
+    ]
+}
+
+fn luv_to_rgb(l: f64, u: f64, v: f64) -> [f64; 3] {
+    let (un, vn) = luv_white();
+    let y = if l <= 8.0 {
+        l / 903.3
+    } else {
+        let t = (l + 16.0) / 116.0;
+        t * t * t
+    };
+    let up = 3.0 * (u + 13.0 * l * un);
+    let vp = (0.25 / (v + 13.0 * l * vn)).clamp(-0.25, 0.25);
+    let x = 3.0 * y * up * vp;
+    let z = y * (((12.0 * 13.0) * l - up) * vp - 5.0);
+    mul(&XYZ2RGB, [x, y, z]).map(linear_to_srgb)
+}
+
+// BT.601 YUV -> RGB 的 20 位定点系数
+const ITUR_SHIFT: u32 = 20;
+const CY: i32 = 1220542;
+const CUB: i32 = 2116026;
+const CUG: i32 = -409993;
+const CVG: i32 = -852492;
+const CVR: i32 = 1673527;
+
+/// 把一个 YUV 像素写成 `dcn` 通道的 BGR(A) / RGB(A)
+fn put_yuv(px: &mut [u8], bidx: usize, y: u8, u: u8, v: u8) {
+    let (u, v) = (u as i32 - 128, v as i32 - 128);
+    let round = 1 << (ITUR_SHIFT - 1);
+    let y = (y as i32 - 16).max(0) * CY;
+    let clip = |x: i32| (x >> ITUR_SHIFT).clamp(0, 255) as u8;
+    px[bidx] = clip(y + round + CUB * u);
+    px[1] = clip(y + round + CVG * v + CUG * u);
+    px[bidx ^ 2] = clip(y + round + CVR * v);
+    if let Some(a) = px.get_mut(3) {
+        *a = 255;
+    }
+}
+
+fn yuv420(src: &MatView<'_>, dst: &mut Mat, planes: Planes, dcn: u8, bidx: usize) {
+    let (h, w) = (src.rows * 2 / 3, src.cols as usize);
+    dst.create(h, w as i32, cv_make_type(CV_8U, dcn));
+    if dcn == 1 {
+        for r in 0..h {
+            dst.row_bytes_mut(r).copy_from_slice(src.row_bytes(r));
+        }
+        return;
+    }
+
+    let half = w / 2;
+    // 三平面格式中每个色度平面有 h / 2 行、每行 w / 2 字节，即输入中的半行；
+    // 第 k 个半行位于第 h + k / 2 行，偏移 (k % 2) * w / 2
+    let half_row = |k: i32| &src.row_bytes(h + k / 2)[(k % 2) as usize * half..][..half];
+    for r in 0..h {
+        let y = src.row_bytes(r);
+        let (u, v, step): (&[u8], &[u8], usize) = match planes {
+            Planes::Nv12 => {
+                let uv = src.row_bytes(h + r / 2);
+                (uv, &uv[1..], 2)
+            }
+            Planes::Nv21 => {
+                let vu = src.row_bytes(h + r / 2);
+                (&vu[1..], vu, 2)
+            }
+            Planes::I420 => (half_row(r / 2), half_row(h / 2 + r / 2), 1),
+            Planes::Yv12 => (half_row(h / 2 + r / 2), half_row(r / 2), 1),
+        };
+        let d = dst.row_bytes_mut(r);
+        for (c, px) in d.chunks_exact_mut(dcn as usize).enumerate() {
+            let k = c / 2 * step;
+            put_yuv(px, bidx, y[c], u[k], v[k]);
+        }
+    }
+}
+
+fn yuv422(src: &MatView<'_>, dst: &mut Mat, order: Order, dcn: u8, bidx: usize) {
+    dst.create(src.rows, src.cols, cv_make_type(CV_8U, dcn));
+    let dcn = dcn as usize;
+    for r in 0..src.rows {
+        let s = src.row_bytes(r);
+        let d = dst.row_bytes_mut(r);
+        for (m, px) in s.chunks_exact(4).zip(d.chunks_exact_mut(2 * dcn)) {
+            let (y0, y1) = (m[order.y], m[order.y + 2]);
+            if dcn == 1 {
+                px.copy_from_slice(&[y0, y1]);
+            } else {
+                let (p0, p1) = px.split_at_mut(dcn);
+                put_yuv(p0, bidx, y0, m[order.u], m[order.v]);
+                put_yuv(p1, bidx, y1, m[order.u], m[order.v]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_32FC3, CV_8UC1, CV_8UC2, CV_8UC3};
+
+    fn convert(src: &Mat, code: i32) -> Mat {
+        let mut dst = Mat::empty();
+        cvt_color(src, &mut dst, code).unwrap();
+        dst
+    }
+
+    fn pixel(bgr: [u8; 3]) -> Mat {
+        Mat::from_slice(1, 1, CV_8UC3, &[bgr]).unwrap()
+    }
+
+    #[test]
+    fn matches_opencv_reference_values() {
+        let red = pixel([0, 0, 255]);
+        let blue = pixel([255, 0, 0]);
+        let white = pixel([255, 255, 255]);
+        let px = |m: Mat| m.data.as_slice().to_vec();
+
+        assert_eq!(px(convert(&pixel([10, 20, 30]), COLOR_BGR2GRAY)), [22]);
+        assert_eq!(px(convert(&pixel([10, 20, 30]), COLOR_RGB2GRAY)), [18]);
+        assert_eq!(px(convert(&red, COLOR_BGR2YCrCb)), [76, 255, 85]);
+        assert_eq!(px(convert(&red, COLOR_BGR2HSV)), [0, 255, 255]);
+        assert_eq!(px(convert(&blue, COLOR_BGR2HSV)), [120, 255, 255]);
+        assert_eq!(px(convert(&blue, COLOR_BGR2HSV_FULL)), [171, 255, 255]);
+        assert_eq!(
+            px(convert(&pixel([0, 128, 255]), COLOR_BGR2HSV)),
+            [15, 255, 255]
+        );
+        assert_eq!(px(convert(&red, COLOR_BGR2HLS)), [0, 128, 255]);
+        // 逆变换的色调范围是 255
+        let hsv = |h| pixel([h, 255, 255]);
+        assert_eq!(px(convert(&hsv(128), COLOR_HSV2BGR_FULL)), [255, 252, 0]);
+        assert_eq!(px(convert(&hsv(43), COLOR_HSV2BGR_FULL)), [0, 255, 252]);
+        assert_eq!(px(convert(&hsv(200), COLOR_HSV2BGR_FULL)), [255, 0, 180]);
+        let blue_full = convert(&blue, COLOR_BGR2HSV_FULL);
+        assert_eq!(px(convert(&blue_full, COLOR_HSV2BGR_FULL)), [255, 0, 6]);
+        let hls = |h, l| pixel([h, l, 255]);
+        assert_eq!(
+            px(convert(&hls(128, 128), COLOR_HLS2BGR_FULL)),
+            [255, 252, 1]
+        );
+        assert_eq!(px(convert(&hls(200, 64), COLOR_HLS2BGR_FULL)), [128, 0, 90]);
+        assert_eq!(px(convert(&red, COLOR_BGR2Lab)), [136, 208, 195]);
+        assert_eq!(px(convert(&blue, COLOR_BGR2Lab)), [82, 207, 20]);
+        assert_eq!(px(convert(&white, COLOR_BGR2Lab)), [255, 128, 128]);
+        assert_eq!(px(convert(&white, COLOR_BGR2Luv)), [255, 97, 136]);
+        assert_eq!(px(convert(&red, COLOR_BGR2RGB)), [255, 0, 0]);
+        assert_eq!(px(convert(&red, COLOR_BGR2BGRA)), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn round_trips_recover_the_input() {
+        let colors: Vec<[u8; 3]> = (0..6 * 6 * 6)
+            .map(|i| {
+                [
+                    (i % 6 * 51) as u8,
+                    (i / 6 % 6 * 51) as u8,
+                    (i / 36 * 51) as u8,
+                ]
+            })
+            .collect();
+        let img = Mat::from_slice(6, 36, CV_8UC3, &colors).unwrap();
+        let float = {
+            let mut f = Mat::empty();
+            img.convert_to(&mut f, CV_32F, 1.0 / 255.0, 0.0).unwrap();
+            f
+        };
+        // 8 位编码的量化误差：色调只有 180 级；XYZ 的 Z 分量会饱和，
+        // Lab / Luv 在接近 0 的通道上经过 sRGB 曲线后误差放大，这两者只检查浮点。
+        // `_FULL` 正向按 256、逆向按 255 缩放色调 (与 OpenCV 相同)，8 位往返本身就不还原，
+        // 由上面的参考值覆盖
+        for (to, back, tolerance) in [
+            (COLOR_BGR2YCrCb, COLOR_YCrCb2BGR, Some(1)),
+            (COLOR_BGR2XYZ, COLOR_XYZ2BGR, None),
+            (COLOR_BGR2HSV, COLOR_HSV2BGR, Some(3)),
+            (COLOR_BGR2HSV_FULL, COLOR_HSV2BGR_FULL, None),
+            (COLOR_RGB2HLS, COLOR_HLS2RGB, Some(3)),
+            (COLOR_BGR2HLS_FULL, COLOR_HLS2BGR_FULL, None),
+            (COLOR_BGR2Lab, COLOR_Lab2BGR, None),
+            (COLOR_BGR2Luv, COLOR_Luv2BGR, None),
+        ] {
+            if let Some(tolerance) = tolerance {
+                let out = convert(&convert(&img, to), back);
+                for (a, b) in out.data.as_slice().iter().zip(img.data.as_slice()) {
+                    assert!(a.abs_diff(*b) <= tolerance, "code {}: {} vs {}", to, a, b);
+                }
+            }
+            let out = convert(&convert(&float, to), back);
+            assert_eq!(out.typ(), CV_32FC3);
+            for (a, b) in out.row::<f32>(0).iter().zip(float.row::<f32>(0)) {
+                assert!((a - b).abs() < 1e-3, "code {}: {} vs {}", to, a, b);
+            }
+        }
+        let mut dst = Mat::empty();
+        let deep = Mat::zeros(1, 1, crate::core::mat::CV_16UC3);
+        assert!(cvt_color(&deep, &mut dst, COLOR_BGR2HSV).is_err());
+        assert!(cvt_color(&img, &mut dst, COLOR_BGRA2BGR).is_err());
+        assert!(cvt_color(&img, &mut dst, 1000).is_err());
+    }
+
+    #[test]
+    fn decodes_yuv_layouts() {
+        // 4x2 图像，左右两个 2x2 块分别共用 (U, V) = (90, 240) 与 (200, 60)；
+        // 期望值为 OpenCV 的 BGR 输出
+        let y = [16u8, 81, 145, 235, 50, 110, 170, 200];
+        let expected: [u8; 24] = [
+            0, 0, 179, 0, 0, 254, 255, 177, 42, 255, 255, 146, //
+            0, 0, 218, 33, 33, 255, 255, 206, 71, 255, 241, 106,
+        ];
+
+        let planar = |chroma: [u8; 4]| {
+            let mut data = y.to_vec();
+            data.extend_from_slice(&chroma);
+            Mat::from_slice(3, 4, CV_8UC1, &data).unwrap()
+        };
+        for (src, code) in [
+            (planar([90, 240, 200, 60]), COLOR_YUV2BGR_NV12),
+            (planar([240, 90, 60, 200]), COLOR_YUV2BGR_NV21),
+            (planar([90, 200, 240, 60]), COLOR_YUV2BGR_I420),
+            (planar([240, 60, 90, 200]), COLOR_YUV2BGR_YV12),
+        ] {
+            assert_eq!(
+                convert(&src, code).data.as_slice(),
+                expected,
+                "code {}",
+                code
+            );
+        }
+        let rgba = convert(&planar([90, 240, 200, 60]), COLOR_YUV2RGBA_NV12);
+        assert_eq!(rgba.at::<[u8; 4]>(1, 1), &[255, 33, 33, 255]);
+        let gray = convert(&planar([90, 240, 200, 60]), COLOR_YUV2GRAY_420);
+        assert_eq!(gray.data.as_slice(), y);
+
+        let packed = |m: [u8; 16]| Mat::from_slice(2, 4, CV_8UC2, &m).unwrap();
+        let yuyv = packed([
+            16, 90, 81, 240, 145, 200, 235, 60, //
+            50, 90, 110, 240, 170, 200, 200, 60,
+        ]);
+        let uyvy = packed([
+            90, 16, 240, 81, 200, 145, 60, 235, //
+            90, 50, 240, 110, 200, 170, 60, 200,
+        ]);
+        let yvyu = packed([
+            16, 240, 81, 90, 145, 60, 235, 200, //
+            50, 240, 110, 90, 170, 60, 200, 200,
+        ]);
+        assert_eq!(convert(&yuyv, COLOR_YUV2BGR_YUYV).data.as_slice(), expected);
+        assert_eq!(convert(&uyvy, COLOR_YUV2BGR_UYVY).data.as_slice(), expected);
+        assert_eq!(convert(&yvyu, COLOR_YUV2BGR_YVYU).data.as_slice(), expected);
+        assert_eq!(convert(&uyvy, COLOR_YUV2GRAY_UYVY).data.as_slice(), y);
+        assert_eq!(convert(&yuyv, COLOR_YUV2GRAY_YUYV).data.as_slice(), y);
+
+        let mut dst = Mat::empty();
+        assert!(cvt_color(&Mat::zeros(4, 2, CV_8UC1), &mut dst, COLOR_YUV2BGR_NV12).is_err());
+    }
+}
@@ -1,70 +1,17 @@
-use crate::core::mat::Mat;
+use crate::core::mat::{saturate, CV_8U};
+use crate::core::types::{Point, Rect, Scalar};
+use crate::core::view::{AsMatViewMut, MatViewMut};
 use rusttype::{point, Font, PositionedGlyph, Scale};
 use std::sync::OnceLock;
 
-// --- 基础结构 ---
-
-#[derive(Clone, Copy, Debug)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
-}
-
-impl Point {
-    pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub struct Rect {
-    pub x: i32,
-    pub y: i32,
-    pub width: i32,
-    pub height: i32,
-}
-
-impl Rect {
-    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
-        Self {
-            x,
-            y,
-            width,
-            height,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub struct Scalar {
-    pub v0: u8, // Blue
-    pub v1: u8, // Green
-    pub v2: u8, // Red
-}
-
-impl Scalar {
-    pub fn new(b: u8, g: u8, r: u8) -> Self {
-        Self {
-            v0: b,
-            v1: g,
-            v2: r,
-        }
-    }
-    pub fn all(v: u8) -> Self {
-        Self {
-            v0: v,
-            v1: v,
-            v2: v,
-        }
-    }
-}
-
 // --- 绘图函数 ---
 
-/// 在 Mat 上绘制矩形 (In-place)
+/// 在 Mat (或 ROI 视图) 上绘制矩形 (In-place)
 ///
-/// 这是一个手动实现的高性能版本，直接操作 Vec<u8>，避免了任何类型转换。
-pub fn rectangle(mat: &mut Mat, rect: Rect, color: Scalar, thickness: i32) {
+/// 这是一个手动实现的高性能版本，直接按行写入字节，避免了任何类型转换。
+/// 支持 1/3/4 通道的 8 位图像，传入视图时坐标相对于视图左上角。
+pub fn rectangle<M: AsMatViewMut + ?Sized>(mat: &mut M, rect: Rect, color: Scalar, thickness: i32) {
+    let mut mat = mat.view_mut();
     let x_min = rect.x.max(0);
     let y_min = rect.y.max(0);
     let x_max = (rect.x + rect.width).min(mat.cols);
@@ -73,36 +20,43 @@ pub fn rectangle(mat: &mut Mat, rect: Rect, color: Scalar, thickness: i32) {
     if x_min >= x_max || y_min >= y_max {
         return;
     }
+    let t = thickness.max(0);
+
+    // 上下边
+    fill(&mut mat, x_min..x_max, y_min..(y_min + t).min(y_max), color);
+    fill(&mut mat, x_min..x_max, (y_max - t).max(y_min)..y_max, color);
+    // 左右边
+    fill(&mut mat, x_min..(x_min + t).min(x_max), y_min..y_max, color);
+    fill(&mut mat, (x_max - t).max(x_min)..x_max, y_min..y_max, color);
+}
 
-    // 辅助闭包：设置像素
-    // 注意：Rust 借用检查器可能不喜欢我们在循环里多次借用 mat.data，所以我们用 raw slice 或 index
-    // 为了代码清晰，这里用 safe index，release 模式下会被优化
-    let set_pixel = |data: &mut Vec<u8>, step: usize, r: i32, c: i32, color: Scalar| {
-        let idx = (r as usize) * step + (c as usize) * 3;
-        if idx + 2 < data.len() {
-            data[idx] = color.v0;
-            data[idx + 1] = color.v1;
-            data[idx + 2] = color.v2;
-        }
-    };
-
-    let step = mat.step;
-
-    // 绘制上下边
-    for c in x_min..x_max {
-        for t in 0..thickness {
-            set_pixel(&mut mat.data, step, y_min + t, c, color); // Top
-            set_pixel(&mut mat.data, step, y_max - 1 - t, c, color); // Bottom
+/// 用颜色填充一块 (已裁剪到图像内的) 区域
+fn fill(
+    mat: &mut MatViewMut<'_>,
+    cols: std::ops::Range<i32>,
+    rows: std::ops::Range<i32>,
+    color: Scalar,
+) {
+    let cn = pixel_channels(mat);
+    let color = color_bytes(color);
+    for r in rows {
+        let row = mat.row_bytes_mut(r);
+        for c in cols.clone() {
+            let px = &mut row[c as usize * cn..];
+            px[..cn.min(3)].copy_from_slice(&color[..cn.min(3)]);
         }
     }
+}
 
-    // 绘制左右边
-    for r in y_min..y_max {
-        for t in 0..thickness {
-            set_pixel(&mut mat.data, step, r, x_min + t, color); // Left
-            set_pixel(&mut mat.data, step, r, x_max - 1 - t, color); // Right
-        }
-    }
+/// 颜色的前 3 个通道饱和到 8 位 (第 4 个通道不绘制)
+fn color_bytes(color: Scalar) -> [u8; 3] {
+    [color.v0, color.v1, color.v2].map(|v| saturate(v, u8::MAX as f64) as u8)
+}
+
+/// 绘图只支持 8 位图像，返回通道数
+fn pixel_channels(mat: &MatViewMut<'_>) -> usize {
+    assert_eq!(mat.depth, CV_8U, "Drawing supports only 8-bit images");
+    mat.channels as usize
 }
 
 // --- 文本渲染 ---
@@ -119,17 +73,24 @@ fn get_font() -> &'static Font<'static> {
     FONT.get_or_init(|| Font::try_from_bytes(FONT_DATA).expect("Error constructing Font"))
 }
 
-/// 在图像上绘制文字
-pub fn put_text(mat: &mut Mat, text: &str, org: Point, font_scale: f32, color: Scalar) {
+/// 在图像 (或 ROI 视图) 上绘制文字
+pub fn put_text<M: AsMatViewMut + ?Sized>(
+    mat: &mut M,
+    text: &str,
+    org: Point,
+    font_scale: f32,
+    color: Scalar,
+) {
+    let mut mat = mat.view_mut();
     let font = get_font();
     let scale = Scale::uniform(font_scale * 20.0); // 调整倍率以匹配 OpenCV 手感
     let start = point(org.x as f32, org.y as f32);
     let glyphs: Vec<PositionedGlyph> = font.layout(text, scale, start).collect();
 
-    let step = mat.step;
     let rows = mat.rows;
     let cols = mat.cols;
-    let channels = 3;
+    let channels = pixel_channels(&mat);
+    let color = color_bytes(color);
 
     for glyph in glyphs {
         if let Some(bounding_box) = glyph.pixel_bounding_box() {
@@ -140,24 +101,49 @@ pub fn put_text(mat: &mut Mat, text: &str, org: Point, font_scale: f32, color: S
                 let py = y as i32 + bounding_box.min.y;
 
                 if px >= 0 && px < cols && py >= 0 && py < rows {
-                    let idx = (py as usize) * step + (px as usize) * channels;
+                    let idx = (px as usize) * channels;
+                    let pixel = &mut mat.row_bytes_mut(py)[idx..idx + channels.min(3)];
 
                     // 简单的 Alpha Blending
-                    // Current Pixel
-                    let b_old = mat.data[idx] as f32;
-                    let g_old = mat.data[idx + 1] as f32;
-                    let r_old = mat.data[idx + 2] as f32;
-
                     let alpha = v;
-                    let b_new = (color.v0 as f32 * alpha) + (b_old * (1.0 - alpha));
-                    let g_new = (color.v1 as f32 * alpha) + (g_old * (1.0 - alpha));
-                    let r_new = (color.v2 as f32 * alpha) + (r_old * (1.0 - alpha));
-
-                    mat.data[idx] = b_new as u8;
-                    mat.data[idx + 1] = g_new as u8;
-                    mat.data[idx + 2] = r_new as u8;
+                    for (old, &new) in pixel.iter_mut().zip(&color) {
+                        *old = (new as f32 * alpha + *old as f32 * (1.0 - alpha)) as u8;
+                    }
                 }
             });
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{Mat, CV_8UC1, CV_8UC3};
+
+    #[test]
+    fn rectangle_draws_inside_roi_only() {
+        let mut mat = Mat::zeros(10, 10, CV_8UC3);
+        let mut roi = mat.roi_mut(Rect::new(4, 4, 4, 4)).unwrap();
+        // 超出视图的部分被裁剪掉，不会画到父图像上
+        rectangle(&mut roi, Rect::new(-2, -2, 5, 5), Scalar::new(1, 2, 3), 1);
+        assert_eq!(*roi.at::<[u8; 3]>(2, 0), [1, 2, 3]);
+        assert_eq!(*roi.at::<[u8; 3]>(0, 2), [1, 2, 3]);
+        assert_eq!(*roi.at::<[u8; 3]>(1, 1), [0, 0, 0]);
+        put_text(&mut roi, "A", Point::new(0, 3), 0.5, Scalar::all(255));
+
+        for r in 0..10 {
+            for c in 0..10 {
+                let inside = (4..8).contains(&r) && (4..8).contains(&c);
+                if !inside {
+                    assert_eq!(*mat.at::<[u8; 3]>(r, c), [0, 0, 0], "({}, {})", r, c);
+                }
+            }
+        }
+
+        // 单通道使用第一个颜色分量
+        let mut gray = Mat::zeros(4, 4, CV_8UC1);
+        rectangle(&mut gray, Rect::new(0, 0, 4, 4), Scalar::all(200), 1);
+        assert_eq!(gray.row_bytes(0), [200; 4]);
+        assert_eq!(gray.row_bytes(1), [200, 0, 0, 200]);
+    }
+}
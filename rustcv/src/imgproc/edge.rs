@@ -0,0 +1,426 @@
+//! 图像导数与边缘检测 (对应 OpenCV 的 `Sobel` / `Scharr` / `Laplacian` / `Canny`)
+//!
+//! 导数可能为负，通常输出为 `CV_16S` 或 `CV_32F`；`ddepth` 为 -1 时与输入深度相同
+//! (8 位输入会截断负值)。
+
+use super::filter::{
+    check_border, check_input, filter_f32, output_depth, sep_filter_f32, BORDER_REPLICATE,
+};
+use crate::core::mat::{Mat, CV_8U, CV_8UC1};
+use crate::core::types::{Point, Size};
+use crate::core::view::AsMatView;
+use anyhow::{anyhow, Result};
+
+/// 作为 `ksize` 传给 `sobel` 时使用 3x3 Scharr 核
+pub const FILTER_SCHARR: i32 = -1;
+
+/// 计算 Sobel / Scharr 导数的可分离核 `(kx, ky)` (对应 OpenCV 的 `getDerivKernels`)
+///
+/// `ksize` 为 1、3、5、7 或 `FILTER_SCHARR`；`normalize` 为 true 时缩放核使平滑部分的系数和为 1。
+pub fn get_deriv_kernels(
+    dx: i32,
+    dy: i32,
+    ksize: i32,
+    normalize: bool,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    if dx < 0 || dy < 0 {
+        return Err(anyhow!("get_deriv_kernels: negative derivative order"));
+    }
+    if ksize == FILTER_SCHARR {
+        if dx + dy != 1 {
+            return Err(anyhow!(
+                "get_deriv_kernels: Scharr supports only first-order derivatives (dx + dy == 1)"
+            ));
+        }
+        return Ok((scharr_kernel(dx, normalize), scharr_kernel(dy, normalize)));
+    }
+    if !matches!(ksize, 1 | 3 | 5 | 7) {
+        return Err(anyhow!(
+            "get_deriv_kernels: kernel size must be 1, 3, 5, 7 or FILTER_SCHARR, got {}",
+            ksize
+        ));
+    }
+    Ok((
+        sobel_kernel(dx, ksize, normalize)?,
+        sobel_kernel(dy, ksize, normalize)?,
+    ))
+}
+
+fn scharr_kernel(order: i32, normalize: bool) -> Vec<f64> {
+    if order == 0 {
+        let scale = if normalize { 1.0 / 32.0 } else { 1.0 };
+        vec![3.0 * scale, 10.0 * scale, 3.0 * scale]
+    } else {
+        vec![-1.0, 0.0, 1.0]
+    }
+}
+
+/// `(1 + x)^(ksize - 1 - order) * (x - 1)^order` 的系数
+fn sobel_kernel(order: i32, ksize: i32, normalize: bool) -> Result<Vec<f64>> {
+    // 求导时 1x1 核按 3x3 处理，与 OpenCV 一致
+    let ksize = if ksize == 1 && order > 0 { 3 } else { ksize };
+    if order >= ksize {
+        return Err(anyhow!(
+            "get_deriv_kernels: derivative order {} needs a kernel larger than {}",
+            order,
+            ksize
+        ));
+    }
+    let mut kernel = vec![1.0];
+    for i in 0..ksize - 1 {
+        let factor = if i < ksize - 1 - order { 1.0 } else { -1.0 };
+        let mut next = vec![0.0; kernel.len() + 1];
+        for (j, &k) in kernel.iter().enumerate() {
+            next[j] += factor * k;
+            next[j + 1] += k;
+        }
+        kernel = next;
+    }
+    if normalize {
+        let scale = 1.0 / (1 << (ksize - order - 1)) as f64;
+        kernel.iter_mut().for_each(|k| *k *= scale);
+    }
+    Ok(kernel)
+}
+
+/// Sobel 导数：`dx`、`dy` 为 x、y 方向的导数阶数，结果乘以 `scale` 再加 `delta`
+#[allow(clippy::too_many_arguments)] // 与 OpenCV 的 Sobel 参数一致
+pub fn sobel<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    dx: i32,
+    dy: i32,
+    ksize: i32,
+    scale: f64,
+    delta: f64,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("sobel", &src)?;
+    let ddepth = output_depth("sobel", src.depth, ddepth)?;
+    check_border("sobel", border_type)?;
+    let (kx, ky) = get_deriv_kernels(dx, dy, ksize, false)?;
+    let anchor = Point::new(kx.len() as i32 / 2, ky.len() as i32 / 2);
+    let acc = sep_filter_f32(&src, &kx, &ky, anchor, border_type)?;
+    acc.convert_to(dst, ddepth, scale, delta)
+}
+
+/// 3x3 Scharr 一阶导数，比 3x3 Sobel 更精确；`dx + dy` 必须为 1
+#[allow(clippy::too_many_arguments)] // 与 OpenCV 的 Scharr 参数一致
+pub fn scharr<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    dx: i32,
+    dy: i32,
+    scale: f64,
+    delta: f64,
+    border_type: i32,
+) -> Result<()> {
+    sobel(
+        src,
+        dst,
+        ddepth,
+        dx,
+        dy,
+        FILTER_SCHARR,
+        scale,
+        delta,
+        border_type,
+    )
+}
+
+/// 拉普拉斯算子 `d2/dx2 + d2/dy2`
+///
+/// `ksize` 为 1 时使用 `[0 1 0; 1 -4 1; 0 1 0]`，为 3 时使用 `[2 0 2; 0 -8 0; 2 0 2]`，
+/// 更大的奇数则为两个二阶 Sobel 导数之和。
+pub fn laplacian<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    ksize: i32,
+    scale: f64,
+    delta: f64,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("laplacian", &src)?;
+    let ddepth = output_depth("laplacian", src.depth, ddepth)?;
+    check_border("laplacian", border_type)?;
+    let acc = match ksize {
+        1 | 3 => {
+            let kernel: [f64; 9] = if ksize == 1 {
+                [0.0, 1.0, 0.0, 1.0, -4.0, 1.0, 0.0, 1.0, 0.0]
+            } else {
+                [2.0, 0.0, 2.0, 0.0, -8.0, 0.0, 2.0, 0.0, 2.0]
+            };
+            filter_f32(
+                &src,
+                &kernel,
+                Size::new(3, 3),
+                Point::new(1, 1),
+                border_type,
+            )?
+        }
+        5 | 7 => {
+            let center = Point::new(ksize / 2, ksize / 2);
+            let (kx, ky) = get_deriv_kernels(2, 0, ksize, false)?;
+            let mut acc = sep_filter_f32(&src, &kx, &ky, center, border_type)?;
+            let dyy = sep_filter_f32(&src, &ky, &kx, center, border_type)?;
+            for r in 0..acc.rows {
+                for (a, &b) in acc.row_mut::<f32>(r).iter_mut().zip(dyy.row::<f32>(r)) {
+                    *a += b;
+                }
+            }
+            acc
+        }
+        _ => {
+            return Err(anyhow!(
+                "laplacian: kernel size must be 1, 3, 5 or 7, got {}",
+                ksize
+            ))
+        }
+    };
+    acc.convert_to(dst, ddepth, scale, delta)
+}
+
+/// Canny 边缘检测，输出 `CV_8UC1`，边缘为 255
+///
+/// 梯度由 `aperture_size` (3 / 5 / 7) 的 Sobel 算子计算；`l2_gradient` 为 true 时梯度幅值
+/// 取 `sqrt(dx² + dy²)`，否则取 `|dx| + |dy|`。幅值高于较大阈值的像素是强边缘，
+/// 介于两阈值之间的像素只有与强边缘相连时才保留。多通道输入取各通道中最大的梯度。
+/// 与 OpenCV 相同，`aperture_size` 为 7 时梯度按 1/16 缩放并饱和到 16 位，两个阈值也除以 16。
+pub fn canny<A: AsMatView + ?Sized>(
+    image: &A,
+    edges: &mut Mat,
+    threshold1: f64,
+    threshold2: f64,
+    aperture_size: i32,
+    l2_gradient: bool,
+) -> Result<()> {
+    let src = image.view();
+    check_input("canny", &src)?;
+    if src.depth != CV_8U {
+        return Err(anyhow!("canny: expected an 8-bit image"));
+    }
+    if !matches!(aperture_size, 3 | 5 | 7) {
+        return Err(anyhow!(
+            "canny: aperture size must be 3, 5 or 7, got {}",
+            aperture_size
+        ));
+    }
+    let (mut low, mut high) = (threshold1.min(threshold2), threshold1.max(threshold2));
+    // 7x7 Sobel 的输出会超出 16 位，OpenCV 先缩小 16 倍
+    let scale = if aperture_size == 7 { 1.0 / 16.0 } else { 1.0 };
+    low *= scale;
+    high *= scale;
+    if l2_gradient {
+        low = low.min(32767.0);
+        high = high.min(32767.0);
+        if low > 0.0 {
+            low *= low;
+        }
+        if high > 0.0 {
+            high *= high;
+        }
+    }
+    let (low, high) = (low.floor() as i64, high.floor() as i64);
+
+    let (kx, ky) = get_deriv_kernels(1, 0, aperture_size, false)?;
+    let anchor = Point::new(aperture_size / 2, aperture_size / 2);
+    let gx = sep_filter_f32(&src, &kx, &ky, anchor, BORDER_REPLICATE)?;
+    let gy = sep_filter_f32(&src, &ky, &kx, anchor, BORDER_REPLICATE)?;
+
+    // 幅值与标记图都在四周各留 1 像素：幅值为 0，标记为"非边缘"
+    let (rows, cols, cn) = (src.rows as usize, src.cols as usize, src.channels as usize);
+    let stride = cols + 2;
+    let mut grad = vec![(0i64, 0i64); rows * cols];
+    let mut mag = vec![0i64; (rows + 2) * stride];
+    // OpenCV 的梯度图是 CV_16S
+    let to_i16 = |v: f32| {
+        (v as f64 * scale)
+            .round_ties_even()
+            .clamp(-32768.0, 32767.0) as i64
+    };
+    for r in 0..rows {
+        let (dx, dy) = (gx.row::<f32>(r as i32), gy.row::<f32>(r as i32));
+        for c in 0..cols {
+            let mut best = (0, 0, -1);
+            for k in c * cn..(c + 1) * cn {
+                let (x, y) = (to_i16(dx[k]), to_i16(dy[k]));
+                let m = if l2_gradient {
+                    x * x + y * y
+                } else {
+                    x.abs() + y.abs()
+                };
+                if m > best.2 {
+                    best = (x, y, m);
+                }
+            }
+            grad[r * cols + c] = (best.0, best.1);
+            mag[(r + 1) * stride + c + 1] = best.2;
+        }
+    }
+
+    // 非极大值抑制：沿梯度方向 (量化为水平、垂直、两条对角线) 比较相邻幅值
+    const NOT_EDGE: u8 = 1;
+    const CANDIDATE: u8 = 0;
+    const EDGE: u8 = 2;
+    const TG22: i64 = 13573; // tan(22.5°) * 2^15
+    let mut map = vec![NOT_EDGE; mag.len()];
+    let mut stack = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let i = (r + 1) * stride + c + 1;
+            let m = mag[i];
+            if m <= low {
+                continue;
+            }
+            let (xs, ys) = grad[r * cols + c];
+            let (x, y) = (xs.abs(), ys.abs() << 15);
+            let tg22x = x * TG22;
+            let is_max = if y < tg22x {
+                m > mag[i - 1] && m >= mag[i + 1]
+            } else if y > tg22x + (x << 16) {
+                m > mag[i - stride] && m >= mag[i + stride]
+            } else {
+                let s: isize = if (xs ^ ys) < 0 { -1 } else { 1 };
+                let (up, down) = (i - stride, i + stride);
+                m > mag[up.wrapping_add_signed(-s)] && m > mag[down.wrapping_add_signed(s)]
+            };
+            if is_max {
+                if m > high {
+                    map[i] = EDGE;
+                    stack.push(i);
+                } else {
+                    map[i] = CANDIDATE;
+                }
+            }
+        }
+    }
+
+    // 滞后阈值：从强边缘出发，沿 8 邻域连通的候选像素延伸
+    let neighbors = [
+        -(stride as isize) - 1,
+        -(stride as isize),
+        -(stride as isize) + 1,
+        -1,
+        1,
+        stride as isize - 1,
+        stride as isize,
+        stride as isize + 1,
+    ];
+    while let Some(i) = stack.pop() {
+        for &offset in &neighbors {
+            let n = i.wrapping_add_signed(offset);
+            if map[n] == CANDIDATE {
+                map[n] = EDGE;
+                stack.push(n);
+            }
+        }
+    }
+
+    edges.create(src.rows, src.cols, CV_8UC1);
+    for r in 0..rows {
+        let row = &map[(r + 1) * stride + 1..][..cols];
+        for (e, &m) in edges.row_bytes_mut(r as i32).iter_mut().zip(row) {
+            *e = if m == EDGE { 255 } else { 0 };
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16S, CV_32F};
+    use crate::imgproc::filter::BORDER_DEFAULT;
+
+    #[test]
+    fn derivative_kernels_and_operators() {
+        let k = |dx, dy, ksize| get_deriv_kernels(dx, dy, ksize, false).unwrap();
+        assert_eq!(k(1, 0, 3), (vec![-1.0, 0.0, 1.0], vec![1.0, 2.0, 1.0]));
+        assert_eq!(k(1, 0, 1), (vec![-1.0, 0.0, 1.0], vec![1.0]));
+        assert_eq!(k(2, 0, 5).0, [1.0, 0.0, -2.0, 0.0, 1.0]);
+        assert_eq!(k(0, 1, 5).1, [-1.0, -2.0, 0.0, 2.0, 1.0]);
+        assert_eq!(k(0, 1, FILTER_SCHARR).0, [3.0, 10.0, 3.0]);
+        assert!(get_deriv_kernels(1, 1, FILTER_SCHARR, false).is_err());
+        assert!(get_deriv_kernels(3, 0, 3, false).is_err());
+
+        // 水平斜坡 10 * x：导数为常数，REFLECT_101 边界处为 0
+        let ramp: Vec<u8> = (0..20).map(|i| (i % 5 * 10) as u8).collect();
+        let ramp = Mat::from_slice(4, 5, CV_8UC1, &ramp).unwrap();
+        let mut d = Mat::empty();
+        sobel(&ramp, &mut d, CV_16S, 1, 0, 3, 1.0, 0.0, BORDER_DEFAULT).unwrap();
+        assert_eq!(d.row::<i16>(2), [0, 80, 80, 80, 0]);
+        sobel(&ramp, &mut d, CV_16S, 0, 1, 3, 1.0, 0.0, BORDER_DEFAULT).unwrap();
+        assert!(d.row::<i16>(2).iter().all(|&v| v == 0));
+        scharr(&ramp, &mut d, CV_32F, 1, 0, 0.5, 1.0, BORDER_DEFAULT).unwrap();
+        assert_eq!(d.row::<f32>(1)[1..4], [161.0, 161.0, 161.0]);
+
+        let mut impulse = Mat::zeros(5, 5, CV_8UC1);
+        *impulse.at_mut::<u8>(2, 2) = 10;
+        laplacian(&impulse, &mut d, CV_16S, 1, 1.0, 0.0, BORDER_DEFAULT).unwrap();
+        assert_eq!(d.row::<i16>(2), [0, 10, -40, 10, 0]);
+        assert_eq!(d.row::<i16>(1), [0, 0, 10, 0, 0]);
+        laplacian(&impulse, &mut d, CV_16S, 5, 1.0, 0.0, BORDER_DEFAULT).unwrap();
+        // 5x5 二阶 Sobel 核中心为 -2 * 6，两个方向相加再乘以脉冲值 10
+        assert_eq!(*d.at::<i16>(2, 2), -240);
+    }
+
+    #[test]
+    fn canny_hysteresis() {
+        // 列 0-4 为 0；列 5-9 上半为 200 (强边缘)，下半为 40 (弱边缘)
+        let mut img = Mat::zeros(12, 10, CV_8UC1);
+        for r in 0..12 {
+            let v = if r < 6 { 200 } else { 40 };
+            img.row_bytes_mut(r)[5..].fill(v);
+        }
+        let column = |edges: &Mat, rows: std::ops::Range<i32>| {
+            rows.map(|r| *edges.at::<u8>(r, 4)).collect::<Vec<_>>()
+        };
+
+        let mut edges = Mat::empty();
+        canny(&img, &mut edges, 100.0, 500.0, 3, false).unwrap();
+        // 下半部分的弱边缘经由两块交界处的水平边缘与强边缘相连
+        assert_eq!(column(&edges, 0..5), [255; 5]);
+        assert_eq!(column(&edges, 7..12), [255; 5]);
+        assert_eq!(edges.row::<u8>(5)[5..], [255; 5]);
+        // 边缘只有 1 像素宽
+        assert!((0..12).all(|r| *edges.at::<u8>(r, 3) == 0 && *edges.at::<u8>(r, 2) == 0));
+
+        // 低阈值高于弱边缘的幅值 (160)：下半部分消失；阈值顺序无关
+        canny(&img, &mut edges, 500.0, 200.0, 3, true).unwrap();
+        assert_eq!(column(&edges, 0..4), [255; 4]);
+        assert_eq!(column(&edges, 8..12), [0; 4]);
+
+        canny(&img, &mut edges, 2000.0, 2100.0, 3, false).unwrap();
+        assert!(edges.data.iter().all(|&v| v == 0));
+        assert!(canny(&img, &mut edges, 1.0, 2.0, 4, false).is_err());
+
+        // 7x7：亮点左侧像素的 dx 为 5 * 20 = 100，缩小 16 倍后取整为 6，阈值也除以 16 后取整
+        let mut dot = Mat::zeros(15, 15, CV_8UC1);
+        dot.row_bytes_mut(7)[7] = 1;
+        canny(&dot, &mut edges, 95.0, 95.0, 7, false).unwrap();
+        assert_eq!(*edges.at::<u8>(7, 6), 255);
+        canny(&dot, &mut edges, 97.0, 97.0, 7, false).unwrap();
+        assert_eq!(*edges.at::<u8>(7, 6), 0);
+
+        let empty = Mat::zeros(0, 4, CV_8UC1);
+        assert!(canny(&empty, &mut edges, 100.0, 200.0, 3, false).is_err());
+        assert!(sobel(
+            &empty,
+            &mut edges,
+            CV_16S,
+            1,
+            0,
+            3,
+            1.0,
+            0.0,
+            BORDER_DEFAULT
+        )
+        .is_err());
+        assert!(laplacian(&empty, &mut edges, CV_16S, 3, 1.0, 0.0, BORDER_DEFAULT).is_err());
+    }
+}
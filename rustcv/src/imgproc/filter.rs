@@ -0,0 +1,694 @@
+//! 图像滤波 (对应 OpenCV 的 `filter2D` / `GaussianBlur` / `medianBlur` 等)
+//!
+//! 线性滤波先转为 `f32` 累加，再按输出深度饱和取整，8 位结果与 OpenCV 的定点实现相差不超过 1。
+//! 所有函数支持任意深度与通道数，各通道独立滤波 (双边滤波的颜色差按所有通道计算)。
+//! 图像外的像素按 `BORDER_*` 边界模式取值，默认 `BORDER_DEFAULT` (即 `BORDER_REFLECT_101`)。
+
+use crate::core::mat::{dispatch_depth, Mat, Sample, CV_16S, CV_16U, CV_32F, CV_8U};
+use crate::core::types::{Point, Size};
+use crate::core::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+
+/// 外部取 0：`000000|abcdefgh|0000000`
+pub const BORDER_CONSTANT: i32 = 0;
+/// 重复边缘像素：`aaaaaa|abcdefgh|hhhhhhh`
+pub const BORDER_REPLICATE: i32 = 1;
+/// 镜像 (含边缘像素)：`fedcba|abcdefgh|hgfedcb`
+pub const BORDER_REFLECT: i32 = 2;
+/// 周期延拓：`cdefgh|abcdefgh|abcdefg`
+pub const BORDER_WRAP: i32 = 3;
+/// 镜像 (不含边缘像素)：`gfedcb|abcdefgh|gfedcba`
+pub const BORDER_REFLECT_101: i32 = 4;
+pub const BORDER_REFLECT101: i32 = BORDER_REFLECT_101;
+pub const BORDER_DEFAULT: i32 = BORDER_REFLECT_101;
+/// 越界的输出像素保持 `dst` 原值，只用于 `remap` / `warp_affine` / `warp_perspective`
+pub const BORDER_TRANSPARENT: i32 = 5;
+
+/// 把坐标 `p` 按边界模式映射回 `[0, len)` (对应 OpenCV 的 `borderInterpolate`)
+///
+/// `BORDER_CONSTANT` 下越界坐标返回 -1；`len` 不大于 0 时没有可映射的坐标，也返回 -1。
+pub fn border_interpolate(p: i32, len: i32, border_type: i32) -> i32 {
+    if (0..len).contains(&p) {
+        return p;
+    }
+    if len <= 0 {
+        return -1;
+    }
+    match border_type {
+        BORDER_REPLICATE => p.clamp(0, len - 1),
+        BORDER_REFLECT | BORDER_REFLECT_101 => {
+            if len == 1 {
+                return 0;
+            }
+            let delta = (border_type == BORDER_REFLECT_101) as i32;
+            let mut p = p;
+            while !(0..len).contains(&p) {
+                p = if p < 0 {
+                    -p - 1 + delta
+                } else {
+                    len - 1 - (p - len) - delta
+                };
+            }
+            p
+        }
+        BORDER_WRAP => p.rem_euclid(len),
+        _ => -1,
+    }
+}
+
+/// 用任意单通道核与图像做相关运算 (不翻转核)：
+/// `dst(x, y) = sum(kernel(i, j) * src(x + i - anchor.x, y + j - anchor.y)) + delta`
+///
+/// `ddepth` 为 -1 时输出深度与输入相同；`anchor` 为 `(-1, -1)` 时取核中心。
+pub fn filter2d<A, K>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    kernel: &K,
+    anchor: Point,
+    delta: f64,
+    border_type: i32,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    K: AsMatView + ?Sized,
+{
+    let src = src.view();
+    check_input("filter2d", &src)?;
+    let kernel = kernel.view();
+    let ddepth = output_depth("filter2d", src.depth, ddepth)?;
+    check_border("filter2d", border_type)?;
+    if kernel.channels != 1 || kernel.is_empty() {
+        return Err(anyhow!(
+            "filter2d: kernel must be a non-empty single-channel matrix"
+        ));
+    }
+    let ksize = Size::new(kernel.cols, kernel.rows);
+    let anchor = resolve_anchor("filter2d", anchor, ksize)?;
+    let weights = dispatch_depth!(kernel.depth, values(&kernel));
+
+    let acc = filter_f32(&src, &weights, ksize, anchor, border_type)?;
+    acc.convert_to(dst, ddepth, 1.0, delta)
+}
+
+/// 可分离滤波：先按行与 `kernel_x` 相关，再按列与 `kernel_y` 相关
+#[allow(clippy::too_many_arguments)] // 与 OpenCV 的 sepFilter2D 参数一致
+pub fn sep_filter2d<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    kernel_x: &[f64],
+    kernel_y: &[f64],
+    anchor: Point,
+    delta: f64,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("sep_filter2d", &src)?;
+    let ddepth = output_depth("sep_filter2d", src.depth, ddepth)?;
+    check_border("sep_filter2d", border_type)?;
+    if kernel_x.is_empty() || kernel_y.is_empty() {
+        return Err(anyhow!("sep_filter2d: empty kernel"));
+    }
+    let ksize = Size::new(kernel_x.len() as i32, kernel_y.len() as i32);
+    let anchor = resolve_anchor("sep_filter2d", anchor, ksize)?;
+    let acc = sep_filter_f32(&src, kernel_x, kernel_y, anchor, border_type)?;
+    acc.convert_to(dst, ddepth, 1.0, delta)
+}
+
+/// 方框滤波：`ksize` 邻域求和，`normalize` 为 true 时再除以面积
+pub fn box_filter<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ddepth: i32,
+    ksize: Size,
+    anchor: Point,
+    normalize: bool,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("box_filter", &src)?;
+    let ddepth = output_depth("box_filter", src.depth, ddepth)?;
+    check_border("box_filter", border_type)?;
+    if ksize.width <= 0 || ksize.height <= 0 {
+        return Err(anyhow!(
+            "box_filter: invalid kernel size {}x{}",
+            ksize.width,
+            ksize.height
+        ));
+    }
+    let anchor = resolve_anchor("box_filter", anchor, ksize)?;
+    let kx = vec![1.0; ksize.width as usize];
+    let ky = vec![1.0; ksize.height as usize];
+    let acc = sep_filter_f32(&src, &kx, &ky, anchor, border_type)?;
+    let scale = if normalize {
+        1.0 / (ksize.width * ksize.height) as f64
+    } else {
+        1.0
+    };
+    acc.convert_to(dst, ddepth, scale, 0.0)
+}
+
+/// 均值滤波，等价于 `box_filter(src, dst, -1, ksize, anchor, true, border_type)`
+pub fn blur<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ksize: Size,
+    anchor: Point,
+    border_type: i32,
+) -> Result<()> {
+    box_filter(src, dst, -1, ksize, anchor, true, border_type)
+}
+
+/// 长度为 `ksize` (奇数) 的一维高斯核，系数和为 1
+///
+/// `sigma <= 0` 时按 `0.3 * ((ksize - 1) * 0.5 - 1) + 0.8` 计算；此时长度不超过 7 的核
+/// 与 OpenCV 一样使用固定的二项式系数。
+pub fn get_gaussian_kernel(ksize: i32, sigma: f64) -> Vec<f64> {
+    const SMALL: [&[f64]; 4] = [
+        &[1.0],
+        &[0.25, 0.5, 0.25],
+        &[0.0625, 0.25, 0.375, 0.25, 0.0625],
+        &[
+            0.03125, 0.109375, 0.21875, 0.28125, 0.21875, 0.109375, 0.03125,
+        ],
+    ];
+    if sigma <= 0.0 && ksize % 2 == 1 && ksize <= 7 {
+        return SMALL[ksize as usize / 2].to_vec();
+    }
+    let sigma = if sigma > 0.0 {
+        sigma
+    } else {
+        ((ksize - 1) as f64 * 0.5 - 1.0) * 0.3 + 0.8
+    };
+    let scale = -0.5 / (sigma * sigma);
+    let kernel: Vec<f64> = (0..ksize)
+        .map(|i| {
+            let x = (i - (ksize - 1) / 2) as f64;
+            (scale * x * x).exp()
+        })
+        .collect();
+    let sum: f64 = kernel.iter().sum();
+    kernel.into_iter().map(|v| v / sum).collect()
+}
+
+/// 高斯模糊
+///
+/// `ksize` 的宽高须为正奇数，或为 0 (由 sigma 推算)；`sigma_y` 为 0 时与 `sigma_x` 相同。
+pub fn gaussian_blur<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    ksize: Size,
+    sigma_x: f64,
+    sigma_y: f64,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("gaussian_blur", &src)?;
+    check_border("gaussian_blur", border_type)?;
+    let sigma_y = if sigma_y <= 0.0 { sigma_x } else { sigma_y };
+    // 与 OpenCV 相同：8 位图像取 ±3 sigma，其余深度取 ±4 sigma
+    let auto = |sigma: f64| {
+        let n = if src.depth == CV_8U { 3.0 } else { 4.0 };
+        (sigma * n * 2.0 + 1.0).round() as i32 | 1
+    };
+    let kw = if ksize.width <= 0 && sigma_x > 0.0 {
+        auto(sigma_x)
+    } else {
+        ksize.width
+    };
+    let kh = if ksize.height <= 0 && sigma_y > 0.0 {
+        auto(sigma_y)
+    } else {
+        ksize.height
+    };
+    if kw <= 0 || kh <= 0 || kw % 2 == 0 || kh % 2 == 0 {
+        return Err(anyhow!(
+            "gaussian_blur: kernel size must be positive and odd, got {}x{}",
+            kw,
+            kh
+        ));
+    }
+    let kx = get_gaussian_kernel(kw, sigma_x);
+    let ky = get_gaussian_kernel(kh, sigma_y);
+    let acc = sep_filter_f32(&src, &kx, &ky, Point::new(kw / 2, kh / 2), border_type)?;
+    acc.convert_to(dst, src.depth, 1.0, 0.0)
+}
+
+/// 中值滤波，`ksize` 为大于 1 的奇数，边界按 `BORDER_REPLICATE` 处理
+pub fn median_blur<A: AsMatView + ?Sized>(src: &A, dst: &mut Mat, ksize: i32) -> Result<()> {
+    let src = src.view();
+    check_input("median_blur", &src)?;
+    if ksize <= 1 || ksize % 2 == 0 {
+        return Err(anyhow!(
+            "median_blur: kernel size must be an odd number greater than 1, got {}",
+            ksize
+        ));
+    }
+    dispatch_depth!(src.depth, median(&src, dst, ksize / 2));
+    Ok(())
+}
+
+/// 双边滤波：按空间距离和颜色差加权平均，平滑的同时保留边缘
+///
+/// `d` 为邻域直径，不大于 0 时由 `sigma_space` 推算；颜色差为各通道差的绝对值之和。
+pub fn bilateral_filter<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    d: i32,
+    sigma_color: f64,
+    sigma_space: f64,
+    border_type: i32,
+) -> Result<()> {
+    let src = src.view();
+    check_input("bilateral_filter", &src)?;
+    check_border("bilateral_filter", border_type)?;
+    let sigma_color = if sigma_color <= 0.0 { 1.0 } else { sigma_color };
+    let sigma_space = if sigma_space <= 0.0 { 1.0 } else { sigma_space };
+    let radius = if d <= 0 {
+        (sigma_space * 1.5).round() as i32
+    } else {
+        d / 2
+    }
+    .max(1);
+    let color_coeff = -0.5 / (sigma_color * sigma_color);
+    let space_coeff = -0.5 / (sigma_space * sigma_space);
+    dispatch_depth!(
+        src.depth,
+        bilateral(&src, dst, radius, color_coeff, space_coeff, border_type)
+    );
+    Ok(())
+}
+
+pub(crate) fn output_depth(name: &str, src_depth: i32, ddepth: i32) -> Result<i32> {
+    match ddepth {
+        d if d < 0 => Ok(src_depth),
+        CV_8U | CV_16U | CV_16S | CV_32F => Ok(ddepth),
+        _ => Err(anyhow!("{}: unsupported output depth {}", name, ddepth)),
+    }
+}
+
+pub(crate) fn check_input(name: &str, src: &MatView<'_>) -> Result<()> {
+    if src.is_empty() {
+        return Err(anyhow!("{}: empty input", name));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_border(name: &str, border_type: i32) -> Result<()> {
+    if !(BORDER_CONSTANT..=BORDER_REFLECT_101).contains(&border_type) {
+        return Err(anyhow!("{}: unknown border type {}", name, border_type));
+    }
+    Ok(())
+}
+
+/// `(-1, -1)` 表示核中心
+pub(crate) fn resolve_anchor(name: &str, anchor: Point, ksize: Size) -> Result<Point> {
+    let anchor = Point::new(
+        if anchor.x < 0 {
+            ksize.width / 2
+        } else {
+            anchor.x
+        },
+        if anchor.y < 0 {
+            ksize.height / 2
+        } else {
+            anchor.y
+        },
+    );
+    if anchor.x >= ksize.width || anchor.y >= ksize.height {
+        return Err(anyhow!(
+            "{}: anchor ({}, {}) is outside the {}x{} kernel",
+            name,
+            anchor.x,
+            anchor.y,
+            ksize.width,
+            ksize.height
+        ));
+    }
+    Ok(anchor)
+}
+
+/// 第 i 项为坐标 `i - before` 按边界模式映射后的输入坐标，`BORDER_CONSTANT` 越界处为 `None`
+pub(crate) fn border_map(
+    len: i32,
+    before: i32,
+    after: i32,
+    border_type: i32,
+) -> Vec<Option<usize>> {
+    (-before..len + after)
+        .map(|p| usize::try_from(border_interpolate(p, len, border_type)).ok())
+        .collect()
+}
+
+pub(crate) fn values<T: Sample>(m: &MatView<'_>) -> Vec<f64> {
+    (0..m.rows)
+        .flat_map(|r| m.row::<T>(r).iter().map(|v| v.to_f64()))
+        .collect()
+}
+
+fn to_f32(src: &MatView<'_>) -> Result<Mat> {
+    let mut m = Mat::empty();
+    src.convert_to(&mut m, CV_32F, 1.0, 0.0)?;
+    Ok(m)
+}
+
+/// 二维相关运算，`weights` 为按行排列的 `ksize` 核，结果为同尺寸的 `CV_32F` 图像
+pub(crate) fn filter_f32(
+    src: &MatView<'_>,
+    weights: &[f64],
+    ksize: Size,
+    anchor: Point,
+    border_type: i32,
+) -> Result<Mat> {
+    let src = to_f32(src)?;
+    let cn = src.channels as usize;
+    let xmap = border_map(src.cols, anchor.x, ksize.width - anchor.x - 1, border_type);
+    let ymap = border_map(src.rows, anchor.y, ksize.height - anchor.y - 1, border_type);
+    let mut acc = Mat::with_depth(src.rows, src.cols, src.channels, CV_32F);
+    for y in 0..src.rows as usize {
+        let d = acc.row_mut::<f32>(y as i32);
+        for (ky, row) in weights.chunks_exact(ksize.width as usize).enumerate() {
+            let Some(sy) = ymap[y + ky] else { continue };
+            let s = src.row::<f32>(sy as i32);
+            for (kx, &w) in row.iter().enumerate().filter(|&(_, &w)| w != 0.0) {
+                for (px, sx) in d.chunks_exact_mut(cn).zip(&xmap[kx..]) {
+                    if let Some(sx) = *sx {
+                        for (v, &sv) in px.iter_mut().zip(&s[sx * cn..]) {
+                            *v += (w * sv as f64) as f32;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(acc)
+}
+
+/// 可分离相关运算，结果为同尺寸的 `CV_32F` 图像
+pub(crate) fn sep_filter_f32(
+    src: &MatView<'_>,
+    kx: &[f64],
+    ky: &[f64],
+    anchor: Point,
+    border_type: i32,
+) -> Result<Mat> {
+    let src = to_f32(src)?;
+    let cn = src.channels as usize;
+    let xmap = border_map(
+        src.cols,
+        anchor.x,
+        kx.len() as i32 - anchor.x - 1,
+        border_type,
+    );
+    let ymap = border_map(
+        src.rows,
+        anchor.y,
+        ky.len() as i32 - anchor.y - 1,
+        border_type,
+    );
+
+    let mut rows = Mat::with_depth(src.rows, src.cols, src.channels, CV_32F);
+    for r in 0..src.rows {
+        let s = src.row::<f32>(r);
+        let t = rows.row_mut::<f32>(r);
+        for (x, px) in t.chunks_exact_mut(cn).enumerate() {
+            for (c, v) in px.iter_mut().enumerate() {
+                *v = kx
+                    .iter()
+                    .zip(&xmap[x..])
+                    .filter_map(|(&w, sx)| sx.map(|sx| w * s[sx * cn + c] as f64))
+                    .sum::<f64>() as f32;
+            }
+        }
+    }
+
+    let mut out = Mat::with_depth(src.rows, src.cols, src.channels, CV_32F);
+    for y in 0..src.rows as usize {
+        let d = out.row_mut::<f32>(y as i32);
+        for (&w, sy) in ky.iter().zip(&ymap[y..]) {
+            if let Some(sy) = *sy {
+                for (v, &t) in d.iter_mut().zip(rows.row::<f32>(sy as i32)) {
+                    *v += (w * t as f64) as f32;
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn median<T: Sample>(src: &MatView<'_>, dst: &mut Mat, radius: i32) {
+    let cn = src.channels as usize;
+    let k = 2 * radius as usize + 1;
+    let xmap = border_map(src.cols, radius, radius, BORDER_REPLICATE);
+    let ymap = border_map(src.rows, radius, radius, BORDER_REPLICATE);
+    dst.create(src.rows, src.cols, src.typ());
+
+    let mut window: Vec<T> = Vec::with_capacity(k * k);
+    for y in 0..src.rows as usize {
+        let rows: Vec<&[T]> = ymap[y..y + k]
+            .iter()
+            .map(|sy| src.row::<T>(sy.unwrap_or(0) as i32))
+            .collect();
+        let d = dst.row_mut::<T>(y as i32);
+        for (x, px) in d.chunks_exact_mut(cn).enumerate() {
+            for (c, v) in px.iter_mut().enumerate() {
+                window.clear();
+                for s in &rows {
+                    window.extend(xmap[x..x + k].iter().map(|sx| s[sx.unwrap_or(0) * cn + c]));
+                }
+                let mid = window.len() / 2;
+                let (_, m, _) = window.select_nth_unstable_by(mid, |a, b| {
+                    a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                *v = *m;
+            }
+        }
+    }
+}
+
+fn bilateral<T: Sample>(
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    radius: i32,
+    color_coeff: f64,
+    space_coeff: f64,
+    border_type: i32,
+) {
+    let cn = src.channels as usize;
+    // 圆形邻域内各偏移的空间权重
+    let offsets: Vec<(usize, usize, f64)> = (-radius..=radius)
+        .flat_map(|i| (-radius..=radius).map(move |j| (i, j)))
+        .filter(|(i, j)| i * i + j * j <= radius * radius)
+        .map(|(i, j)| {
+            let w = ((i * i + j * j) as f64 * space_coeff).exp();
+            ((i + radius) as usize, (j + radius) as usize, w)
+        })
+        .collect();
+    let xmap = border_map(src.cols, radius, radius, border_type);
+    let ymap = border_map(src.rows, radius, radius, border_type);
+    dst.create(src.rows, src.cols, src.typ());
+
+    let zeros = vec![T::from_f64(0.0); cn];
+    let mut sum = vec![0.0; cn];
+    for y in 0..src.rows as usize {
+        let center_row = src.row::<T>(y as i32);
+        let d = dst.row_mut::<T>(y as i32);
+        for (x, out) in d.chunks_exact_mut(cn).enumerate() {
+            let center = &center_row[x * cn..(x + 1) * cn];
+            sum.fill(0.0);
+            let mut wsum = 0.0;
+            for &(dy, dx, ws) in &offsets {
+                let px = match (ymap[y + dy], xmap[x + dx]) {
+                    (Some(sy), Some(sx)) => &src.row::<T>(sy as i32)[sx * cn..(sx + 1) * cn],
+                    _ => &zeros[..],
+                };
+                let diff: f64 = px
+                    .iter()
+                    .zip(center)
+                    .map(|(a, b)| (a.to_f64() - b.to_f64()).abs())
+                    .sum();
+                let w = ws * (diff * diff * color_coeff).exp();
+                for (s, v) in sum.iter_mut().zip(px) {
+                    *s += w * v.to_f64();
+                }
+                wsum += w;
+            }
+            for (o, s) in out.iter_mut().zip(&sum) {
+                *o = T::from_f64(s / wsum);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_32FC1, CV_8UC1, CV_8UC3};
+
+    fn impulse(value: u8) -> Mat {
+        let mut m = Mat::zeros(5, 5, CV_8UC1);
+        *m.at_mut::<u8>(2, 2) = value;
+        m
+    }
+
+    #[test]
+    fn border_modes() {
+        let cases = [
+            (BORDER_CONSTANT, [-1, -1]),
+            (BORDER_REPLICATE, [0, 4]),
+            (BORDER_REFLECT, [0, 3]),
+            (BORDER_WRAP, [4, 1]),
+            (BORDER_REFLECT_101, [1, 2]),
+        ];
+        for (border, expected) in cases {
+            assert_eq!(
+                [-1, 6].map(|p| border_interpolate(p, 5, border)),
+                expected,
+                "border {}",
+                border
+            );
+        }
+        assert_eq!(border_interpolate(-3, 1, BORDER_REFLECT_101), 0);
+        for border in [
+            BORDER_REPLICATE,
+            BORDER_REFLECT,
+            BORDER_WRAP,
+            BORDER_REFLECT_101,
+        ] {
+            assert_eq!(border_interpolate(1, 0, border), -1);
+        }
+
+        // 空图像直接报错，不会在边界映射中死循环或越界
+        let empty = Mat::zeros(0, 4, CV_8UC1);
+        let mut dst = Mat::empty();
+        assert!(
+            gaussian_blur(&empty, &mut dst, Size::new(3, 3), 0.0, 0.0, BORDER_DEFAULT).is_err()
+        );
+        assert!(median_blur(&empty, &mut dst, 3).is_err());
+        assert!(blur(
+            &empty,
+            &mut dst,
+            Size::new(3, 3),
+            Point::new(-1, -1),
+            BORDER_REFLECT
+        )
+        .is_err());
+        assert!(bilateral_filter(&empty, &mut dst, 5, 10.0, 10.0, BORDER_DEFAULT).is_err());
+    }
+
+    #[test]
+    fn linear_filters() {
+        // 相关运算：冲激响应是翻转后的核
+        let kernel: Vec<f32> = (1..=9).map(|v| v as f32).collect();
+        let kernel = Mat::from_slice(3, 3, CV_32FC1, &kernel).unwrap();
+        let mut dst = Mat::empty();
+        filter2d(
+            &impulse(1),
+            &mut dst,
+            -1,
+            &kernel,
+            Point::new(-1, -1),
+            0.0,
+            BORDER_DEFAULT,
+        )
+        .unwrap();
+        assert_eq!(
+            [(1, 1), (1, 3), (3, 1), (3, 3), (0, 0)].map(|(r, c)| *dst.at::<u8>(r, c)),
+            [9, 7, 3, 1, 0]
+        );
+
+        assert_eq!(get_gaussian_kernel(3, 0.0), [0.25, 0.5, 0.25]);
+        let k = get_gaussian_kernel(9, 1.5);
+        assert!((k.iter().sum::<f64>() - 1.0).abs() < 1e-12 && k[0] == k[8] && k[4] > k[3]);
+
+        gaussian_blur(
+            &impulse(255),
+            &mut dst,
+            Size::new(3, 3),
+            0.0,
+            0.0,
+            BORDER_DEFAULT,
+        )
+        .unwrap();
+        assert_eq!([*dst.at::<u8>(2, 2), *dst.at::<u8>(1, 1)], [64, 16]);
+        let mut sep = Mat::empty();
+        let k = [0.25, 0.5, 0.25];
+        sep_filter2d(
+            &impulse(255),
+            &mut sep,
+            -1,
+            &k,
+            &k,
+            Point::new(-1, -1),
+            0.0,
+            BORDER_DEFAULT,
+        )
+        .unwrap();
+        assert_eq!(sep.data, dst.data);
+
+        // 不归一化的方框滤波：REFLECT_101 下全 1 图像每处都是 9，常数边界的角上只有 4
+        let ones = Mat::ones(4, 4, CV_8UC1);
+        let anchor = Point::new(-1, -1);
+        box_filter(
+            &ones,
+            &mut dst,
+            CV_32F,
+            Size::new(3, 3),
+            anchor,
+            false,
+            BORDER_DEFAULT,
+        )
+        .unwrap();
+        assert!(dst.row::<f32>(0).iter().all(|&v| v == 9.0));
+        box_filter(
+            &ones,
+            &mut dst,
+            CV_32F,
+            Size::new(3, 3),
+            anchor,
+            false,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        assert_eq!(dst.row::<f32>(0), [4.0, 6.0, 6.0, 4.0]);
+
+        let color = Mat::from_slice(3, 3, CV_8UC3, &[[10u8, 20, 30]; 9]).unwrap();
+        blur(&color, &mut dst, Size::new(3, 3), anchor, BORDER_REPLICATE).unwrap();
+        assert_eq!(dst.data, color.data);
+        assert!(blur(
+            &color,
+            &mut dst,
+            Size::new(3, 3),
+            Point::new(3, 0),
+            BORDER_DEFAULT
+        )
+        .is_err());
+        assert!(gaussian_blur(&color, &mut dst, Size::new(4, 3), 0.0, 0.0, 4).is_err());
+    }
+
+    #[test]
+    fn median_and_bilateral_preserve_edges() {
+        // 左半 0、右半 200 的阶跃边缘，叠加椒盐噪声
+        let mut img = Mat::zeros(6, 6, CV_8UC1);
+        for r in 0..6 {
+            for c in 3..6 {
+                *img.at_mut::<u8>(r, c) = 200;
+            }
+        }
+        let clean = img.deep_clone();
+        *img.at_mut::<u8>(2, 1) = 255;
+        *img.at_mut::<u8>(4, 4) = 0;
+
+        let mut dst = Mat::empty();
+        median_blur(&img, &mut dst, 3).unwrap();
+        assert_eq!(dst.data, clean.data);
+        assert!(median_blur(&img, &mut dst, 4).is_err());
+
+        bilateral_filter(&clean, &mut dst, 5, 10.0, 3.0, BORDER_DEFAULT).unwrap();
+        assert_eq!(dst.data, clean.data);
+        // 颜色差远小于 sigma_color 时退化为高斯平滑
+        bilateral_filter(&impulse(10), &mut dst, 3, 1000.0, 1000.0, BORDER_DEFAULT).unwrap();
+        assert_eq!([*dst.at::<u8>(2, 2), *dst.at::<u8>(1, 2)], [2, 2]);
+    }
+}
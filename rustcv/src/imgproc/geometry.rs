@@ -0,0 +1,982 @@
+//! 几何变换 (对应 OpenCV 的 `resize` / `warpAffine` / `warpPerspective` / `remap` / `flip` / `rotate`)
+//!
+//! `resize` 除最近邻外都拆成水平、垂直两个一维重采样，按 OpenCV 相同的采样位置与权重计算，
+//! 中间结果使用浮点，8 位图像与 OpenCV 的定点实现相差不超过 1。
+//! `warp_*` / `remap` 逐点插值，越界像素按 `BORDER_*` 取值。
+//! 输入可以是带 `step` 的 ROI 视图，支持所有深度与通道数。
+
+use super::filter::{border_interpolate, BORDER_CONSTANT, BORDER_TRANSPARENT};
+use crate::core::mat::{
+    dispatch_depth, type_name, Mat, Sample, CV_16SC2, CV_16UC1, CV_32FC1, CV_32FC2,
+};
+use crate::core::types::{Point2f, Scalar, Size};
+use crate::core::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+use std::f64::consts::PI;
+
+/// 最近邻
+pub const INTER_NEAREST: i32 = 0;
+/// 双线性
+pub const INTER_LINEAR: i32 = 1;
+/// 双三次 (4x4 邻域)
+pub const INTER_CUBIC: i32 = 2;
+/// 按像素面积加权平均，缩小图像时不产生摩尔纹；放大时与双线性相近
+pub const INTER_AREA: i32 = 3;
+/// Lanczos (8x8 邻域)
+pub const INTER_LANCZOS4: i32 = 4;
+/// 从 `flags` 中取出插值方式的掩码
+pub const INTER_MAX: i32 = 7;
+/// `warp_affine` / `warp_perspective` 的标志：矩阵已经是 `dst -> src` 的映射，不再求逆
+pub const WARP_INVERSE_MAP: i32 = 16;
+
+/// 顺时针旋转 90 度
+pub const ROTATE_90_CLOCKWISE: i32 = 0;
+/// 旋转 180 度
+pub const ROTATE_180: i32 = 1;
+/// 逆时针旋转 90 度
+pub const ROTATE_90_COUNTERCLOCKWISE: i32 = 2;
+
+/// `warp_*` / `remap` 的小数坐标精度为 1/32 像素
+const INTER_BITS: i32 = 5;
+const INTER_TAB_SIZE: i32 = 1 << INTER_BITS;
+
+/// 缩放图像
+///
+/// `dsize` 的宽高都大于 0 时按 `dsize` 输出，否则输出尺寸为 `(cols * fx, rows * fy)` 取整。
+///
+/// ```no_run
+/// # use rustcv::core::mat::Mat;
+/// # use rustcv::imgproc::{resize, Size, INTER_AREA};
+/// # let frame = Mat::empty();
+/// // 1080p 缩小为 640x360 预览
+/// let mut preview = Mat::empty();
+/// resize(&frame, &mut preview, Size::new(640, 360), 0.0, 0.0, INTER_AREA)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn resize<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    dsize: Size,
+    fx: f64,
+    fy: f64,
+    interpolation: i32,
+) -> Result<()> {
+    let src = src.view();
+    if src.is_empty() {
+        return Err(anyhow!("resize: empty input"));
+    }
+    let (dsize, inv_scale_x, inv_scale_y) = if dsize.width > 0 && dsize.height > 0 {
+        (
+            dsize,
+            dsize.width as f64 / src.cols as f64,
+            dsize.height as f64 / src.rows as f64,
+        )
+    } else if fx > 0.0 && fy > 0.0 {
+        let size = Size::new(
+            (src.cols as f64 * fx).round() as i32,
+            (src.rows as f64 * fy).round() as i32,
+        );
+        (size, fx, fy)
+    } else {
+        return Err(anyhow!(
+            "resize: either dsize or both fx and fy must be positive"
+        ));
+    };
+    if dsize.width <= 0 || dsize.height <= 0 {
+        return Err(anyhow!(
+            "resize: output size {}x{} is empty",
+            dsize.width,
+            dsize.height
+        ));
+    }
+    if !(INTER_NEAREST..=INTER_LANCZOS4).contains(&interpolation) {
+        return Err(anyhow!("resize: unknown interpolation {}", interpolation));
+    }
+
+    dst.create(dsize.height, dsize.width, src.typ());
+    if interpolation == INTER_NEAREST {
+        resize_nearest(&src, dst, inv_scale_x, inv_scale_y);
+        return Ok(());
+    }
+
+    // 两个方向都缩小时 INTER_AREA 按面积加权，否则退化为特殊的双线性
+    let area = interpolation == INTER_AREA && inv_scale_x <= 1.0 && inv_scale_y <= 1.0;
+    let xtab = taps(interpolation, area, src.cols, dsize.width, inv_scale_x);
+    let ytab = taps(interpolation, area, src.rows, dsize.height, inv_scale_y);
+    dispatch_depth!(src.depth, resample(&src, dst, &xtab, &ytab));
+    Ok(())
+}
+
+/// 仿射变换：`dst(x, y) = src(M11 x + M12 y + M13, M21 x + M22 y + M23)`，
+/// 其中 `M` 为 `m` 的逆矩阵；`flags` 含 `WARP_INVERSE_MAP` 时直接把 `m` 当作 `dst -> src` 的映射
+///
+/// `dsize` 的宽高不大于 0 时与输入相同。`flags` 为插值方式 (`INTER_AREA` 按双线性处理)
+/// 与 `WARP_INVERSE_MAP` 的按位或；越界像素按 `border_type` 取值，`BORDER_CONSTANT` 时取 `border_value`。
+///
+/// ```no_run
+/// # use rustcv::core::mat::Mat;
+/// # use rustcv::imgproc::*;
+/// # let frame = Mat::empty();
+/// // 绕中心逆时针旋转 15 度
+/// let center = Point2f::new(frame.cols as f32 / 2.0, frame.rows as f32 / 2.0);
+/// let m = get_rotation_matrix_2d(center, 15.0, 1.0);
+/// let mut rotated = Mat::empty();
+/// warp_affine(&frame, &mut rotated, &m, Size::new(0, 0), INTER_LINEAR, BORDER_CONSTANT, Scalar::all(0))?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn warp_affine<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    m: &[[f64; 3]; 2],
+    dsize: Size,
+    flags: i32,
+    border_type: i32,
+    border_value: Scalar,
+) -> Result<()> {
+    let src = src.view();
+    let interpolation = check_warp("warp_affine", &src, flags, border_type)?;
+    let m = if flags & WARP_INVERSE_MAP != 0 {
+        *m
+    } else {
+        invert_affine(m).ok_or_else(|| anyhow!("warp_affine: matrix is singular"))?
+    };
+    let dsize = output_size(&src, dsize);
+    dst.create(dsize.height, dsize.width, src.typ());
+    let coords = |x: i32, y: i32| {
+        let (x, y) = (x as f64, y as f64);
+        (
+            m[0][0] * x + m[0][1] * y + m[0][2],
+            m[1][0] * x + m[1][1] * y + m[1][2],
+        )
+    };
+    let value = border_value.to_array();
+    dispatch_depth!(
+        src.depth,
+        warp(&src, dst, interpolation, border_type, value, &coords)
+    );
+    Ok(())
+}
+
+/// 透视变换：`dst(x, y) = src((M11 x + M12 y + M13) / w, (M21 x + M22 y + M23) / w)`，
+/// `w = M31 x + M32 y + M33`，`M` 为 `m` 的逆矩阵；其余参数同 `warp_affine`
+///
+/// ```no_run
+/// # use rustcv::core::mat::Mat;
+/// # use rustcv::imgproc::*;
+/// # let photo = Mat::empty();
+/// // 把拍摄到的文档四角 (左上、右上、右下、左下) 拉正为 A4 比例的 840x1188 图像
+/// let corners = [
+///     Point2f::new(112.0, 80.0),
+///     Point2f::new(905.0, 131.0),
+///     Point2f::new(948.0, 1270.0),
+///     Point2f::new(61.0, 1215.0),
+/// ];
+/// let page = [
+///     Point2f::new(0.0, 0.0),
+///     Point2f::new(839.0, 0.0),
+///     Point2f::new(839.0, 1187.0),
+///     Point2f::new(0.0, 1187.0),
+/// ];
+/// let m = get_perspective_transform(&corners, &page)?;
+/// let mut scan = Mat::empty();
+/// warp_perspective(&photo, &mut scan, &m, Size::new(840, 1188), INTER_LINEAR, BORDER_REPLICATE, Scalar::all(0))?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn warp_perspective<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    m: &[[f64; 3]; 3],
+    dsize: Size,
+    flags: i32,
+    border_type: i32,
+    border_value: Scalar,
+) -> Result<()> {
+    let src = src.view();
+    let interpolation = check_warp("warp_perspective", &src, flags, border_type)?;
+    let m = if flags & WARP_INVERSE_MAP != 0 {
+        *m
+    } else {
+        invert3(m).ok_or_else(|| anyhow!("warp_perspective: matrix is singular"))?
+    };
+    let dsize = output_size(&src, dsize);
+    dst.create(dsize.height, dsize.width, src.typ());
+    let coords = |x: i32, y: i32| {
+        let (x, y) = (x as f64, y as f64);
+        let w = m[2][0] * x + m[2][1] * y + m[2][2];
+        // w 为 0 时映射到无穷远，按越界处理
+        (
+            (m[0][0] * x + m[0][1] * y + m[0][2]) / w,
+            (m[1][0] * x + m[1][1] * y + m[1][2]) / w,
+        )
+    };
+    let value = border_value.to_array();
+    dispatch_depth!(
+        src.depth,
+        warp(&src, dst, interpolation, border_type, value, &coords)
+    );
+    Ok(())
+}
+
+/// 按坐标映射表重采样：`dst(x, y) = src(map_x(x, y), map_y(x, y))`，输出尺寸与映射表相同
+///
+/// 支持的映射表格式：
+/// - `map1` 为 `CV_32FC2` (每个元素为 `(x, y)`)，`map2` 为 `None`；
+/// - `map1`、`map2` 都是 `CV_32FC1`，分别存放 x、y；
+/// - `map1` 为 `CV_16SC2` 整数坐标，`map2` 为 `None` 或 `CV_16UC1` 小数索引
+///   (`(fy << 5) | fx`，即 OpenCV `convertMaps` 生成的定点格式)。
+#[allow(clippy::too_many_arguments)] // 与 OpenCV 的 remap 参数一致
+pub fn remap<A, M>(
+    src: &A,
+    dst: &mut Mat,
+    map1: &M,
+    map2: Option<&dyn AsMatView>,
+    interpolation: i32,
+    border_type: i32,
+    border_value: Scalar,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    M: AsMatView + ?Sized,
+{
+    let src = src.view();
+    let interpolation = check_warp("remap", &src, interpolation, border_type)?;
+    let map1 = map1.view();
+    let map2 = map2.map(|m| m.view()).filter(|m| !m.is_empty());
+    if let Some(m2) = &map2 {
+        if (m2.rows, m2.cols) != (map1.rows, map1.cols) {
+            return Err(anyhow!(
+                "remap: map sizes differ ({}x{} vs {}x{})",
+                map1.cols,
+                map1.rows,
+                m2.cols,
+                m2.rows
+            ));
+        }
+    }
+    let (t1, t2) = (map1.typ(), map2.map(|m| m.typ()));
+    if !matches!(
+        (t1, t2),
+        (CV_32FC2, None) | (CV_32FC1, Some(CV_32FC1)) | (CV_16SC2, None | Some(CV_16UC1))
+    ) {
+        return Err(anyhow!(
+            "remap: unsupported map types {} / {}",
+            type_name(t1),
+            t2.map_or_else(|| "none".to_string(), type_name)
+        ));
+    }
+
+    let scale = 1.0 / INTER_TAB_SIZE as f64;
+    let coords = |x: i32, y: i32| {
+        let x = x as usize;
+        match (t1, &map2) {
+            (CV_32FC2, _) => {
+                let p = map1.row::<[f32; 2]>(y)[x];
+                (p[0] as f64, p[1] as f64)
+            }
+            (CV_32FC1, Some(map2)) => (map1.row::<f32>(y)[x] as f64, map2.row::<f32>(y)[x] as f64),
+            _ => {
+                let p = map1.row::<[i16; 2]>(y)[x];
+                let frac = map2.map_or(0, |m| m.row::<u16>(y)[x] as i32);
+                let (fx, fy) = (
+                    frac & (INTER_TAB_SIZE - 1),
+                    (frac >> INTER_BITS) & (INTER_TAB_SIZE - 1),
+                );
+                (
+                    p[0] as f64 + fx as f64 * scale,
+                    p[1] as f64 + fy as f64 * scale,
+                )
+            }
+        }
+    };
+    dst.create(map1.rows, map1.cols, src.typ());
+    let value = border_value.to_array();
+    dispatch_depth!(
+        src.depth,
+        warp(&src, dst, interpolation, border_type, value, &coords)
+    );
+    Ok(())
+}
+
+/// 绕 `center` 旋转 `angle` 度 (正值为逆时针，y 轴向下) 并缩放 `scale` 倍的仿射矩阵
+pub fn get_rotation_matrix_2d(center: Point2f, angle: f64, scale: f64) -> [[f64; 3]; 2] {
+    let angle = angle.to_radians();
+    let (alpha, beta) = (scale * angle.cos(), scale * angle.sin());
+    let (cx, cy) = (center.x as f64, center.y as f64);
+    [
+        [alpha, beta, (1.0 - alpha) * cx - beta * cy],
+        [-beta, alpha, beta * cx + (1.0 - alpha) * cy],
+    ]
+}
+
+/// 由三对对应点求仿射矩阵 (`src[i]` 映射到 `dst[i]`)，三点共线时返回错误
+pub fn get_affine_transform(src: &[Point2f; 3], dst: &[Point2f; 3]) -> Result<[[f64; 3]; 2]> {
+    let mut a = [[0.0; 6]; 6];
+    let mut b = [0.0; 6];
+    for (i, (s, d)) in src.iter().zip(dst).enumerate() {
+        let (x, y) = (s.x as f64, s.y as f64);
+        a[i] = [x, y, 1.0, 0.0, 0.0, 0.0];
+        a[i + 3] = [0.0, 0.0, 0.0, x, y, 1.0];
+        b[i] = d.x as f64;
+        b[i + 3] = d.y as f64;
+    }
+    let m = solve(a, b).ok_or_else(|| anyhow!("get_affine_transform: points are collinear"))?;
+    Ok([[m[0], m[1], m[2]], [m[3], m[4], m[5]]])
+}
+
+/// 由四对对应点求透视变换矩阵 (`src[i]` 映射到 `dst[i]`)，任意三点共线时返回错误
+pub fn get_perspective_transform(src: &[Point2f; 4], dst: &[Point2f; 4]) -> Result<[[f64; 3]; 3]> {
+    let mut a = [[0.0; 8]; 8];
+    let mut b = [0.0; 8];
+    for (i, (s, d)) in src.iter().zip(dst).enumerate() {
+        let (x, y, u, v) = (s.x as f64, s.y as f64, d.x as f64, d.y as f64);
+        a[i] = [x, y, 1.0, 0.0, 0.0, 0.0, -x * u, -y * u];
+        a[i + 4] = [0.0, 0.0, 0.0, x, y, 1.0, -x * v, -y * v];
+        b[i] = u;
+        b[i + 4] = v;
+    }
+    let m =
+        solve(a, b).ok_or_else(|| anyhow!("get_perspective_transform: points are degenerate"))?;
+    Ok([[m[0], m[1], m[2]], [m[3], m[4], m[5]], [m[6], m[7], 1.0]])
+}
+
+/// 翻转图像：`flip_code` 为 0 时上下翻转，大于 0 时左右翻转，小于 0 时两者都做 (即旋转 180 度)
+pub fn flip<A: AsMatView + ?Sized>(src: &A, dst: &mut Mat, flip_code: i32) -> Result<()> {
+    let src = src.view();
+    let px = src.elem_size();
+    dst.create(src.rows, src.cols, src.typ());
+    for y in 0..src.rows {
+        let sy = if flip_code <= 0 { src.rows - 1 - y } else { y };
+        let s = src.row_bytes(sy);
+        let d = dst.row_bytes_mut(y);
+        if flip_code == 0 {
+            d.copy_from_slice(s);
+        } else {
+            for (out, p) in d.chunks_exact_mut(px).zip(s.chunks_exact(px).rev()) {
+                out.copy_from_slice(p);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按 90 度的整数倍旋转图像 (只复制像素，不插值)，`rotate_code` 为 `ROTATE_*`
+pub fn rotate<A: AsMatView + ?Sized>(src: &A, dst: &mut Mat, rotate_code: i32) -> Result<()> {
+    let src = src.view();
+    let (rows, cols) = (src.rows, src.cols);
+    let px = src.elem_size();
+    // 输出像素 (x, y) 对应的输入坐标
+    let source: fn(i32, i32, i32, i32) -> (i32, i32) = match rotate_code {
+        ROTATE_90_CLOCKWISE => |x, y, rows, _| (y, rows - 1 - x),
+        ROTATE_180 => return flip(&src, dst, -1),
+        ROTATE_90_COUNTERCLOCKWISE => |x, y, _, cols| (cols - 1 - y, x),
+        _ => return Err(anyhow!("rotate: unknown rotate code {}", rotate_code)),
+    };
+    dst.create(cols, rows, src.typ());
+    for y in 0..cols {
+        let d = dst.row_bytes_mut(y);
+        for (x, out) in d.chunks_exact_mut(px).enumerate() {
+            let (sx, sy) = source(x as i32, y, rows, cols);
+            out.copy_from_slice(&src.row_bytes(sy)[sx as usize * px..][..px]);
+        }
+    }
+    Ok(())
+}
+
+fn resize_nearest(src: &MatView<'_>, dst: &mut Mat, inv_scale_x: f64, inv_scale_y: f64) {
+    let px = src.elem_size();
+    let nearest = |d: i32, inv_scale: f64, len: i32| {
+        ((d as f64 / inv_scale).floor() as i32).min(len - 1) as usize
+    };
+    let xofs: Vec<usize> = (0..dst.cols)
+        .map(|x| nearest(x, inv_scale_x, src.cols) * px)
+        .collect();
+    for y in 0..dst.rows {
+        let s = src.row_bytes(nearest(y, inv_scale_y, src.rows) as i32);
+        let d = dst.row_bytes_mut(y);
+        for (out, &ofs) in d.chunks_exact_mut(px).zip(&xofs) {
+            out.copy_from_slice(&s[ofs..ofs + px]);
+        }
+    }
+}
+
+/// 一维重采样表：每个输出坐标对应的 (输入坐标, 权重)
+type Taps = Vec<Vec<(usize, f64)>>;
+
+fn taps(interpolation: i32, area: bool, src_len: i32, dst_len: i32, inv_scale: f64) -> Taps {
+    let scale = 1.0 / inv_scale;
+    if area {
+        return area_taps(src_len, dst_len, scale);
+    }
+    let last = src_len as i64 - 1;
+    (0..dst_len)
+        .map(|d| {
+            let (s, t) = if interpolation == INTER_AREA {
+                // 放大时的 INTER_AREA：输出像素只覆盖一个输入像素时直接取值
+                let s = (d as f64 * scale).floor();
+                let t = (d + 1) as f64 - (s + 1.0) * inv_scale;
+                (s as i64, if t <= 0.0 { 0.0 } else { t - t.floor() })
+            } else {
+                let f = (d as f64 + 0.5) * scale - 0.5;
+                (f.floor() as i64, f - f.floor())
+            };
+            let (offset, coeffs): (i64, Vec<f64>) = match interpolation {
+                INTER_CUBIC => (-1, cubic_coeffs(t).to_vec()),
+                INTER_LANCZOS4 => (-3, lanczos4_coeffs(t).to_vec()),
+                _ => {
+                    // 双线性在边界处不外插，直接取边缘像素
+                    let (s2, t) = if s < 0 {
+                        (0, 0.0)
+                    } else if s >= last {
+                        (last, 0.0)
+                    } else {
+                        (s, t)
+                    };
+                    return vec![(s2 as usize, 1.0 - t), ((s2 + 1).min(last) as usize, t)];
+                }
+            };
+            coeffs
+                .into_iter()
+                .enumerate()
+                .map(|(k, w)| ((s + offset + k as i64).clamp(0, last) as usize, w))
+                .collect()
+        })
+        .collect()
+}
+
+/// 缩小时每个输出像素覆盖 `scale` 个输入像素，按覆盖面积加权
+fn area_taps(src_len: i32, dst_len: i32, scale: f64) -> Taps {
+    let src_len = src_len as f64;
+    (0..dst_len)
+        .map(|d| {
+            let fs1 = d as f64 * scale;
+            let fs2 = fs1 + scale;
+            let cell = scale.min(src_len - fs1);
+            let s2 = fs2.floor().min(src_len - 1.0);
+            let s1 = fs1.ceil().min(s2);
+
+            let mut tab = Vec::new();
+            if s1 - fs1 > 1e-3 {
+                tab.push((s1 as usize - 1, (s1 - fs1) / cell));
+            }
+            for s in s1 as usize..s2 as usize {
+                tab.push((s, 1.0 / cell));
+            }
+            if fs2 - s2 > 1e-3 {
+                tab.push((s2 as usize, (fs2 - s2).min(1.0).min(cell) / cell));
+            }
+            tab
+        })
+        .collect()
+}
+
+/// Keys 三次卷积核，A = -0.75 (与 OpenCV 相同)
+fn cubic_coeffs(x: f64) -> [f64; 4] {
+    const A: f64 = -0.75;
+    let c0 = ((A * (x + 1.0) - 5.0 * A) * (x + 1.0) + 8.0 * A) * (x + 1.0) - 4.0 * A;
+    let c1 = ((A + 2.0) * x - (A + 3.0)) * x * x + 1.0;
+    let c2 = ((A + 2.0) * (1.0 - x) - (A + 3.0)) * (1.0 - x) * (1.0 - x) + 1.0;
+    [c0, c1, c2, 1.0 - c0 - c1 - c2]
+}
+
+/// 8 个采样点 (偏移 -3..=4) 的 Lanczos 窗函数，归一化到和为 1
+fn lanczos4_coeffs(x: f64) -> [f64; 8] {
+    let mut coeffs = [0.0; 8];
+    if x < f32::EPSILON as f64 {
+        coeffs[3] = 1.0;
+        return coeffs;
+    }
+    for (i, c) in coeffs.iter_mut().enumerate() {
+        let t = x + 3.0 - i as f64;
+        *c = 4.0 * (PI * t).sin() * (PI * t / 4.0).sin() / (PI * PI * t * t);
+    }
+    let sum: f64 = coeffs.iter().sum();
+    coeffs.map(|c| c / sum)
+}
+
+/// 先把每个输入行水平重采样，再按行权重垂直合成
+fn resample<T: Sample>(src: &MatView<'_>, dst: &mut Mat, xtab: &Taps, ytab: &Taps) {
+    let cn = src.channels as usize;
+    let width = xtab.len() * cn;
+    let mut rows = vec![0f32; src.rows as usize * width];
+    for (r, out) in rows.chunks_exact_mut(width).enumerate() {
+        let s = src.row::<T>(r as i32);
+        for (px, tab) in out.chunks_exact_mut(cn).zip(xtab) {
+            for (c, v) in px.iter_mut().enumerate() {
+                *v = tab
+                    .iter()
+                    .map(|&(x, w)| s[x * cn + c].to_f64() * w)
+                    .sum::<f64>() as f32;
+            }
+        }
+    }
+
+    for (y, tab) in ytab.iter().enumerate() {
+        let d = dst.row_mut::<T>(y as i32);
+        for (i, v) in d.iter_mut().enumerate() {
+            let sum: f64 = tab
+                .iter()
+                .map(|&(r, w)| rows[r * width + i] as f64 * w)
+                .sum();
+            *v = T::from_f64(sum);
+        }
+    }
+}
+
+fn check_warp(name: &str, src: &MatView<'_>, flags: i32, border_type: i32) -> Result<i32> {
+    if src.is_empty() {
+        return Err(anyhow!("{}: empty input", name));
+    }
+    if !(BORDER_CONSTANT..=BORDER_TRANSPARENT).contains(&border_type) {
+        return Err(anyhow!("{}: unknown border type {}", name, border_type));
+    }
+    match flags & INTER_MAX {
+        INTER_AREA => Ok(INTER_LINEAR),
+        i @ (INTER_NEAREST | INTER_LINEAR | INTER_CUBIC | INTER_LANCZOS4) => Ok(i),
+        i => Err(anyhow!("{}: unknown interpolation {}", name, i)),
+    }
+}
+
+fn output_size(src: &MatView<'_>, dsize: Size) -> Size {
+    if dsize.width > 0 && dsize.height > 0 {
+        dsize
+    } else {
+        Size::new(src.cols, src.rows)
+    }
+}
+
+/// `[A | b]` 的逆变换 `[A⁻¹ | -A⁻¹ b]`
+fn invert_affine(m: &[[f64; 3]; 2]) -> Option<[[f64; 3]; 2]> {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    if det == 0.0 {
+        return None;
+    }
+    let (a, b, c, d) = (m[1][1] / det, -m[0][1] / det, -m[1][0] / det, m[0][0] / det);
+    Some([
+        [a, b, -a * m[0][2] - b * m[1][2]],
+        [c, d, -c * m[0][2] - d * m[1][2]],
+    ])
+}
+
+/// 3x3 矩阵求逆 (伴随矩阵除以行列式)
+fn invert3(m: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let cof =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let adj = [
+        [cof(1, 2, 1, 2), -cof(0, 2, 1, 2), cof(0, 1, 1, 2)],
+        [-cof(1, 2, 0, 2), cof(0, 2, 0, 2), -cof(0, 1, 0, 2)],
+        [cof(1, 2, 0, 1), -cof(0, 2, 0, 1), cof(0, 1, 0, 1)],
+    ];
+    let det = m[0][0] * adj[0][0] + m[0][1] * adj[1][0] + m[0][2] * adj[2][0];
+    if det == 0.0 {
+        return None;
+    }
+    Some(adj.map(|row| row.map(|v| v / det)))
+}
+
+/// 列主元高斯消元解 `a x = b`，矩阵奇异时返回 `None`
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for col in 0..N {
+        let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for r in col + 1..N {
+            let f = a[r][col] / a[col][col];
+            let (top, bottom) = a.split_at_mut(r);
+            for (v, &p) in bottom[0][col..].iter_mut().zip(&top[col][col..]) {
+                *v -= f * p;
+            }
+            b[r] -= f * b[col];
+        }
+    }
+    let mut x = [0.0; N];
+    for r in (0..N).rev() {
+        let sum: f64 = (r + 1..N).map(|c| a[r][c] * x[c]).sum();
+        x[r] = (b[r] - sum) / a[r][r];
+    }
+    Some(x)
+}
+
+/// 按 `coords` 给出的输入坐标逐点插值，`dst` 已按输出尺寸分配
+///
+/// 与 OpenCV 一样，小数坐标量化到 1/32 像素，坐标饱和到 16 位整数范围。
+fn warp<T: Sample>(
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    interpolation: i32,
+    border_type: i32,
+    border_value: [f64; 4],
+    coords: &dyn Fn(i32, i32) -> (f64, f64),
+) {
+    let cn = src.channels as usize;
+    let (offset, coeffs): (i64, fn(f64) -> Vec<f64>) = match interpolation {
+        INTER_NEAREST => (0, |_| vec![1.0]),
+        INTER_CUBIC => (-1, |t| cubic_coeffs(t).to_vec()),
+        INTER_LANCZOS4 => (-3, |t| lanczos4_coeffs(t).to_vec()),
+        _ => (0, |t| vec![1.0 - t, t]),
+    };
+    let tab: Vec<Vec<f64>> = (0..INTER_TAB_SIZE)
+        .map(|i| coeffs(i as f64 / INTER_TAB_SIZE as f64))
+        .collect();
+    let border: Vec<T> = border_value[..cn].iter().map(|&v| T::from_f64(v)).collect();
+    let pixel = |x: i64, y: i64| -> Option<&[T]> {
+        let inside = (0..src.cols as i64).contains(&x) && (0..src.rows as i64).contains(&y);
+        let (x, y) = if inside {
+            (x as i32, y as i32)
+        } else if border_type == BORDER_CONSTANT || border_type == BORDER_TRANSPARENT {
+            return None;
+        } else {
+            (
+                border_interpolate(x as i32, src.cols, border_type),
+                border_interpolate(y as i32, src.rows, border_type),
+            )
+        };
+        Some(&src.row::<T>(y)[x as usize * cn..][..cn])
+    };
+    // 非有限值 (如透视变换的无穷远点) 饱和后必然越界
+    let fixed = |v: f64| {
+        let v = if v.is_nan() { f64::INFINITY } else { v };
+        let limit = (i16::MAX as i64 + 1) * INTER_TAB_SIZE as i64;
+        ((v * INTER_TAB_SIZE as f64).round() as i64).clamp(-limit, limit)
+    };
+
+    let mut acc = vec![0f64; cn];
+    for y in 0..dst.rows {
+        let d = dst.row_mut::<T>(y);
+        for (x, out) in d.chunks_exact_mut(cn).enumerate() {
+            let (sx, sy) = coords(x as i32, y);
+            let (fx, fy) = if interpolation == INTER_NEAREST {
+                (fixed(sx.round()), fixed(sy.round()))
+            } else {
+                (fixed(sx), fixed(sy))
+            };
+            let (ix, iy) = (fx >> INTER_BITS, fy >> INTER_BITS);
+            let (wx, wy) = (
+                &tab[(fx & (INTER_TAB_SIZE as i64 - 1)) as usize],
+                &tab[(fy & (INTER_TAB_SIZE as i64 - 1)) as usize],
+            );
+
+            acc.fill(0.0);
+            let mut transparent = false;
+            for (i, &wy) in wy.iter().enumerate() {
+                for (j, &wx) in wx.iter().enumerate() {
+                    let w = wy * wx;
+                    if w == 0.0 {
+                        continue;
+                    }
+                    let p = pixel(ix + offset + j as i64, iy + offset + i as i64);
+                    match p {
+                        Some(p) => acc
+                            .iter_mut()
+                            .zip(p)
+                            .for_each(|(a, v)| *a += w * v.to_f64()),
+                        None if border_type == BORDER_TRANSPARENT => transparent = true,
+                        None => acc
+                            .iter_mut()
+                            .zip(&border)
+                            .for_each(|(a, v)| *a += w * v.to_f64()),
+                    }
+                }
+            }
+            if !transparent {
+                out.iter_mut()
+                    .zip(&acc)
+                    .for_each(|(o, &a)| *o = T::from_f64(a));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_8UC1, CV_8UC3};
+    use crate::core::types::Rect;
+    use crate::imgproc::filter::BORDER_REPLICATE;
+
+    fn resized(src: &Mat, w: i32, h: i32, interpolation: i32) -> Mat {
+        let mut dst = Mat::empty();
+        resize(src, &mut dst, Size::new(w, h), 0.0, 0.0, interpolation).unwrap();
+        dst
+    }
+
+    #[test]
+    fn matches_opencv_sampling() {
+        let src = Mat::from_slice(1, 4, CV_8UC1, &[0u8, 100, 200, 255]).unwrap();
+        // OpenCV: cv2.resize(src, (2, 1), interpolation=...)
+        assert_eq!(resized(&src, 2, 1, INTER_NEAREST).data, [0, 200]);
+        assert_eq!(resized(&src, 2, 1, INTER_LINEAR).data, [50, 228]);
+        assert_eq!(resized(&src, 2, 1, INTER_AREA).data, [50, 228]);
+        // 放大 2 倍：双线性的采样点为 -0.25, 0.25, 0.75, ...，INTER_AREA 为像素复制
+        assert_eq!(
+            resized(&src, 8, 1, INTER_LINEAR).data,
+            [0, 25, 75, 125, 175, 214, 241, 255]
+        );
+        assert_eq!(
+            resized(&src, 8, 1, INTER_AREA).data,
+            [0, 0, 100, 100, 200, 200, 255, 255]
+        );
+
+        // 非整数倍缩小：每个输出覆盖 1.5 个输入像素
+        let src = Mat::from_slice(1, 3, CV_32FC1, &[0f32, 3.0, 6.0]).unwrap();
+        let out = resized(&src, 2, 1, INTER_AREA);
+        assert!((out.row::<f32>(0)[0] - 1.0).abs() < 1e-5);
+        assert!((out.row::<f32>(0)[1] - 5.0).abs() < 1e-5);
+
+        // 常数图像在任何插值下保持不变
+        let flat = Mat::from_slice(3, 5, CV_8UC3, &[[7u8, 80, 200]; 15]).unwrap();
+        for interpolation in [INTER_LINEAR, INTER_CUBIC, INTER_AREA, INTER_LANCZOS4] {
+            for (w, h) in [(2, 2), (11, 7)] {
+                let out = resized(&flat, w, h, interpolation);
+                assert!(
+                    out.data.as_slice().chunks(3).all(|px| px == [7, 80, 200]),
+                    "interpolation {} to {}x{}",
+                    interpolation,
+                    w,
+                    h
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn resizes_views_by_factor() {
+        let data: Vec<u8> = (0..64).map(|i| i * 2).collect();
+        let img = Mat::from_slice(8, 8, CV_8UC1, &data).unwrap();
+        let roi = img.roi(Rect::new(2, 2, 4, 4)).unwrap();
+        let mut half = Mat::empty();
+        resize(&roi, &mut half, Size::new(0, 0), 0.5, 0.5, INTER_AREA).unwrap();
+        assert_eq!((half.rows, half.cols), (2, 2));
+        // 每个输出是 2x2 块的均值
+        assert_eq!(half.data, [45, 49, 77, 81]);
+
+        let mut dst = Mat::empty();
+        assert!(resize(&roi, &mut dst, Size::new(0, 0), 0.0, 0.0, INTER_LINEAR).is_err());
+        assert!(resize(&roi, &mut dst, Size::new(2, 2), 0.0, 0.0, 9).is_err());
+    }
+
+    #[test]
+    fn flip_rotate_and_affine_warps_agree() {
+        let img = Mat::from_slice(2, 3, CV_8UC1, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+        let mut out = Mat::empty();
+        for (code, want) in [
+            (0, [4, 5, 6, 1, 2, 3]),
+            (1, [3, 2, 1, 6, 5, 4]),
+            (-1, [6, 5, 4, 3, 2, 1]),
+        ] {
+            flip(&img, &mut out, code).unwrap();
+            assert_eq!(out.data, want, "flip {}", code);
+        }
+        rotate(&img, &mut out, ROTATE_90_CLOCKWISE).unwrap();
+        assert_eq!(
+            (out.rows, out.cols, out.data.as_slice()),
+            (3, 2, &[4, 1, 5, 2, 6, 3][..])
+        );
+        rotate(&img, &mut out, ROTATE_90_COUNTERCLOCKWISE).unwrap();
+        assert_eq!(out.data, [3, 6, 2, 5, 1, 4]);
+        rotate(&img, &mut out, ROTATE_180).unwrap();
+        assert_eq!(out.data, [6, 5, 4, 3, 2, 1]);
+        assert!(rotate(&img, &mut out, 3).is_err());
+
+        // 绕中心逆时针旋转 90 度的仿射变换与 rotate 结果一致
+        let data: Vec<u8> = (0..25).collect();
+        let img = Mat::from_slice(5, 5, CV_8UC1, &data).unwrap();
+        let mut expected = Mat::empty();
+        rotate(&img, &mut expected, ROTATE_90_COUNTERCLOCKWISE).unwrap();
+        let m = get_rotation_matrix_2d(Point2f::new(2.0, 2.0), 90.0, 1.0);
+        for interpolation in [INTER_NEAREST, INTER_LINEAR, INTER_CUBIC] {
+            warp_affine(
+                &img,
+                &mut out,
+                &m,
+                Size::new(0, 0),
+                interpolation,
+                BORDER_CONSTANT,
+                Scalar::all(0),
+            )
+            .unwrap();
+            assert_eq!(out.data, expected.data, "interpolation {}", interpolation);
+        }
+
+        let from = [
+            Point2f::new(0.0, 0.0),
+            Point2f::new(4.0, 0.0),
+            Point2f::new(0.0, 4.0),
+        ];
+        let to = [
+            Point2f::new(0.0, 4.0),
+            Point2f::new(0.0, 0.0),
+            Point2f::new(4.0, 4.0),
+        ];
+        let solved = get_affine_transform(&from, &to).unwrap();
+        for (row, want) in solved.iter().zip(&m) {
+            assert!(
+                row.iter().zip(want).all(|(a, b)| (a - b).abs() < 1e-9),
+                "{:?}",
+                solved
+            );
+        }
+        let collinear = [
+            Point2f::new(0.0, 0.0),
+            Point2f::new(1.0, 1.0),
+            Point2f::new(2.0, 2.0),
+        ];
+        assert!(get_affine_transform(&collinear, &to).is_err());
+
+        // WARP_INVERSE_MAP：矩阵直接是 dst -> src，右侧越界部分填 border_value
+        let shift = [[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+        let row = Mat::from_slice(1, 3, CV_8UC1, &[10u8, 20, 30]).unwrap();
+        let flags = INTER_LINEAR | WARP_INVERSE_MAP;
+        warp_affine(
+            &row,
+            &mut out,
+            &shift,
+            Size::new(0, 0),
+            flags,
+            BORDER_CONSTANT,
+            Scalar::all(9),
+        )
+        .unwrap();
+        assert_eq!(out.data, [20, 30, 9]);
+        warp_affine(
+            &row,
+            &mut out,
+            &shift,
+            Size::new(0, 0),
+            INTER_LINEAR,
+            BORDER_REPLICATE,
+            Scalar::all(9),
+        )
+        .unwrap();
+        assert_eq!(out.data, [10, 10, 20]);
+    }
+
+    #[test]
+    fn perspective_and_remap() {
+        let square = [
+            Point2f::new(0.0, 0.0),
+            Point2f::new(1.0, 0.0),
+            Point2f::new(1.0, 1.0),
+            Point2f::new(0.0, 1.0),
+        ];
+        let quad = [
+            Point2f::new(10.0, 20.0),
+            Point2f::new(90.0, 5.0),
+            Point2f::new(100.0, 80.0),
+            Point2f::new(0.0, 60.0),
+        ];
+        let m = get_perspective_transform(&square, &quad).unwrap();
+        for (s, q) in square.iter().zip(&quad) {
+            let (x, y) = (s.x as f64, s.y as f64);
+            let w = m[2][0] * x + m[2][1] * y + m[2][2];
+            let u = (m[0][0] * x + m[0][1] * y + m[0][2]) / w;
+            let v = (m[1][0] * x + m[1][1] * y + m[1][2]) / w;
+            assert!((u - q.x as f64).abs() < 1e-9 && (v - q.y as f64).abs() < 1e-9);
+        }
+        assert!(get_perspective_transform(&[square[0]; 4], &quad).is_err());
+
+        // 放大 2 倍的透视矩阵：dst(x) = src(x / 2)
+        let row = Mat::from_slice(1, 2, CV_8UC1, &[0u8, 100]).unwrap();
+        let scale = [[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 1.0]];
+        let mut out = Mat::empty();
+        warp_perspective(
+            &row,
+            &mut out,
+            &scale,
+            Size::new(4, 1),
+            INTER_LINEAR,
+            BORDER_REPLICATE,
+            Scalar::all(0),
+        )
+        .unwrap();
+        assert_eq!(out.data, [0, 50, 100, 100]);
+
+        // 三种映射表格式都表示 x + 0.5，最后一个点一半落在图像外
+        let src = Mat::from_slice(1, 4, CV_32FC1, &[0f32, 10.0, 20.0, 30.0]).unwrap();
+        let xy = Mat::from_slice(
+            1,
+            4,
+            CV_32FC2,
+            &[[0.5f32, 0.0], [1.5, 0.0], [2.5, 0.0], [3.5, 0.0]],
+        )
+        .unwrap();
+        let mx = Mat::from_slice(1, 4, CV_32FC1, &[0.5f32, 1.5, 2.5, 3.5]).unwrap();
+        let my = Mat::zeros(1, 4, CV_32FC1);
+        let fixed = Mat::from_slice(1, 4, CV_16SC2, &[[0i16, 0], [1, 0], [2, 0], [3, 0]]).unwrap();
+        let frac = Mat::from_slice(1, 4, CV_16UC1, &[16u16; 4]).unwrap();
+        let want = [5.0, 15.0, 25.0, 18.5];
+        let border = Scalar::all(7);
+        remap(
+            &src,
+            &mut out,
+            &xy,
+            None,
+            INTER_LINEAR,
+            BORDER_CONSTANT,
+            border,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(0), want);
+        remap(
+            &src,
+            &mut out,
+            &mx,
+            Some(&my),
+            INTER_LINEAR,
+            BORDER_CONSTANT,
+            border,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(0), want);
+        remap(
+            &src,
+            &mut out,
+            &fixed,
+            Some(&frac),
+            INTER_LINEAR,
+            BORDER_CONSTANT,
+            border,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(0), want);
+        remap(
+            &src,
+            &mut out,
+            &fixed,
+            None,
+            INTER_LINEAR,
+            BORDER_CONSTANT,
+            border,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(0), [0.0, 10.0, 20.0, 30.0]);
+
+        // BORDER_TRANSPARENT 保留 dst 中越界位置的原值
+        let mut kept = Mat::from_slice(1, 4, CV_32FC1, &[-1f32; 4]).unwrap();
+        remap(
+            &src,
+            &mut kept,
+            &xy,
+            None,
+            INTER_LINEAR,
+            BORDER_TRANSPARENT,
+            border,
+        )
+        .unwrap();
+        assert_eq!(kept.row::<f32>(0), [5.0, 15.0, 25.0, -1.0]);
+
+        assert!(remap(
+            &src,
+            &mut out,
+            &mx,
+            None,
+            INTER_LINEAR,
+            BORDER_CONSTANT,
+            border
+        )
+        .is_err());
+        assert!(remap(&src, &mut out, &xy, None, INTER_LINEAR, 6, border).is_err());
+    }
+}
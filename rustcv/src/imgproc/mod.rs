@@ -1,4 +1,17 @@
+pub mod color;
 pub mod drawing;
+pub mod edge;
+pub mod filter;
+pub mod geometry;
+pub mod morph;
+pub mod threshold;
 
 // Re-export drawing primitives
-pub use drawing::{put_text, rectangle, Point, Rect, Scalar};
+pub use crate::core::types::{Point, Point2f, Rect, Scalar, Size};
+pub use color::*;
+pub use drawing::{put_text, rectangle};
+pub use edge::*;
+pub use filter::*;
+pub use geometry::*;
+pub use morph::*;
+pub use threshold::*;
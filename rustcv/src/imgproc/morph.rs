@@ -0,0 +1,587 @@
+//! 形态学运算 (对应 OpenCV 的 `getStructuringElement` / `erode` / `dilate` / `morphologyEx`)
+//!
+//! 结构元素是单通道矩阵，非零元素构成邻域形状。支持所有深度与通道数，各通道独立处理，
+//! 二值图像和灰度图像都适用。`BORDER_CONSTANT` 下图像外的像素不参与运算
+//! (相当于 OpenCV 默认的 `morphologyDefaultBorderValue`)。
+//!
+//! ```no_run
+//! # use rustcv::core::mat::Mat;
+//! # use rustcv::imgproc::*;
+//! # let motion_mask = Mat::empty();
+//! // 先开运算去掉孤立噪点，再闭运算填上目标内部的小洞
+//! let kernel = get_structuring_element(MORPH_ELLIPSE, Size::new(5, 5), Point::new(-1, -1))?;
+//! let (mut opened, mut cleaned) = (Mat::empty(), Mat::empty());
+//! morphology_ex(&motion_mask, &mut opened, MORPH_OPEN, &kernel, Point::new(-1, -1), 1, BORDER_CONSTANT)?;
+//! morphology_ex(&opened, &mut cleaned, MORPH_CLOSE, &kernel, Point::new(-1, -1), 1, BORDER_CONSTANT)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::filter::{border_map, check_border, check_input, resolve_anchor, values};
+use crate::core::arithm::{bitwise_and, bitwise_not, subtract};
+use crate::core::mat::{dispatch_depth, type_name, Mat, Sample, CV_8UC1};
+use crate::core::types::{Point, Size};
+use crate::core::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+
+/// 矩形结构元素
+pub const MORPH_RECT: i32 = 0;
+/// 十字形结构元素 (过锚点的一行和一列)
+pub const MORPH_CROSS: i32 = 1;
+/// 内切椭圆结构元素
+pub const MORPH_ELLIPSE: i32 = 2;
+
+/// 腐蚀：邻域最小值
+pub const MORPH_ERODE: i32 = 0;
+/// 膨胀：邻域最大值
+pub const MORPH_DILATE: i32 = 1;
+/// 开运算：先腐蚀再膨胀，去掉比结构元素小的亮斑
+pub const MORPH_OPEN: i32 = 2;
+/// 闭运算：先膨胀再腐蚀，填上比结构元素小的暗洞
+pub const MORPH_CLOSE: i32 = 3;
+/// 形态学梯度：膨胀 - 腐蚀，得到目标轮廓
+pub const MORPH_GRADIENT: i32 = 4;
+/// 顶帽：原图 - 开运算
+pub const MORPH_TOPHAT: i32 = 5;
+/// 黑帽：闭运算 - 原图
+pub const MORPH_BLACKHAT: i32 = 6;
+/// 击中击不中：核中 1 处必须为前景 (非零)，-1 处必须为背景 (0)，0 处不关心；只支持 `CV_8UC1`
+pub const MORPH_HITMISS: i32 = 7;
+
+/// 生成 `ksize` 大小的结构元素 (`CV_8UC1`，元素为 0 或 1)
+///
+/// `anchor` 只影响 `MORPH_CROSS` 的十字位置，`(-1, -1)` 表示中心。
+pub fn get_structuring_element(shape: i32, ksize: Size, anchor: Point) -> Result<Mat> {
+    if !(MORPH_RECT..=MORPH_ELLIPSE).contains(&shape) {
+        return Err(anyhow!("get_structuring_element: unknown shape {}", shape));
+    }
+    if ksize.width <= 0 || ksize.height <= 0 {
+        return Err(anyhow!(
+            "get_structuring_element: invalid kernel size {}x{}",
+            ksize.width,
+            ksize.height
+        ));
+    }
+    let anchor = resolve_anchor("get_structuring_element", anchor, ksize)?;
+    let (r, c) = (ksize.height / 2, ksize.width / 2);
+    let mut kernel = Mat::zeros(ksize.height, ksize.width, CV_8UC1);
+    for i in 0..ksize.height {
+        let (j1, j2) = match shape {
+            MORPH_RECT => (0, ksize.width),
+            MORPH_CROSS if i == anchor.y => (0, ksize.width),
+            MORPH_CROSS => (anchor.x, anchor.x + 1),
+            _ => {
+                let dy = i - r;
+                // 与 OpenCV 相同的取整方式，保证 3x3 / 5x5 等常用尺寸的形状一致；
+                // 高为 1 时 OpenCV 取 inv_r2 = 0，只保留中心点
+                let dx = if r == 0 {
+                    0
+                } else {
+                    let ratio = (r * r - dy * dy) as f64 / (r * r) as f64;
+                    (c as f64 * ratio.sqrt()).round() as i32
+                };
+                ((c - dx).max(0), (c + dx + 1).min(ksize.width))
+            }
+        };
+        kernel.row_bytes_mut(i)[j1 as usize..j2 as usize].fill(1);
+    }
+    Ok(kernel)
+}
+
+/// 腐蚀：`dst(x, y) = min(src(x + i - anchor.x, y + j - anchor.y))`，`(i, j)` 取核的非零元素
+///
+/// `kernel` 为空时使用 3x3 矩形；`iterations` 为重复次数，为 0 时原样复制。
+pub fn erode<A, K>(
+    src: &A,
+    dst: &mut Mat,
+    kernel: &K,
+    anchor: Point,
+    iterations: i32,
+    border_type: i32,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    K: AsMatView + ?Sized,
+{
+    morph(
+        "erode",
+        &src.view(),
+        dst,
+        false,
+        &kernel.view(),
+        anchor,
+        iterations,
+        border_type,
+    )
+}
+
+/// 膨胀：`dst(x, y) = max(src(x + i - anchor.x, y + j - anchor.y))`，参数含义同 `erode`
+pub fn dilate<A, K>(
+    src: &A,
+    dst: &mut Mat,
+    kernel: &K,
+    anchor: Point,
+    iterations: i32,
+    border_type: i32,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    K: AsMatView + ?Sized,
+{
+    morph(
+        "dilate",
+        &src.view(),
+        dst,
+        true,
+        &kernel.view(),
+        anchor,
+        iterations,
+        border_type,
+    )
+}
+
+/// 由腐蚀和膨胀组合的形态学运算，`op` 为 `MORPH_*` 运算类型
+///
+/// `iterations` 作用于每一步腐蚀 / 膨胀，例如 2 次开运算为腐蚀两次再膨胀两次。
+#[allow(clippy::too_many_arguments)] // 与 OpenCV 的 morphologyEx 参数一致
+pub fn morphology_ex<A, K>(
+    src: &A,
+    dst: &mut Mat,
+    op: i32,
+    kernel: &K,
+    anchor: Point,
+    iterations: i32,
+    border_type: i32,
+) -> Result<()>
+where
+    A: AsMatView + ?Sized,
+    K: AsMatView + ?Sized,
+{
+    let src = src.view();
+    let kernel = kernel.view();
+    let name = "morphology_ex";
+    let erode = |s: &MatView<'_>, d: &mut Mat| {
+        morph(name, s, d, false, &kernel, anchor, iterations, border_type)
+    };
+    let dilate = |s: &MatView<'_>, d: &mut Mat| {
+        morph(name, s, d, true, &kernel, anchor, iterations, border_type)
+    };
+    let mut tmp = Mat::empty();
+    match op {
+        MORPH_ERODE => erode(&src, dst),
+        MORPH_DILATE => dilate(&src, dst),
+        MORPH_OPEN => {
+            erode(&src, &mut tmp)?;
+            dilate(&tmp.view(), dst)
+        }
+        MORPH_CLOSE => {
+            dilate(&src, &mut tmp)?;
+            erode(&tmp.view(), dst)
+        }
+        MORPH_GRADIENT => {
+            let mut eroded = Mat::empty();
+            erode(&src, &mut eroded)?;
+            dilate(&src, &mut tmp)?;
+            subtract(&tmp, &eroded, dst, None)
+        }
+        MORPH_TOPHAT => {
+            let mut opened = Mat::empty();
+            erode(&src, &mut tmp)?;
+            dilate(&tmp.view(), &mut opened)?;
+            subtract(&src, &opened, dst, None)
+        }
+        MORPH_BLACKHAT => {
+            let mut closed = Mat::empty();
+            dilate(&src, &mut tmp)?;
+            erode(&tmp.view(), &mut closed)?;
+            subtract(&closed, &src, dst, None)
+        }
+        MORPH_HITMISS => hit_miss(&src, dst, &kernel, anchor, iterations, border_type),
+        _ => Err(anyhow!("morphology_ex: unknown operation {}", op)),
+    }
+}
+
+/// 前景用核中的 1 腐蚀，背景 (取反后) 用核中的 -1 腐蚀，两者都命中的位置输出 255
+///
+/// 与 OpenCV 一致：核中没有 1 (或 -1) 时对应的一项视为处处命中，核全为 0 时原样复制。
+fn hit_miss(
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    kernel: &MatView<'_>,
+    anchor: Point,
+    iterations: i32,
+    border_type: i32,
+) -> Result<()> {
+    let name = "morphology_ex";
+    check_input(name, src)?;
+    if src.typ() != CV_8UC1 {
+        return Err(anyhow!(
+            "morphology_ex: MORPH_HITMISS needs a CV_8UC1 image, got {}",
+            type_name(src.typ())
+        ));
+    }
+    if kernel.channels != 1 || kernel.is_empty() {
+        return Err(anyhow!(
+            "morphology_ex: kernel must be a non-empty single-channel matrix"
+        ));
+    }
+    let weights = dispatch_depth!(kernel.depth, values(kernel));
+    if weights.iter().all(|&w| w == 0.0) {
+        *dst = src.to_mat();
+        return Ok(());
+    }
+
+    // 二值化后再腐蚀，使灰度输入也按"非零即前景"处理
+    let mut binary = Mat::zeros(src.rows, src.cols, CV_8UC1);
+    for r in 0..src.rows {
+        for (d, &v) in binary.row_mut::<u8>(r).iter_mut().zip(src.row::<u8>(r)) {
+            *d = if v != 0 { 255 } else { 0 };
+        }
+    }
+    let term = |want: f64, img: &Mat| -> Result<Mat> {
+        let k: Vec<u8> = weights.iter().map(|&w| (w == want) as u8).collect();
+        let mut out = Mat::zeros(src.rows, src.cols, CV_8UC1);
+        if k.contains(&1) {
+            let k = Mat::from_slice(kernel.rows, kernel.cols, CV_8UC1, &k)?;
+            let (img, k) = (img.view(), k.view());
+            morph(
+                name,
+                &img,
+                &mut out,
+                false,
+                &k,
+                anchor,
+                iterations,
+                border_type,
+            )?;
+        } else {
+            out.data.as_mut_slice().fill(255);
+        }
+        Ok(out)
+    };
+    let mut inverted = Mat::empty();
+    bitwise_not(&binary, &mut inverted, None)?;
+    let (hit, miss) = (term(1.0, &binary)?, term(-1.0, &inverted)?);
+    bitwise_and(&hit, &miss, dst, None)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn morph(
+    name: &str,
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    dilate: bool,
+    kernel: &MatView<'_>,
+    anchor: Point,
+    iterations: i32,
+    border_type: i32,
+) -> Result<()> {
+    check_input(name, src)?;
+    check_border(name, border_type)?;
+    if iterations < 0 {
+        return Err(anyhow!("{}: negative iteration count {}", name, iterations));
+    }
+    if iterations == 0 {
+        *dst = src.to_mat();
+        return Ok(());
+    }
+    let default_kernel;
+    let kernel = if kernel.is_empty() {
+        default_kernel = Mat::ones(3, 3, CV_8UC1);
+        default_kernel.view()
+    } else {
+        *kernel
+    };
+    morph_pass(name, src, dst, dilate, &kernel, anchor, border_type)?;
+    let mut tmp = Mat::empty();
+    for _ in 1..iterations {
+        std::mem::swap(dst, &mut tmp);
+        morph_pass(name, &tmp.view(), dst, dilate, &kernel, anchor, border_type)?;
+    }
+    Ok(())
+}
+
+/// 一次腐蚀 / 膨胀
+fn morph_pass(
+    name: &str,
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    dilate: bool,
+    kernel: &MatView<'_>,
+    anchor: Point,
+    border_type: i32,
+) -> Result<()> {
+    if kernel.channels != 1 {
+        return Err(anyhow!(
+            "{}: kernel must be a single-channel matrix, got {}",
+            name,
+            type_name(kernel.typ())
+        ));
+    }
+    let ksize = Size::new(kernel.cols, kernel.rows);
+    let anchor = resolve_anchor(name, anchor, ksize)?;
+    let weights = dispatch_depth!(kernel.depth, values(kernel));
+    let offsets: Vec<(usize, usize)> = weights
+        .iter()
+        .enumerate()
+        .filter(|&(_, &w)| w != 0.0)
+        .map(|(i, _)| (i / ksize.width as usize, i % ksize.width as usize))
+        .collect();
+    if offsets.is_empty() {
+        *dst = src.to_mat();
+        return Ok(());
+    }
+    dispatch_depth!(
+        src.depth,
+        extremum(src, dst, &offsets, ksize, anchor, border_type, dilate)
+    );
+    Ok(())
+}
+
+/// `offsets` 为核中非零元素的 `(行, 列)`；越界且为 `BORDER_CONSTANT` 的邻居被跳过
+fn extremum<T: Sample>(
+    src: &MatView<'_>,
+    dst: &mut Mat,
+    offsets: &[(usize, usize)],
+    ksize: Size,
+    anchor: Point,
+    border_type: i32,
+    dilate: bool,
+) {
+    let cn = src.channels as usize;
+    let xmap = border_map(src.cols, anchor.x, ksize.width - 1 - anchor.x, border_type);
+    let ymap = border_map(src.rows, anchor.y, ksize.height - 1 - anchor.y, border_type);
+    dst.create(src.rows, src.cols, src.typ());
+    for y in 0..src.rows as usize {
+        let d = dst.row_mut::<T>(y as i32);
+        for x in 0..src.cols as usize {
+            for c in 0..cn {
+                let mut best: Option<T> = None;
+                for &(i, j) in offsets {
+                    let (Some(sy), Some(sx)) = (ymap[y + i], xmap[x + j]) else {
+                        continue;
+                    };
+                    let v = src.row::<T>(sy as i32)[sx * cn + c];
+                    let better = match best {
+                        Some(b) if dilate => v > b,
+                        Some(b) => v < b,
+                        None => true,
+                    };
+                    if better {
+                        best = Some(v);
+                    }
+                }
+                // 邻域全部在图像外时保持原值
+                d[x * cn + c] = best.unwrap_or(src.row::<T>(y as i32)[x * cn + c]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::{CV_16SC1, CV_32FC1};
+    use crate::imgproc::filter::{BORDER_CONSTANT, BORDER_REPLICATE};
+
+    const CENTER: Point = Point { x: -1, y: -1 };
+
+    #[test]
+    fn structuring_elements() {
+        let k = get_structuring_element(MORPH_ELLIPSE, Size::new(5, 5), CENTER).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(k.data, [
+            0, 0, 1, 0, 0,
+            1, 1, 1, 1, 1,
+            1, 1, 1, 1, 1,
+            1, 1, 1, 1, 1,
+            0, 0, 1, 0, 0,
+        ]);
+        let k = get_structuring_element(MORPH_ELLIPSE, Size::new(5, 1), CENTER).unwrap();
+        assert_eq!(k.data, [0, 0, 1, 0, 0]);
+        let k = get_structuring_element(MORPH_CROSS, Size::new(3, 3), Point::new(0, 2)).unwrap();
+        assert_eq!(k.data, [1, 0, 0, 1, 0, 0, 1, 1, 1]);
+        let k = get_structuring_element(MORPH_RECT, Size::new(3, 2), CENTER).unwrap();
+        assert_eq!(
+            (k.rows, k.cols, k.data.iter().all(|&v| v == 1)),
+            (2, 3, true)
+        );
+        assert!(get_structuring_element(3, Size::new(3, 3), CENTER).is_err());
+        assert!(get_structuring_element(MORPH_RECT, Size::new(3, 3), Point::new(3, 0)).is_err());
+    }
+
+    #[test]
+    fn erode_dilate_open_close() {
+        // 7x7 背景上有一个 3x3 方块、一个孤立噪点，方块中心有一个洞
+        let mut mask = Mat::zeros(7, 7, CV_8UC1);
+        for r in 1..4 {
+            mask.row_bytes_mut(r)[1..4].fill(255);
+        }
+        *mask.at_mut::<u8>(2, 2) = 0;
+        *mask.at_mut::<u8>(5, 5) = 255;
+        let none = Mat::empty();
+        let mut out = Mat::empty();
+
+        dilate(&mask, &mut out, &none, CENTER, 1, BORDER_CONSTANT).unwrap();
+        assert_eq!(out.row::<u8>(0), [255, 255, 255, 255, 255, 0, 0]);
+        assert_eq!(out.row::<u8>(6), [0, 0, 0, 0, 255, 255, 255]);
+        // 图像外不参与腐蚀，贴边的前景不会被边界侵蚀
+        let full = Mat::ones(3, 3, CV_8UC1);
+        erode(&full, &mut out, &none, CENTER, 1, BORDER_CONSTANT).unwrap();
+        assert_eq!(out.data, full.data);
+
+        morphology_ex(
+            &mask,
+            &mut out,
+            MORPH_CLOSE,
+            &none,
+            CENTER,
+            1,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        assert_eq!(*out.at::<u8>(2, 2), 255);
+        assert_eq!(*out.at::<u8>(5, 5), 255);
+        morphology_ex(
+            &mask,
+            &mut out,
+            MORPH_OPEN,
+            &none,
+            CENTER,
+            1,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        assert!(out.data.iter().all(|&v| v == 0));
+
+        // 灰度图：梯度、顶帽、黑帽；多次迭代等价于更大的矩形核
+        let ramp: Vec<f32> = (0..25).map(|i| (i % 5 * 10) as f32).collect();
+        let ramp = Mat::from_slice(5, 5, CV_32FC1, &ramp).unwrap();
+        morphology_ex(
+            &ramp,
+            &mut out,
+            MORPH_GRADIENT,
+            &none,
+            CENTER,
+            1,
+            BORDER_REPLICATE,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(2), [10.0, 20.0, 20.0, 20.0, 10.0]);
+        morphology_ex(
+            &ramp,
+            &mut out,
+            MORPH_TOPHAT,
+            &none,
+            CENTER,
+            1,
+            BORDER_REPLICATE,
+        )
+        .unwrap();
+        assert_eq!(out.row::<f32>(2), [0.0, 0.0, 0.0, 0.0, 10.0]);
+        let spike = Mat::from_slice(1, 5, CV_16SC1, &[5i16, 5, -20, 5, 5]).unwrap();
+        morphology_ex(
+            &spike,
+            &mut out,
+            MORPH_BLACKHAT,
+            &none,
+            CENTER,
+            1,
+            BORDER_REPLICATE,
+        )
+        .unwrap();
+        assert_eq!(out.row::<i16>(0), [0, 0, 25, 0, 0]);
+        let mut once = Mat::empty();
+        erode(&ramp, &mut out, &none, CENTER, 2, BORDER_REPLICATE).unwrap();
+        let k5 = get_structuring_element(MORPH_RECT, Size::new(5, 5), CENTER).unwrap();
+        erode(&ramp, &mut once, &k5, CENTER, 1, BORDER_REPLICATE).unwrap();
+        assert_eq!(out.data, once.data);
+        assert!(morphology_ex(&ramp, &mut out, 8, &none, CENTER, 1, BORDER_CONSTANT).is_err());
+
+        let empty = Mat::zeros(0, 4, CV_8UC1);
+        assert!(erode(&empty, &mut out, &none, CENTER, 1, BORDER_REPLICATE).is_err());
+        assert!(morphology_ex(
+            &empty,
+            &mut out,
+            MORPH_CLOSE,
+            &none,
+            CENTER,
+            1,
+            BORDER_REPLICATE
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn hit_miss_finds_isolated_points() {
+        let mut img = Mat::zeros(5, 5, CV_8UC1);
+        *img.at_mut::<u8>(1, 1) = 255;
+        img.row_bytes_mut(3)[2..4].fill(200);
+        // 中心为前景、8 邻域都是背景
+        let mut kernel = Mat::from_slice(3, 3, CV_16SC1, &[-1i16; 9]).unwrap();
+        *kernel.at_mut::<i16>(1, 1) = 1;
+        let mut out = Mat::empty();
+        morphology_ex(
+            &img,
+            &mut out,
+            MORPH_HITMISS,
+            &kernel,
+            CENTER,
+            1,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        let hits: Vec<(usize, u8)> = out
+            .data
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != 0)
+            .map(|(i, &v)| (i, v))
+            .collect();
+        assert_eq!(hits, [(6, 255)]);
+
+        // 只有 1 的核等价于腐蚀；全 0 的核原样复制
+        let mut block = Mat::zeros(5, 5, CV_8UC1);
+        for r in 1..4 {
+            block.row_bytes_mut(r)[1..4].fill(255);
+        }
+        let ones = Mat::ones(3, 3, CV_16SC1);
+        let mut eroded = Mat::empty();
+        erode(&block, &mut eroded, &ones, CENTER, 1, BORDER_CONSTANT).unwrap();
+        morphology_ex(
+            &block,
+            &mut out,
+            MORPH_HITMISS,
+            &ones,
+            CENTER,
+            1,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        assert_eq!(*out.at::<u8>(2, 2), 255);
+        assert_eq!(out.data, eroded.data);
+        let zeros = Mat::zeros(3, 3, CV_16SC1);
+        morphology_ex(
+            &block,
+            &mut out,
+            MORPH_HITMISS,
+            &zeros,
+            CENTER,
+            1,
+            BORDER_CONSTANT,
+        )
+        .unwrap();
+        assert_eq!(out.data, block.data);
+
+        let ramp = Mat::zeros(5, 5, CV_32FC1);
+        assert!(morphology_ex(
+            &ramp,
+            &mut out,
+            MORPH_HITMISS,
+            &kernel,
+            CENTER,
+            1,
+            BORDER_CONSTANT
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,357 @@
+//! 阈值分割 (对应 OpenCV 的 `threshold` / `adaptiveThreshold`)
+//!
+//! `threshold` 支持所有深度与通道数，各通道独立处理；整数深度下阈值先向下取整。
+//! 自动阈值 (`THRESH_OTSU` / `THRESH_TRIANGLE`) 与自适应阈值只支持 `CV_8UC1`。
+
+use super::filter::{box_filter, check_input, gaussian_blur, BORDER_REPLICATE};
+use crate::core::mat::{dispatch_depth, type_name, Mat, Sample, CV_32F, CV_8UC1};
+use crate::core::types::{Point, Size};
+use crate::core::view::{AsMatView, MatView};
+use anyhow::{anyhow, Result};
+
+/// `dst = src > thresh ? maxval : 0`
+pub const THRESH_BINARY: i32 = 0;
+/// `dst = src > thresh ? 0 : maxval`
+pub const THRESH_BINARY_INV: i32 = 1;
+/// `dst = src > thresh ? thresh : src`
+pub const THRESH_TRUNC: i32 = 2;
+/// `dst = src > thresh ? src : 0`
+pub const THRESH_TOZERO: i32 = 3;
+/// `dst = src > thresh ? 0 : src`
+pub const THRESH_TOZERO_INV: i32 = 4;
+/// 取出阈值类型的掩码 (去掉自动阈值标志)
+pub const THRESH_MASK: i32 = 7;
+/// 用 Otsu 法 (类间方差最大) 自动选择阈值，与上面的类型按位或组合
+pub const THRESH_OTSU: i32 = 8;
+/// 用三角法自动选择阈值，适合单峰直方图，与上面的类型按位或组合
+pub const THRESH_TRIANGLE: i32 = 16;
+
+/// 阈值取 `block_size` 邻域的均值减去 `c`
+pub const ADAPTIVE_THRESH_MEAN_C: i32 = 0;
+/// 阈值取 `block_size` 邻域的高斯加权均值减去 `c`
+pub const ADAPTIVE_THRESH_GAUSSIAN_C: i32 = 1;
+
+/// 固定阈值分割，返回实际使用的阈值 (自动阈值时为计算结果)
+///
+/// ```no_run
+/// # use rustcv::core::mat::Mat;
+/// # use rustcv::imgproc::{threshold, THRESH_BINARY, THRESH_OTSU};
+/// # let gray = Mat::empty();
+/// let mut mask = Mat::empty();
+/// let t = threshold(&gray, &mut mask, 0.0, 255.0, THRESH_BINARY | THRESH_OTSU)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn threshold<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    thresh: f64,
+    maxval: f64,
+    typ: i32,
+) -> Result<f64> {
+    let src = src.view();
+    check_input("threshold", &src)?;
+    let kind = typ & THRESH_MASK;
+    if kind > THRESH_TOZERO_INV {
+        return Err(anyhow!("threshold: unknown threshold type {}", typ));
+    }
+    let thresh = match typ & !THRESH_MASK {
+        0 => thresh,
+        auto @ (THRESH_OTSU | THRESH_TRIANGLE) => {
+            if src.typ() != CV_8UC1 {
+                return Err(anyhow!(
+                    "threshold: automatic thresholds need a CV_8UC1 image, got {}",
+                    type_name(src.typ())
+                ));
+            }
+            let hist = histogram(&src);
+            if auto == THRESH_OTSU {
+                otsu(&hist)
+            } else {
+                triangle(&hist)
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "threshold: THRESH_OTSU and THRESH_TRIANGLE cannot be combined"
+            ))
+        }
+    };
+    let thresh = if src.depth == CV_32F {
+        thresh
+    } else {
+        thresh.floor()
+    };
+    dispatch_depth!(src.depth, apply(&src, dst, thresh, maxval, kind));
+    Ok(thresh)
+}
+
+/// 自适应阈值：每个像素的阈值为邻域 (加权) 均值减去 `c`
+///
+/// `threshold_type` 只能是 `THRESH_BINARY` 或 `THRESH_BINARY_INV`；`block_size` 为大于 1 的奇数。
+/// 邻域越出图像的部分按 `BORDER_REPLICATE` 取值。光照不均 (如背光传送带) 时比固定阈值稳定。
+pub fn adaptive_threshold<A: AsMatView + ?Sized>(
+    src: &A,
+    dst: &mut Mat,
+    max_value: f64,
+    adaptive_method: i32,
+    threshold_type: i32,
+    block_size: i32,
+    c: f64,
+) -> Result<()> {
+    let src = src.view();
+    check_input("adaptive_threshold", &src)?;
+    if src.typ() != CV_8UC1 {
+        return Err(anyhow!(
+            "adaptive_threshold: expected a CV_8UC1 image, got {}",
+            type_name(src.typ())
+        ));
+    }
+    if block_size <= 1 || block_size % 2 == 0 {
+        return Err(anyhow!(
+            "adaptive_threshold: block size must be an odd number greater than 1, got {}",
+            block_size
+        ));
+    }
+    if threshold_type != THRESH_BINARY && threshold_type != THRESH_BINARY_INV {
+        return Err(anyhow!(
+            "adaptive_threshold: threshold type must be THRESH_BINARY or THRESH_BINARY_INV"
+        ));
+    }
+
+    let ksize = Size::new(block_size, block_size);
+    let mut mean = Mat::empty();
+    match adaptive_method {
+        ADAPTIVE_THRESH_MEAN_C => box_filter(
+            &src,
+            &mut mean,
+            -1,
+            ksize,
+            Point::new(-1, -1),
+            true,
+            BORDER_REPLICATE,
+        )?,
+        ADAPTIVE_THRESH_GAUSSIAN_C => {
+            gaussian_blur(&src, &mut mean, ksize, 0.0, 0.0, BORDER_REPLICATE)?
+        }
+        _ => {
+            return Err(anyhow!(
+                "adaptive_threshold: unknown adaptive method {}",
+                adaptive_method
+            ))
+        }
+    }
+
+    // 与 OpenCV 一致：c 按比较方向取整，比较 src - mean 与 -c
+    let maxval = u8::from_f64(max_value);
+    let c = if threshold_type == THRESH_BINARY {
+        c.ceil()
+    } else {
+        c.floor()
+    } as i32;
+    dst.create(src.rows, src.cols, CV_8UC1);
+    for r in 0..src.rows {
+        let (s, m) = (src.row::<u8>(r), mean.row::<u8>(r));
+        for ((d, &v), &m) in dst.row_mut::<u8>(r).iter_mut().zip(s).zip(m) {
+            let above = v as i32 - m as i32 > -c;
+            *d = if above == (threshold_type == THRESH_BINARY) {
+                maxval
+            } else {
+                0
+            };
+        }
+    }
+    Ok(())
+}
+
+fn apply<T: Sample>(src: &MatView<'_>, dst: &mut Mat, thresh: f64, maxval: f64, kind: i32) {
+    let (t, maxval, zero) = (T::from_f64(thresh), T::from_f64(maxval), T::from_f64(0.0));
+    dst.create(src.rows, src.cols, src.typ());
+    for r in 0..src.rows {
+        let s = src.row::<T>(r);
+        for (d, &v) in dst.row_mut::<T>(r).iter_mut().zip(s) {
+            let above = v.to_f64() > thresh;
+            *d = match (kind, above) {
+                (THRESH_BINARY, true) | (THRESH_BINARY_INV, false) => maxval,
+                (THRESH_BINARY, false) | (THRESH_BINARY_INV, true) => zero,
+                (THRESH_TRUNC, true) => t,
+                (THRESH_TOZERO, true) | (THRESH_TOZERO_INV, false) | (THRESH_TRUNC, false) => v,
+                _ => zero,
+            };
+        }
+    }
+}
+
+fn histogram(src: &MatView<'_>) -> [u32; 256] {
+    let mut hist = [0u32; 256];
+    for r in 0..src.rows {
+        for &v in src.row::<u8>(r) {
+            hist[v as usize] += 1;
+        }
+    }
+    hist
+}
+
+/// 使类间方差最大的阈值
+fn otsu(hist: &[u32; 256]) -> f64 {
+    let total: f64 = hist.iter().map(|&h| h as f64).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    let mu: f64 = hist
+        .iter()
+        .enumerate()
+        .map(|(i, &h)| i as f64 * h as f64)
+        .sum::<f64>()
+        / total;
+    let (mut q1, mut mu1) = (0.0, 0.0);
+    let (mut max_sigma, mut best) = (0.0, 0);
+    for (i, &h) in hist.iter().enumerate() {
+        let p = h as f64 / total;
+        mu1 *= q1;
+        q1 += p;
+        let q2 = 1.0 - q1;
+        if q1.min(q2) < f32::EPSILON as f64 || q1.max(q2) > 1.0 - f32::EPSILON as f64 {
+            continue;
+        }
+        mu1 = (mu1 + i as f64 * p) / q1;
+        let mu2 = (mu - q1 * mu1) / q2;
+        let sigma = q1 * q2 * (mu1 - mu2) * (mu1 - mu2);
+        if sigma > max_sigma {
+            max_sigma = sigma;
+            best = i;
+        }
+    }
+    best as f64
+}
+
+/// 三角法：直方图峰顶与较长一侧尾端连线，取离连线最远的灰度
+fn triangle(hist: &[u32; 256]) -> f64 {
+    const N: usize = 256;
+    let mut left = hist.iter().position(|&h| h > 0).unwrap_or(0);
+    let mut right = (1..N).rev().find(|&i| hist[i] > 0).unwrap_or(0);
+    left = left.saturating_sub(1);
+    if right < N - 1 {
+        right += 1;
+    }
+    let mut peak = (0..N).fold(0, |p, i| if hist[i] > hist[p] { i } else { p });
+
+    // 统一成尾端在峰顶左侧
+    let mut h = *hist;
+    let flipped = peak - left < right - peak;
+    if flipped {
+        h.reverse();
+        left = N - 1 - right;
+        peak = N - 1 - peak;
+    }
+    let (a, b) = (h[peak] as f64, left as f64 - peak as f64);
+    let (mut thresh, mut dist) = (left, 0.0);
+    for (i, &v) in h.iter().enumerate().take(peak + 1).skip(left + 1) {
+        let d = a * i as f64 + b * v as f64;
+        if d > dist {
+            dist = d;
+            thresh = i;
+        }
+    }
+    // 与 OpenCV 相同不做下限保护：可能得到 -1，翻转后为 256
+    let thresh = thresh as isize - 1;
+    (if flipped {
+        N as isize - 1 - thresh
+    } else {
+        thresh
+    }) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mat::CV_32FC1;
+
+    #[test]
+    fn threshold_types() {
+        let src = Mat::from_slice(1, 5, CV_8UC1, &[0u8, 99, 100, 101, 255]).unwrap();
+        let mut dst = Mat::empty();
+        let expected: [(i32, [u8; 5]); 5] = [
+            (THRESH_BINARY, [0, 0, 0, 200, 200]),
+            (THRESH_BINARY_INV, [200, 200, 200, 0, 0]),
+            (THRESH_TRUNC, [0, 99, 100, 100, 100]),
+            (THRESH_TOZERO, [0, 0, 0, 101, 255]),
+            (THRESH_TOZERO_INV, [0, 99, 100, 0, 0]),
+        ];
+        for (kind, want) in expected {
+            // 整数图像的阈值向下取整
+            assert_eq!(
+                threshold(&src, &mut dst, 100.7, 200.0, kind).unwrap(),
+                100.0
+            );
+            assert_eq!(dst.data, want, "type {}", kind);
+        }
+
+        let src = Mat::from_slice(1, 3, CV_32FC1, &[0.25f32, 0.5, 0.75]).unwrap();
+        assert_eq!(
+            threshold(&src, &mut dst, 0.4, 1.0, THRESH_TRUNC).unwrap(),
+            0.4
+        );
+        assert_eq!(dst.row::<f32>(0), [0.25, 0.4, 0.4]);
+        assert!(threshold(&src, &mut dst, 0.0, 1.0, 5).is_err());
+        assert!(threshold(&src, &mut dst, 0.0, 1.0, THRESH_OTSU).is_err());
+    }
+
+    #[test]
+    fn automatic_thresholds() {
+        // 双峰：暗背景 20±2，亮零件 200±2
+        let data: Vec<u8> = (0..100)
+            .map(|i| if i < 60 { 18 + i % 5 } else { 198 + i % 5 } as u8)
+            .collect();
+        let img = Mat::from_slice(10, 10, CV_8UC1, &data).unwrap();
+        let mut mask = Mat::empty();
+        let t = threshold(&img, &mut mask, 0.0, 255.0, THRESH_BINARY | THRESH_OTSU).unwrap();
+        assert!((22.0..198.0).contains(&t), "otsu {}", t);
+        assert_eq!(mask.data.iter().filter(|&&v| v == 255).count(), 40);
+
+        // 单峰加一条长尾：三角法阈值落在峰顶与尾端之间，靠近峰顶
+        let data: Vec<u8> = (0..100)
+            .map(|i| if i < 80 { 50 } else { 60 + (i - 80) * 8 } as u8)
+            .collect();
+        let img = Mat::from_slice(10, 10, CV_8UC1, &data).unwrap();
+        let t = threshold(&img, &mut mask, 0.0, 255.0, THRESH_BINARY | THRESH_TRIANGLE).unwrap();
+        assert!((50.0..60.0).contains(&t), "triangle {}", t);
+        assert_eq!(mask.data.iter().filter(|&&v| v == 255).count(), 20);
+
+        // 均匀直方图：与 OpenCV 一样得到 256，二值化结果全为 0
+        let data: Vec<u8> = (0..=255).collect();
+        let img = Mat::from_slice(16, 16, CV_8UC1, &data).unwrap();
+        let t = threshold(&img, &mut mask, 0.0, 255.0, THRESH_BINARY | THRESH_TRIANGLE).unwrap();
+        assert_eq!(t, 256.0);
+        assert!(mask.data.iter().all(|&v| v == 0));
+
+        let empty = Mat::zeros(0, 4, CV_8UC1);
+        assert!(threshold(&empty, &mut mask, 0.0, 255.0, THRESH_BINARY | THRESH_OTSU).is_err());
+        assert!(threshold(&empty, &mut mask, 0.0, 255.0, THRESH_TRIANGLE).is_err());
+    }
+
+    #[test]
+    fn adaptive_threshold_follows_uneven_lighting() {
+        // 亮度从左到右递增的背景上有一条比局部背景暗 30 的竖线
+        let mut img = Mat::zeros(9, 40, CV_8UC1);
+        for r in 0..9 {
+            for (c, v) in img.row_mut::<u8>(r).iter_mut().enumerate() {
+                *v = (40 + c * 5) as u8 - if c == 30 { 30 } else { 0 };
+            }
+        }
+        let mut dst = Mat::empty();
+        for method in [ADAPTIVE_THRESH_MEAN_C, ADAPTIVE_THRESH_GAUSSIAN_C] {
+            adaptive_threshold(&img, &mut dst, 255.0, method, THRESH_BINARY_INV, 5, 10.0).unwrap();
+            for r in 0..9 {
+                let dark: Vec<usize> = (0..40)
+                    .filter(|&c| *dst.at::<u8>(r, c as i32) == 255)
+                    .collect();
+                assert_eq!(dark, [30], "method {}", method);
+            }
+        }
+        assert!(adaptive_threshold(&img, &mut dst, 255.0, 2, THRESH_BINARY, 5, 0.0).is_err());
+        assert!(adaptive_threshold(&img, &mut dst, 255.0, 0, THRESH_TRUNC, 5, 0.0).is_err());
+        assert!(adaptive_threshold(&img, &mut dst, 255.0, 0, THRESH_BINARY, 4, 0.0).is_err());
+        let empty = Mat::zeros(0, 4, CV_8UC1);
+        assert!(adaptive_threshold(&empty, &mut dst, 255.0, 0, THRESH_BINARY, 5, 0.0).is_err());
+    }
+}
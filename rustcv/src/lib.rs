@@ -12,5 +12,5 @@ pub use core::mat::Mat;
 pub mod prelude {
     pub use crate::core::mat::Mat;
     pub use crate::core::tick_meter::TickMeter; // 高精度计时器
-    pub use crate::videoio::VideoCapture;
+    pub use crate::videoio::{VideoCapture, VideoWriter};
 }
@@ -0,0 +1,965 @@
+//! MJPEG AVI 封装
+//!
+//! 布局与 OpenCV 的 MJPEG `VideoWriter` 相同：单个视频流，每帧一个 `00dc` 块，
+//! 文件末尾是 AVI 1.0 的 `idx1` 索引。
+//!
+//! 超过 1 GB 时按 OpenDML (AVI 2.0) 继续写 `RIFF AVIX` 段：每个 `movi` 末尾写标准索引 `ix00`，
+//! 头部预留的 `JUNK` 在结束时改写为超级索引 `indx` 和 `LIST odml`。
+//! 小文件保持纯 AVI 1.0，老播放器也能打开。
+//!
+//! `AviReader` 读取上述两种文件，以及其他写入器生成的 MJPEG AVI。
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+const AVIF_HASINDEX: u32 = 0x10;
+const AVIIF_KEYFRAME: u32 = 0x10;
+const AVI_INDEX_OF_INDEXES: u8 = 0x00;
+const AVI_INDEX_OF_CHUNKS: u8 = 0x01;
+
+/// OpenDML 约定：每个 RIFF 段不超过 1 GB
+pub(crate) const SEGMENT_LIMIT: u64 = 1 << 30;
+/// 超级索引预留的段数 (即文件上限约 256 GB)
+const SUPER_INDEX_ENTRIES: usize = 256;
+const SUPER_INDEX_LEN: u32 = 24 + 16 * SUPER_INDEX_ENTRIES as u32;
+const DMLH_LEN: u32 = 248;
+/// `LIST odml` 整体大小 (含块头)
+const ODML_LIST_LEN: u32 = 12 + 8 + DMLH_LEN;
+
+/// 头部中结束时需要回填的位置
+#[derive(Debug, Clone, Copy)]
+struct HeaderLayout {
+    avih: u64,
+    strh: u64,
+    indx: u64,
+    odml: u64,
+}
+
+/// 把 fps 转换为 AVI 的 `dwRate / dwScale`
+pub(crate) fn frame_rate(fps: f64) -> (u32, u32) {
+    if fps.fract() == 0.0 {
+        return (fps as u32, 1);
+    }
+    // 非整数帧率按千分之一精度表示 (29.97 -> 2997/100)
+    let (mut rate, mut scale) = ((fps * 1000.0).round() as u32, 1000u32);
+    let (mut a, mut b) = (rate, scale);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a > 1 {
+        rate /= a;
+        scale /= a;
+    }
+    (rate, scale)
+}
+
+/// MJPEG AVI 写入器
+///
+/// 只负责容器格式，帧数据 (JPEG) 原样写入。
+#[derive(Debug)]
+pub(crate) struct AviWriter<W: Write + Seek> {
+    out: W,
+    /// 当前写入位置 (文件末尾)
+    pos: u64,
+    rate: u32,
+    scale: u32,
+    layout: HeaderLayout,
+    segment_limit: u64,
+    /// 当前 RIFF 段与其 `LIST movi` 的起始位置
+    riff_start: u64,
+    movi_start: u64,
+    /// 当前段内各帧数据的绝对偏移与长度
+    chunks: Vec<(u64, u32)>,
+    /// 第一个 RIFF 段的帧数 (第一个段结束后确定)
+    first_segment_frames: Option<u32>,
+    /// 已写出的 `ix00`: (偏移, 块大小, 帧数)
+    std_indexes: Vec<(u64, u32, u32)>,
+    total_frames: u32,
+    max_chunk: u32,
+}
+
+impl<W: Write + Seek> AviWriter<W> {
+    pub(crate) fn new(mut out: W, width: u32, height: u32, fps: f64) -> io::Result<Self> {
+        let (rate, scale) = frame_rate(fps);
+        if rate == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid frame rate: {}", fps),
+            ));
+        }
+
+        let start = out.stream_position()?;
+        let (header, layout) = build_header(width, height, rate, scale);
+        out.write_all(&header)?;
+        let pos = start + header.len() as u64;
+
+        Ok(Self {
+            out,
+            pos,
+            rate,
+            scale,
+            layout: HeaderLayout {
+                avih: start + layout.avih,
+                strh: start + layout.strh,
+                indx: start + layout.indx,
+                odml: start + layout.odml,
+            },
+            segment_limit: SEGMENT_LIMIT,
+            riff_start: start,
+            // `LIST movi` 位于头部末尾
+            movi_start: pos - 12,
+            chunks: vec![],
+            first_segment_frames: None,
+            std_indexes: vec![],
+            total_frames: 0,
+            max_chunk: 0,
+        })
+    }
+
+    /// 缩小段上限，测试 OpenDML 时不必写满 1 GB
+    #[cfg(test)]
+    pub(crate) fn with_segment_limit(mut self, limit: u64) -> Self {
+        self.segment_limit = limit;
+        self
+    }
+
+    pub(crate) fn frames(&self) -> u32 {
+        self.total_frames
+    }
+
+    /// 追加一帧 (所有帧都是关键帧)
+    pub(crate) fn write_frame(&mut self, data: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Frame too large"))?;
+        let chunk_len = 8 + padded(len) as u64;
+
+        // 当前段写入这一帧后还要放得下自己的索引
+        let n = self.chunks.len() as u64 + 1;
+        let idx1_len = if self.first_segment_frames.is_none() {
+            8 + 16 * n
+        } else {
+            0
+        };
+        let index_len = idx1_len + 32 + 8 * n;
+        if !self.chunks.is_empty()
+            && self.pos + chunk_len + index_len > self.riff_start + self.segment_limit
+        {
+            self.next_segment()?;
+        }
+
+        self.out.write_all(b"00dc")?;
+        self.out.write_all(&len.to_le_bytes())?;
+        self.out.write_all(data)?;
+        if len % 2 == 1 {
+            self.out.write_all(&[0])?;
+        }
+        self.chunks.push((self.pos + 8, len));
+        self.pos += chunk_len;
+        self.total_frames += 1;
+        self.max_chunk = self.max_chunk.max(len);
+        Ok(())
+    }
+
+    /// 写索引、回填头部，返回底层输出
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        let odml = !self.std_indexes.is_empty();
+        self.close_segment(odml)?;
+
+        let suggested = padded(self.max_chunk) + 8;
+        let max_bytes_per_sec = (self.max_chunk as u64 * self.rate as u64 / self.scale as u64)
+            .min(u32::MAX as u64) as u32;
+
+        // avih: dwMaxBytesPerSec / dwTotalFrames (仅第一个段) / dwSuggestedBufferSize
+        let avih = self.layout.avih;
+        self.patch(avih + 4, &max_bytes_per_sec.to_le_bytes())?;
+        let first = self.first_segment_frames.unwrap_or(0);
+        self.patch(avih + 16, &first.to_le_bytes())?;
+        self.patch(avih + 28, &suggested.to_le_bytes())?;
+        // strh: dwLength (全部帧) / dwSuggestedBufferSize
+        let strh = self.layout.strh;
+        self.patch(strh + 32, &self.total_frames.to_le_bytes())?;
+        self.patch(strh + 36, &suggested.to_le_bytes())?;
+
+        if odml {
+            let mut indx = Vec::with_capacity(32 + 16 * self.std_indexes.len());
+            indx.extend_from_slice(b"indx");
+            indx.extend_from_slice(&SUPER_INDEX_LEN.to_le_bytes());
+            indx.extend_from_slice(&4u16.to_le_bytes());
+            indx.push(0);
+            indx.push(AVI_INDEX_OF_INDEXES);
+            indx.extend_from_slice(&(self.std_indexes.len() as u32).to_le_bytes());
+            indx.extend_from_slice(b"00dc");
+            indx.extend_from_slice(&[0; 12]);
+            for &(offset, size, duration) in &self.std_indexes {
+                indx.extend_from_slice(&offset.to_le_bytes());
+                indx.extend_from_slice(&size.to_le_bytes());
+                indx.extend_from_slice(&duration.to_le_bytes());
+            }
+            self.patch(self.layout.indx, &indx)?;
+
+            let mut odml_list = Vec::with_capacity(24);
+            odml_list.extend_from_slice(b"LIST");
+            odml_list.extend_from_slice(&(ODML_LIST_LEN - 8).to_le_bytes());
+            odml_list.extend_from_slice(b"odmldmlh");
+            odml_list.extend_from_slice(&DMLH_LEN.to_le_bytes());
+            odml_list.extend_from_slice(&self.total_frames.to_le_bytes());
+            self.patch(self.layout.odml, &odml_list)?;
+        }
+
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    /// 结束当前段，开始一个 `RIFF AVIX` 段
+    fn next_segment(&mut self) -> io::Result<()> {
+        // 关闭当前段和最终段各占一项
+        if self.std_indexes.len() + 2 > SUPER_INDEX_ENTRIES {
+            return Err(io::Error::other(
+                "AVI file exceeds the OpenDML index capacity",
+            ));
+        }
+        self.close_segment(true)?;
+
+        self.riff_start = self.pos;
+        self.movi_start = self.pos + 12;
+        self.out.write_all(b"RIFF\0\0\0\0AVIXLIST\0\0\0\0movi")?;
+        self.pos += 24;
+        Ok(())
+    }
+
+    fn close_segment(&mut self, odml: bool) -> io::Result<()> {
+        if odml {
+            self.write_std_index()?;
+        }
+        let movi_len = (self.pos - self.movi_start - 8) as u32;
+        self.patch(self.movi_start + 4, &movi_len.to_le_bytes())?;
+
+        if self.first_segment_frames.is_none() {
+            self.first_segment_frames = Some(self.chunks.len() as u32);
+            self.write_idx1()?;
+        }
+
+        let riff_len = (self.pos - self.riff_start - 8) as u32;
+        self.patch(self.riff_start + 4, &riff_len.to_le_bytes())?;
+        self.chunks.clear();
+        Ok(())
+    }
+
+    /// AVI 1.0 索引，偏移相对 `movi` 标识
+    fn write_idx1(&mut self) -> io::Result<()> {
+        let movi = self.movi_start + 8;
+        let mut buf = Vec::with_capacity(8 + 16 * self.chunks.len());
+        buf.extend_from_slice(b"idx1");
+        buf.extend_from_slice(&(16 * self.chunks.len() as u32).to_le_bytes());
+        for &(offset, len) in &self.chunks {
+            buf.extend_from_slice(b"00dc");
+            buf.extend_from_slice(&AVIIF_KEYFRAME.to_le_bytes());
+            buf.extend_from_slice(&((offset - 8 - movi) as u32).to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+        self.out.write_all(&buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+
+    /// OpenDML 标准索引，偏移相对当前 RIFF 段起点，指向帧数据
+    fn write_std_index(&mut self) -> io::Result<()> {
+        let base = self.riff_start;
+        let n = self.chunks.len() as u32;
+        let mut buf = Vec::with_capacity(32 + 8 * n as usize);
+        buf.extend_from_slice(b"ix00");
+        buf.extend_from_slice(&(24 + 8 * n).to_le_bytes());
+        buf.extend_from_slice(&2u16.to_le_bytes());
+        buf.push(0);
+        buf.push(AVI_INDEX_OF_CHUNKS);
+        buf.extend_from_slice(&n.to_le_bytes());
+        buf.extend_from_slice(b"00dc");
+        buf.extend_from_slice(&base.to_le_bytes());
+        buf.extend_from_slice(&[0; 4]);
+        for &(offset, len) in &self.chunks {
+            buf.extend_from_slice(&((offset - base) as u32).to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+
+        self.std_indexes.push((self.pos, buf.len() as u32, n));
+        self.out.write_all(&buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+
+    fn patch(&mut self, at: u64, bytes: &[u8]) -> io::Result<()> {
+        self.out.seek(SeekFrom::Start(at))?;
+        self.out.write_all(bytes)?;
+        self.out.seek(SeekFrom::Start(self.pos))?;
+        Ok(())
+    }
+}
+
+/// MJPEG AVI 读取器：按索引随机访问帧
+///
+/// 索引优先级：OpenDML 超级索引 > `idx1` > 逐块扫描 `movi`
+/// (最后一种用于写入中途崩溃、没有索引的文件)。
+#[derive(Debug)]
+pub(crate) struct AviReader<R: Read + Seek> {
+    input: R,
+    width: u32,
+    height: u32,
+    rate: u32,
+    scale: u32,
+    /// 各帧数据的绝对偏移与长度
+    frames: Vec<(u64, u32)>,
+}
+
+/// 从 `hdrl` 中解析出的视频流信息
+#[derive(Debug, Default)]
+struct StreamInfo {
+    /// 视频块标识 `##dc` 的前两位
+    number: Option<usize>,
+    width: u32,
+    height: u32,
+    rate: u32,
+    scale: u32,
+    codec: [u8; 4],
+    super_index: Vec<u64>,
+}
+
+impl<R: Read + Seek> AviReader<R> {
+    pub(crate) fn new(mut input: R) -> io::Result<Self> {
+        let file_len = input.seek(SeekFrom::End(0))?;
+        let mut stream = None;
+        let mut idx1 = None;
+        // 各 `movi` 标识的位置与列表结束位置
+        let mut movi_lists = vec![];
+
+        let mut pos = 0;
+        while pos + 12 <= file_len {
+            let (id, size) = read_chunk_header(&mut input, pos)?;
+            let form = read_fourcc(&mut input)?;
+            let expected: &[u8; 4] = if pos == 0 { b"AVI " } else { b"AVIX" };
+            if &id != b"RIFF" || &form != expected {
+                if pos == 0 {
+                    return Err(invalid("Not an AVI file"));
+                }
+                break;
+            }
+            // 未正常结束的文件大小字段为 0
+            let end = chunk_end(pos, size, file_len);
+
+            let mut child = pos + 12;
+            while child + 8 <= end {
+                let (id, size) = read_chunk_header(&mut input, child)?;
+                let child_end = chunk_end(child, size, end);
+                match &id {
+                    b"LIST" if child_end < child + 12 => {
+                        return Err(invalid("Truncated LIST chunk"));
+                    }
+                    b"LIST" => match &read_fourcc(&mut input)? {
+                        b"hdrl" if stream.is_none() => {
+                            let mut hdrl = vec![0; (child_end - child - 12) as usize];
+                            input.read_exact(&mut hdrl)?;
+                            stream = Some(parse_hdrl(&hdrl));
+                        }
+                        b"movi" => movi_lists.push((child + 8, child_end)),
+                        _ => {}
+                    },
+                    b"idx1" if idx1.is_none() => {
+                        let mut buf = vec![0; (child_end - child - 8) as usize];
+                        input.read_exact(&mut buf)?;
+                        idx1 = Some(buf);
+                    }
+                    _ => {}
+                }
+                child = child_end + (child_end & 1);
+            }
+            pos = end + (end & 1);
+        }
+
+        let stream = stream.ok_or_else(|| invalid("AVI file has no header"))?;
+        let number = stream
+            .number
+            .ok_or_else(|| invalid("AVI file has no video stream"))?;
+        if !stream.codec.eq_ignore_ascii_case(b"MJPG") {
+            return Err(invalid(&format!(
+                "Unsupported AVI codec {}, only MJPG is available",
+                String::from_utf8_lossy(&stream.codec)
+            )));
+        }
+        let ids = [
+            format!("{:02}dc", number).into_bytes(),
+            format!("{:02}db", number).into_bytes(),
+        ];
+
+        let mut frames = vec![];
+        if !stream.super_index.is_empty() {
+            for &ix in &stream.super_index {
+                read_std_index(&mut input, ix, file_len, &mut frames)?;
+            }
+        } else if let (Some(idx1), Some(&(movi, _))) = (&idx1, movi_lists.first()) {
+            read_idx1(&mut input, idx1, movi, &ids, &mut frames)?;
+        } else {
+            for &(movi, end) in &movi_lists {
+                scan_movi(&mut input, movi + 4, end, &ids, &mut frames)?;
+            }
+        }
+        // 丢掉空帧与超出文件末尾的帧 (文件被截断)
+        frames.retain(|&(offset, len)| len > 0 && offset + len as u64 <= file_len);
+
+        Ok(Self {
+            input,
+            width: stream.width,
+            height: stream.height,
+            rate: stream.rate,
+            scale: stream.scale,
+            frames,
+        })
+    }
+
+    pub(crate) fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub(crate) fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub(crate) fn fps(&self) -> f64 {
+        if self.scale == 0 {
+            0.0
+        } else {
+            self.rate as f64 / self.scale as f64
+        }
+    }
+
+    pub(crate) fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 读取第 `index` 帧的 JPEG 数据
+    pub(crate) fn read_frame(&mut self, index: usize, buf: &mut Vec<u8>) -> io::Result<()> {
+        let &(offset, len) = self
+            .frames
+            .get(index)
+            .ok_or_else(|| invalid("Frame index out of range"))?;
+        self.input.seek(SeekFrom::Start(offset))?;
+        buf.resize(len as usize, 0);
+        self.input.read_exact(buf)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn le32(buf: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+}
+
+fn read_fourcc<R: Read>(input: &mut R) -> io::Result<[u8; 4]> {
+    let mut id = [0; 4];
+    input.read_exact(&mut id)?;
+    Ok(id)
+}
+
+fn read_chunk_header<R: Read + Seek>(input: &mut R, at: u64) -> io::Result<([u8; 4], u32)> {
+    input.seek(SeekFrom::Start(at))?;
+    let mut head = [0; 8];
+    input.read_exact(&mut head)?;
+    Ok(([head[0], head[1], head[2], head[3]], le32(&head, 4)))
+}
+
+fn chunk_end(at: u64, size: u32, parent_end: u64) -> u64 {
+    if size == 0 {
+        parent_end
+    } else {
+        (at + 8 + size as u64).min(parent_end)
+    }
+}
+
+/// 遍历内存中的子块: (标识, 数据)
+fn sub_chunks(mut data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < 8 {
+            return None;
+        }
+        let id = [data[0], data[1], data[2], data[3]];
+        let len = (le32(data, 4) as usize).min(data.len() - 8);
+        let body = &data[8..8 + len];
+        data = &data[(8 + len + (len & 1)).min(data.len())..];
+        Some((id, body))
+    })
+}
+
+/// 解析 `hdrl` 列表 (不含 `hdrl` 标识)，取第一个视频流
+fn parse_hdrl(hdrl: &[u8]) -> StreamInfo {
+    let mut info = StreamInfo::default();
+    let mut stream_number = 0;
+    for (id, body) in sub_chunks(hdrl) {
+        match &id {
+            b"avih" if body.len() >= 40 => {
+                info.width = le32(body, 32);
+                info.height = le32(body, 36);
+            }
+            b"LIST" if body.starts_with(b"strl") && info.number.is_none() => {
+                let mut is_video = false;
+                for (id, body) in sub_chunks(&body[4..]) {
+                    match &id {
+                        b"strh" if body.len() >= 28 && &body[..4] == b"vids" => {
+                            is_video = true;
+                            info.scale = le32(body, 20);
+                            info.rate = le32(body, 24);
+                        }
+                        b"strf" if is_video && body.len() >= 20 => {
+                            info.width = le32(body, 4);
+                            info.height = (le32(body, 8) as i32).unsigned_abs();
+                            info.codec.copy_from_slice(&body[16..20]);
+                        }
+                        b"indx"
+                            if is_video && body.len() >= 24 && body[3] == AVI_INDEX_OF_INDEXES =>
+                        {
+                            let entries = le32(body, 4) as usize;
+                            info.super_index = (0..entries)
+                                .filter_map(|e| body.get(24 + 16 * e..32 + 16 * e))
+                                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                                .collect();
+                        }
+                        _ => {}
+                    }
+                }
+                if is_video {
+                    info.number = Some(stream_number);
+                }
+                stream_number += 1;
+            }
+            _ => {}
+        }
+    }
+    info
+}
+
+/// 读取一个 `ix##` 标准索引
+fn read_std_index<R: Read + Seek>(
+    input: &mut R,
+    at: u64,
+    file_len: u64,
+    frames: &mut Vec<(u64, u32)>,
+) -> io::Result<()> {
+    let (_, size) = read_chunk_header(input, at)?;
+    // 先检查大小再分配，损坏的块头可能声称有 4 GiB
+    if at + 8 + size as u64 > file_len {
+        return Err(invalid("Corrupt OpenDML index"));
+    }
+    let mut buf = vec![0; size as usize];
+    input.read_exact(&mut buf)?;
+    if buf.len() < 24 || buf[3] != AVI_INDEX_OF_CHUNKS {
+        return Err(invalid("Corrupt OpenDML index"));
+    }
+    let entries = le32(&buf, 4) as usize;
+    let base = u64::from_le_bytes(buf[12..20].try_into().unwrap());
+    for e in 0..entries {
+        let Some(entry) = buf.get(24 + 8 * e..32 + 8 * e) else {
+            break;
+        };
+        // 最高位是 "非关键帧" 标志
+        let offset = base
+            .checked_add(le32(entry, 0) as u64)
+            .ok_or_else(|| invalid("Corrupt OpenDML index"))?;
+        frames.push((offset, le32(entry, 4) & 0x7FFF_FFFF));
+    }
+    Ok(())
+}
+
+/// 读取 `idx1`；偏移通常相对 `movi` 标识，少数写入器使用绝对偏移
+fn read_idx1<R: Read + Seek>(
+    input: &mut R,
+    idx1: &[u8],
+    movi: u64,
+    ids: &[Vec<u8>],
+    frames: &mut Vec<(u64, u32)>,
+) -> io::Result<()> {
+    let entries: Vec<_> = idx1
+        .chunks_exact(16)
+        .filter(|e| ids.iter().any(|id| e[..4] == id[..]))
+        .map(|e| (le32(e, 8) as u64, le32(e, 12)))
+        .collect();
+    let Some(&(first, _)) = entries.first() else {
+        return Ok(());
+    };
+
+    let relative = matches!(
+        read_chunk_header(input, movi + first),
+        Ok((id, _)) if ids.iter().any(|want| id[..] == want[..])
+    );
+    let base = if relative { movi } else { 0 };
+    frames.extend(entries.iter().map(|&(off, len)| (base + off + 8, len)));
+    Ok(())
+}
+
+/// 没有索引时逐块扫描 `movi`
+fn scan_movi<R: Read + Seek>(
+    input: &mut R,
+    mut pos: u64,
+    end: u64,
+    ids: &[Vec<u8>],
+    frames: &mut Vec<(u64, u32)>,
+) -> io::Result<()> {
+    while pos + 8 <= end {
+        let (id, size) = read_chunk_header(input, pos)?;
+        if &id == b"LIST" {
+            // `LIST rec` 分组：进入内部
+            pos += 12;
+            continue;
+        }
+        if ids.iter().any(|want| id[..] == want[..]) {
+            frames.push((pos + 8, size));
+        }
+        pos += 8 + size as u64 + (size as u64 & 1);
+    }
+    Ok(())
+}
+
+/// 从 SOF 段读取 JPEG 的宽高
+pub(crate) fn jpeg_size(data: &[u8]) -> Option<(u16, u16)> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            // 填充字节
+            i += 1;
+            continue;
+        }
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        match marker {
+            // SOF0..SOF15 (排除 DHT / JPG / DAC)
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let sof = data.get(i + 5..i + 9)?;
+                let h = u16::from_be_bytes([sof[0], sof[1]]);
+                let w = u16::from_be_bytes([sof[2], sof[3]]);
+                return Some((w, h));
+            }
+            // 扫描开始或结束前仍未找到 SOF
+            0xDA | 0xD9 => return None,
+            _ => i += 2 + len,
+        }
+    }
+    None
+}
+
+fn padded(len: u32) -> u32 {
+    len + (len & 1)
+}
+
+/// 生成 `RIFF AVI` + `LIST hdrl` + `LIST movi` 头部，返回各回填位置 (相对头部起点)
+fn build_header(width: u32, height: u32, rate: u32, scale: u32) -> (Vec<u8>, HeaderLayout) {
+    let mut h = Vec::with_capacity(4096 + SUPER_INDEX_LEN as usize);
+    let put32 = |h: &mut Vec<u8>, v: u32| h.extend_from_slice(&v.to_le_bytes());
+    let put16 = |h: &mut Vec<u8>, v: u16| h.extend_from_slice(&v.to_le_bytes());
+
+    h.extend_from_slice(b"RIFF\0\0\0\0AVI ");
+    h.extend_from_slice(b"LIST\0\0\0\0hdrl");
+    let hdrl = h.len() - 12;
+
+    // MainAVIHeader
+    h.extend_from_slice(b"avih");
+    put32(&mut h, 56);
+    let avih = h.len() as u64;
+    let micros = (1_000_000u64 * scale as u64 / rate as u64) as u32;
+    put32(&mut h, micros);
+    put32(&mut h, 0); // dwMaxBytesPerSec
+    put32(&mut h, 0); // dwPaddingGranularity
+    put32(&mut h, AVIF_HASINDEX);
+    put32(&mut h, 0); // dwTotalFrames
+    put32(&mut h, 0); // dwInitialFrames
+    put32(&mut h, 1); // dwStreams
+    put32(&mut h, 0); // dwSuggestedBufferSize
+    put32(&mut h, width);
+    put32(&mut h, height);
+    h.extend_from_slice(&[0; 16]);
+
+    h.extend_from_slice(b"LIST\0\0\0\0strl");
+    let strl = h.len() - 12;
+
+    // AVISTREAMHEADER
+    h.extend_from_slice(b"strh");
+    put32(&mut h, 56);
+    let strh = h.len() as u64;
+    h.extend_from_slice(b"vidsMJPG");
+    put32(&mut h, 0); // dwFlags
+    put16(&mut h, 0); // wPriority
+    put16(&mut h, 0); // wLanguage
+    put32(&mut h, 0); // dwInitialFrames
+    put32(&mut h, scale);
+    put32(&mut h, rate);
+    put32(&mut h, 0); // dwStart
+    put32(&mut h, 0); // dwLength
+    put32(&mut h, 0); // dwSuggestedBufferSize
+    put32(&mut h, u32::MAX); // dwQuality: 默认
+    put32(&mut h, 0); // dwSampleSize
+    put16(&mut h, 0);
+    put16(&mut h, 0);
+    put16(&mut h, width as u16);
+    put16(&mut h, height as u16);
+
+    // BITMAPINFOHEADER
+    h.extend_from_slice(b"strf");
+    put32(&mut h, 40);
+    put32(&mut h, 40);
+    put32(&mut h, width);
+    put32(&mut h, height);
+    put16(&mut h, 1); // biPlanes
+    put16(&mut h, 24); // biBitCount
+    h.extend_from_slice(b"MJPG");
+    put32(&mut h, width * height * 3);
+    h.extend_from_slice(&[0; 16]);
+
+    // 超级索引占位
+    let indx = h.len() as u64;
+    h.extend_from_slice(b"JUNK");
+    put32(&mut h, SUPER_INDEX_LEN);
+    h.resize(h.len() + SUPER_INDEX_LEN as usize, 0);
+    let strl_len = (h.len() - strl - 8) as u32;
+    h[strl + 4..strl + 8].copy_from_slice(&strl_len.to_le_bytes());
+
+    // `LIST odml` 占位
+    let odml = h.len() as u64;
+    h.extend_from_slice(b"JUNK");
+    put32(&mut h, ODML_LIST_LEN - 8);
+    h.resize(h.len() + (ODML_LIST_LEN - 8) as usize, 0);
+    let hdrl_len = (h.len() - hdrl - 8) as u32;
+    h[hdrl + 4..hdrl + 8].copy_from_slice(&hdrl_len.to_le_bytes());
+
+    h.extend_from_slice(b"LIST\0\0\0\0movi");
+
+    (
+        h,
+        HeaderLayout {
+            avih,
+            strh,
+            indx,
+            odml,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn u32_at(buf: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap())
+    }
+
+    /// 列出 [start, end) 内的块: (标识, 数据起点, 数据长度)
+    fn chunks(buf: &[u8], start: usize, end: usize) -> Vec<([u8; 4], usize, usize)> {
+        let mut out = vec![];
+        let mut at = start;
+        while at + 8 <= end {
+            let id: [u8; 4] = buf[at..at + 4].try_into().unwrap();
+            let len = u32_at(buf, at + 4) as usize;
+            out.push((id, at + 8, len));
+            at += 8 + len + (len & 1);
+        }
+        assert_eq!(at, end, "chunks overrun their parent");
+        out
+    }
+
+    fn fake_frame(i: usize, len: usize) -> Vec<u8> {
+        (0..len).map(|k| (i * 31 + k) as u8).collect()
+    }
+
+    #[test]
+    fn frame_rate_is_rational() {
+        assert_eq!(frame_rate(30.0), (30, 1));
+        assert_eq!(frame_rate(29.97), (2997, 100));
+        assert_eq!(frame_rate(12.5), (25, 2));
+    }
+
+    #[test]
+    fn small_file_is_plain_avi_with_idx1() {
+        let mut avi = AviWriter::new(Cursor::new(vec![]), 64, 48, 25.0).unwrap();
+        let frames: Vec<_> = (0..3).map(|i| fake_frame(i, 101 + i)).collect();
+        for f in &frames {
+            avi.write_frame(f).unwrap();
+        }
+        let buf = avi.finish().unwrap().into_inner();
+
+        let top = chunks(&buf, 0, buf.len());
+        assert_eq!(top.len(), 1);
+        assert_eq!(&top[0].0, b"RIFF");
+        assert_eq!(&buf[8..12], b"AVI ");
+
+        let body = chunks(&buf, 12, buf.len());
+        let ids: Vec<_> = body.iter().map(|c| c.0).collect();
+        assert_eq!(ids, [*b"LIST", *b"LIST", *b"idx1"]);
+
+        // hdrl: avih 帧数与尺寸, 没有 OpenDML 结构
+        let hdrl = body[0];
+        assert_eq!(&buf[hdrl.1..hdrl.1 + 4], b"hdrl");
+        let avih = hdrl.1 + 12;
+        assert_eq!(u32_at(&buf, avih), 40_000);
+        assert_eq!(u32_at(&buf, avih + 16), 3);
+        assert_eq!((u32_at(&buf, avih + 32), u32_at(&buf, avih + 36)), (64, 48));
+        let hdrl_ids: Vec<_> = chunks(&buf, hdrl.1 + 4, hdrl.1 + hdrl.2)
+            .iter()
+            .map(|c| c.0)
+            .collect();
+        assert_eq!(hdrl_ids, [*b"avih", *b"LIST", *b"JUNK"]);
+
+        // idx1 偏移相对 movi 标识
+        let movi = body[1];
+        assert_eq!(&buf[movi.1..movi.1 + 4], b"movi");
+        let idx1 = body[2];
+        assert_eq!(idx1.2, 16 * 3);
+        for (i, f) in frames.iter().enumerate() {
+            let entry = idx1.1 + 16 * i;
+            assert_eq!(&buf[entry..entry + 4], b"00dc");
+            assert_eq!(u32_at(&buf, entry + 4), AVIIF_KEYFRAME);
+            let chunk = movi.1 + u32_at(&buf, entry + 8) as usize;
+            assert_eq!(&buf[chunk..chunk + 4], b"00dc");
+            assert_eq!(u32_at(&buf, entry + 12) as usize, f.len());
+            assert_eq!(&buf[chunk + 8..chunk + 8 + f.len()], &f[..]);
+        }
+    }
+
+    #[test]
+    fn large_file_switches_to_opendml() {
+        let avi = AviWriter::new(Cursor::new(vec![]), 32, 32, 30.0).unwrap();
+        let mut avi = avi.with_segment_limit(16 * 1024);
+        let frames: Vec<_> = (0..40).map(|i| fake_frame(i, 1001)).collect();
+        for f in &frames {
+            avi.write_frame(f).unwrap();
+        }
+        assert_eq!(avi.frames(), 40);
+        let buf = avi.finish().unwrap().into_inner();
+
+        let top = chunks(&buf, 0, buf.len());
+        assert!(top.len() >= 3, "{} segments", top.len());
+        assert_eq!(&buf[top[0].1..top[0].1 + 4], b"AVI ");
+        for seg in &top[1..] {
+            assert_eq!(&buf[seg.1..seg.1 + 4], b"AVIX");
+        }
+        for seg in &top {
+            assert!(seg.2 as u64 + 8 <= 16 * 1024);
+        }
+
+        // 超级索引: 每段一个 ix00, 帧数合计 40
+        let hdrl = top[0].1 + 4;
+        let strl = chunks(&buf, hdrl + 12, hdrl + 8 + u32_at(&buf, hdrl + 4) as usize);
+        assert_eq!(&strl[1].0, b"LIST");
+        let indx = chunks(&buf, strl[1].1 + 4, strl[1].1 + strl[1].2)[2];
+        assert_eq!(&indx.0, b"indx");
+        let entries = u32_at(&buf, indx.1 + 4) as usize;
+        assert_eq!(entries, top.len());
+
+        let mut seen = 0;
+        for e in 0..entries {
+            let entry = indx.1 + 24 + 16 * e;
+            let ix = u64::from_le_bytes(buf[entry..entry + 8].try_into().unwrap()) as usize;
+            let duration = u32_at(&buf, entry + 12) as usize;
+            assert_eq!(&buf[ix..ix + 4], b"ix00");
+            assert_eq!(u32_at(&buf, ix + 12) as usize, duration);
+            let base = u64::from_le_bytes(buf[ix + 20..ix + 28].try_into().unwrap()) as usize;
+            for k in 0..duration {
+                let off = base + u32_at(&buf, ix + 32 + 8 * k) as usize;
+                let len = u32_at(&buf, ix + 36 + 8 * k) as usize;
+                assert_eq!(&buf[off..off + len], &frames[seen][..]);
+                seen += 1;
+            }
+        }
+        assert_eq!(seen, 40);
+
+        // avih 只计第一段, dmlh 与 strh 计全部
+        let first_segment = u32_at(&buf, indx.1 + 24 + 12);
+        assert_eq!(u32_at(&buf, hdrl + 12 + 8 + 16), first_segment);
+        let odml = chunks(&buf, hdrl + 12, hdrl + 8 + u32_at(&buf, hdrl + 4) as usize);
+        let odml = odml.last().unwrap();
+        assert_eq!(&buf[odml.1..odml.1 + 8], b"odmldmlh");
+        assert_eq!(u32_at(&buf, odml.1 + 12), 40);
+    }
+
+    fn read_all<R: Read + Seek>(reader: &mut AviReader<R>) -> Vec<Vec<u8>> {
+        (0..reader.frame_count())
+            .map(|i| {
+                let mut buf = vec![];
+                reader.read_frame(i, &mut buf).unwrap();
+                buf
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reader_round_trips_both_index_kinds() {
+        let frames: Vec<_> = (0..40).map(|i| fake_frame(i, 1000 + i)).collect();
+        for limit in [SEGMENT_LIMIT, 16 * 1024] {
+            let avi = AviWriter::new(Cursor::new(vec![]), 320, 240, 29.97).unwrap();
+            let mut avi = avi.with_segment_limit(limit);
+            for f in &frames {
+                avi.write_frame(f).unwrap();
+            }
+            let file = avi.finish().unwrap();
+
+            let mut reader = AviReader::new(file).unwrap();
+            assert_eq!((reader.width(), reader.height()), (320, 240));
+            assert!((reader.fps() - 29.97).abs() < 1e-9);
+            assert_eq!(read_all(&mut reader), frames);
+        }
+    }
+
+    #[test]
+    fn reader_scans_unfinished_files() {
+        // 写入过程中崩溃：没有索引，头部大小字段全为 0
+        let mut avi = AviWriter::new(Cursor::new(vec![]), 64, 48, 30.0).unwrap();
+        let frames: Vec<_> = (0..3).map(|i| fake_frame(i, 77)).collect();
+        for f in &frames {
+            avi.write_frame(f).unwrap();
+        }
+        let mut file = avi.out;
+        // 最后一帧只写了一半
+        let len = file.get_ref().len();
+        file.get_mut().truncate(len - 20);
+
+        let mut reader = AviReader::new(file).unwrap();
+        assert_eq!(read_all(&mut reader), frames[..2]);
+    }
+
+    #[test]
+    fn reader_rejects_other_files() {
+        assert!(AviReader::new(Cursor::new(b"RIFF\0\0\0\0WAVEfmt ".to_vec())).is_err());
+        let mut wrong_codec = AviWriter::new(Cursor::new(vec![]), 8, 8, 30.0)
+            .unwrap()
+            .finish()
+            .unwrap()
+            .into_inner();
+        let strf = wrong_codec.windows(4).position(|w| w == b"strf").unwrap();
+        wrong_codec[strf + 24..strf + 28].copy_from_slice(b"H264");
+        assert!(AviReader::new(Cursor::new(wrong_codec)).is_err());
+
+        // LIST 块的大小放不下列表类型
+        let mut truncated = b"RIFF\0\0\0\0AVI LIST".to_vec();
+        truncated.extend_from_slice(&2u32.to_le_bytes());
+        truncated.extend_from_slice(b"hd");
+        let err = AviReader::new(Cursor::new(truncated)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // ix00 的大小超出文件、基准偏移溢出
+        let mut avi = AviWriter::new(Cursor::new(vec![]), 8, 8, 30.0)
+            .unwrap()
+            .with_segment_limit(16 * 1024);
+        for i in 0..20 {
+            avi.write_frame(&fake_frame(i, 1000)).unwrap();
+        }
+        let file = avi.finish().unwrap().into_inner();
+        let ix = file.windows(4).position(|w| w == b"ix00").unwrap();
+        let mut huge = file.clone();
+        huge[ix + 4..ix + 8].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        assert!(AviReader::new(Cursor::new(huge)).is_err());
+        let mut wrapped = file;
+        wrapped[ix + 20..ix + 28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(AviReader::new(Cursor::new(wrapped)).is_err());
+    }
+}
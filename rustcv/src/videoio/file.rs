@@ -0,0 +1,202 @@
+//! 文件输入：MJPEG AVI 与 printf 风格的图像序列 (`img_%04d.png`)
+//!
+//! 与相机不同，文件在调用线程上同步读取，不经过后台 Runtime。
+
+use super::avi::{jpeg_size, AviReader};
+use super::Response;
+use crate::imgcodecs::imread;
+use anyhow::{anyhow, Result};
+use rustcv_core::pixel_format::FourCC;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct FileSource {
+    kind: Kind,
+    /// 下一次读取的帧号
+    position: usize,
+}
+
+enum Kind {
+    Avi(AviReader<BufReader<File>>),
+    Sequence(ImageSequence),
+}
+
+impl FileSource {
+    /// 路径中含 `%d` / `%0Nd` 时按图像序列打开，否则按 AVI 打开
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let kind = match SequencePattern::parse(&path.to_string_lossy()) {
+            Some(pattern) => Kind::Sequence(ImageSequence::open(pattern)?),
+            None => {
+                let file = File::open(path)
+                    .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+                let reader = AviReader::new(BufReader::new(file))
+                    .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+                Kind::Avi(reader)
+            }
+        };
+        Ok(Self { kind, position: 0 })
+    }
+
+    pub(crate) fn size(&self) -> (u32, u32) {
+        match &self.kind {
+            Kind::Avi(avi) => (avi.width(), avi.height()),
+            Kind::Sequence(seq) => seq.size,
+        }
+    }
+
+    pub(crate) fn fps(&self) -> Option<f64> {
+        match &self.kind {
+            Kind::Avi(avi) => Some(avi.fps()),
+            Kind::Sequence(_) => None,
+        }
+    }
+
+    pub(crate) fn frame_count(&self) -> usize {
+        match &self.kind {
+            Kind::Avi(avi) => avi.frame_count(),
+            Kind::Sequence(seq) => seq.count,
+        }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 跳转到第 `frame` 帧 (允许等于帧数，即跳到末尾)
+    pub(crate) fn seek(&mut self, frame: usize) -> Result<()> {
+        if frame > self.frame_count() {
+            return Err(anyhow!(
+                "Cannot seek to frame {}, file has {} frames",
+                frame,
+                self.frame_count()
+            ));
+        }
+        self.position = frame;
+        Ok(())
+    }
+
+    /// 读取下一帧：AVI 返回 JPEG 原始数据，图像序列返回解码后的 BGR
+    pub(crate) fn next_frame(&mut self) -> Response {
+        if self.position >= self.frame_count() {
+            return Response::EndOfStream;
+        }
+
+        let result = match &mut self.kind {
+            Kind::Avi(avi) => {
+                let mut data = vec![];
+                avi.read_frame(self.position, &mut data)
+                    .map_err(|e| anyhow!("Failed to read frame {}: {}", self.position, e))
+                    .map(|_| {
+                        // 以 JPEG 自身的尺寸为准，防止头部与数据不一致
+                        let (w, h) = jpeg_size(&data)
+                            .map_or((avi.width(), avi.height()), |(w, h)| (w as u32, h as u32));
+                        Response::FrameData {
+                            width: w,
+                            height: h,
+                            data,
+                            fourcc: FourCC::MJPEG.0,
+                        }
+                    })
+            }
+            Kind::Sequence(seq) => imread(seq.path(self.position)).map(|mat| Response::FrameData {
+                width: mat.cols as u32,
+                height: mat.rows as u32,
+                data: mat.data.into_vec(),
+                fourcc: FourCC::BGR3.0,
+            }),
+        };
+
+        match result {
+            Ok(response) => {
+                self.position += 1;
+                response
+            }
+            Err(e) => Response::Error(e.to_string()),
+        }
+    }
+}
+
+/// `prefix%0Nd suffix` 形式的文件名模板
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SequencePattern {
+    prefix: String,
+    suffix: String,
+    width: usize,
+    zero_pad: bool,
+}
+
+impl SequencePattern {
+    /// 只识别一个 `%d` / `%Nd` / `%0Nd` 占位符
+    fn parse(path: &str) -> Option<Self> {
+        let start = path.rfind('%')?;
+        let spec = &path[start + 1..];
+        let zero_pad = spec.starts_with('0');
+        let digits = spec.chars().take_while(|c| c.is_ascii_digit()).count();
+        if spec[digits..].chars().next()? != 'd' {
+            return None;
+        }
+        Some(Self {
+            prefix: path[..start].to_string(),
+            suffix: spec[digits + 1..].to_string(),
+            width: spec[..digits].parse().unwrap_or(0),
+            zero_pad,
+        })
+    }
+
+    fn format(&self, index: usize) -> PathBuf {
+        let number = if self.zero_pad {
+            format!("{:0width$}", index, width = self.width)
+        } else {
+            format!("{:width$}", index, width = self.width)
+        };
+        PathBuf::from(format!("{}{}{}", self.prefix, number, self.suffix))
+    }
+}
+
+struct ImageSequence {
+    pattern: SequencePattern,
+    /// 第一帧的编号 (0 或 1，与 OpenCV 一致)
+    first: usize,
+    count: usize,
+    size: (u32, u32),
+}
+
+impl ImageSequence {
+    fn open(pattern: SequencePattern) -> Result<Self> {
+        let first = (0..=1)
+            .find(|&i| pattern.format(i).is_file())
+            .ok_or_else(|| anyhow!("No image matches {}", pattern.format(0).display()))?;
+        let count = (first..)
+            .take_while(|&i| pattern.format(i).is_file())
+            .count();
+        let size = image::image_dimensions(pattern.format(first))
+            .map_err(|e| anyhow!("Failed to open image: {}", e))?;
+
+        Ok(Self {
+            pattern,
+            first,
+            count,
+            size,
+        })
+    }
+
+    fn path(&self, position: usize) -> PathBuf {
+        self.pattern.format(self.first + position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_printf_patterns() {
+        let p = SequencePattern::parse("data/img_%04d.png").unwrap();
+        assert_eq!(p.format(7), PathBuf::from("data/img_0007.png"));
+        let p = SequencePattern::parse("frame%d.jpg").unwrap();
+        assert_eq!(p.format(123), PathBuf::from("frame123.jpg"));
+        assert!(SequencePattern::parse("video.avi").is_none());
+        assert!(SequencePattern::parse("100%.png").is_none());
+    }
+}
@@ -1,12 +1,20 @@
+mod avi;
 pub mod backend;
+mod file;
+mod writer;
 
-use crate::core::mat::Mat;
+pub use writer::VideoWriter;
+
+use crate::core::mat::{Mat, CV_8U};
+use crate::core::view::MatView;
+use crate::imgproc::{cvt_color, COLOR_BGRA2BGR, COLOR_YUV2BGR_YUYV};
 use crate::internal::runtime;
 use anyhow::{anyhow, Result};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use rustcv_core::builder::CameraConfig;
 use rustcv_core::pixel_format::{FourCC, PixelFormat};
 use rustcv_core::traits::{Driver, Stream};
+use std::path::Path;
 
 #[cfg(feature = "turbojpeg")]
 use turbojpeg::{Decompressor, Image, PixelFormat as TJPixelFormat};
@@ -28,16 +36,27 @@ enum Response {
     },
     PropertySet, // 【新增】属性设置成功确认
     Error(String),
-    #[allow(dead_code)]
     EndOfStream,
 }
 
+/// 帧来源
+enum Source {
+    /// 相机：由后台 Runtime 中的任务驱动
+    Camera {
+        cmd_tx: Sender<Command>,
+        res_rx: Receiver<Response>,
+    },
+    /// 视频文件或图像序列：在调用线程上同步读取
+    File(file::FileSource),
+}
+
 pub struct VideoCapture {
-    cmd_tx: Sender<Command>,
-    res_rx: Receiver<Response>,
+    source: Source,
     width: i32,
     height: i32,
     is_opened: bool,
+    /// 已读取的帧数 (相机)
+    frames_read: usize,
 }
 
 impl VideoCapture {
@@ -48,6 +67,14 @@ impl VideoCapture {
         // 2. 查找设备 ID
         let device_id = Self::resolve_device_id(&*driver, index)?;
 
+        Self::with_driver(driver, device_id)
+    }
+
+    /// 使用指定驱动打开设备 (例如 `rustcv_simulation::SimDriver`)
+    /// 便于在没有摄像头的环境中跑通整条管线
+    pub fn with_driver(driver: Box<dyn Driver>, device_id: impl Into<String>) -> Result<Self> {
+        let device_id = device_id.into();
+
         // 3. 创建通道
         let (cmd_tx, cmd_rx) = bounded::<Command>(1);
         let (res_tx, res_rx) = bounded::<Response>(1);
@@ -157,11 +184,27 @@ impl VideoCapture {
         });
 
         Ok(Self {
-            cmd_tx,
-            res_rx,
+            source: Source::Camera { cmd_tx, res_rx },
             width: 0,
             height: 0,
             is_opened: true,
+            frames_read: 0,
+        })
+    }
+
+    /// 打开 MJPEG AVI 文件或图像序列 (如 `"data/img_%04d.png"`)
+    ///
+    /// 之后与相机一样用 `read` 逐帧读取，读完时 `read` 返回 `false`。
+    /// 图像序列从编号 0 或 1 开始，到第一个缺失的编号为止。
+    pub fn open_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let source = file::FileSource::open(path.as_ref())?;
+        let (width, height) = source.size();
+        Ok(Self {
+            source: Source::File(source),
+            width: width as i32,
+            height: height as i32,
+            is_opened: true,
+            frames_read: 0,
         })
     }
 
@@ -169,16 +212,8 @@ impl VideoCapture {
         if !self.is_opened {
             return Ok(false);
         }
-        if self.cmd_tx.send(Command::NextFrame).is_err() {
-            return Err(anyhow!("Background worker is dead"));
-        }
 
-        let response = self
-            .res_rx
-            .recv()
-            .map_err(|_| anyhow!("Failed to receive response"))?;
-
-        match response {
+        match self.request_frame()? {
             Response::FrameData {
                 width,
                 height,
@@ -187,22 +222,29 @@ impl VideoCapture {
             } => {
                 self.width = width as i32;
                 self.height = height as i32;
+                self.frames_read += 1;
 
-                // 确保 Mat 大小匹配
+                // 确保 Mat 大小匹配；上一帧仍被其他 Mat 共享时换一块新缓冲，
+                // 既不影响其他持有者，也省掉写时复制的拷贝
                 let target_len = (width * height * 3) as usize;
-                if mat.data.len() != target_len {
-                    mat.data = vec![0; target_len];
+                if mat.data.len() != target_len || mat.data.is_shared() {
+                    mat.data = vec![0; target_len].into();
                 }
                 mat.rows = height as i32;
                 mat.cols = width as i32;
                 mat.channels = 3;
+                mat.depth = CV_8U;
                 mat.step = (width * 3) as usize;
 
                 let fcc = FourCC(fourcc);
                 if fcc == FourCC::YUYV {
-                    yuyv_to_bgr(&data, &mut mat.data, width as usize, height as usize);
+                    if let Some(src) = packed_view(&data, width, height, 2) {
+                        cvt_color(&src, mat, COLOR_YUV2BGR_YUYV)?;
+                    }
                 } else if fcc == FourCC::BGRA {
-                    bgra_to_bgr(&data, &mut mat.data, width as usize, height as usize);
+                    if let Some(src) = packed_view(&data, width, height, 4) {
+                        cvt_color(&src, mat, COLOR_BGRA2BGR)?;
+                    }
                 } else if fcc == FourCC::MJPEG {
                     // === TurboJPEG v1.4.0 极速解码 ===
                     #[cfg(feature = "turbojpeg")]
@@ -264,6 +306,48 @@ impl VideoCapture {
         }
     }
 
+    /// 读取一帧未解码的原始数据 (例如相机输出的 MJPEG)，返回其格式
+    ///
+    /// 配合 `VideoWriter::write_jpeg` 可以不经重新编码直接录像。
+    /// 流结束时返回 `None`。
+    pub fn read_raw(&mut self, buf: &mut Vec<u8>) -> Result<Option<FourCC>> {
+        if !self.is_opened {
+            return Ok(None);
+        }
+
+        match self.request_frame()? {
+            Response::FrameData {
+                width,
+                height,
+                data,
+                fourcc,
+            } => {
+                self.width = width as i32;
+                self.height = height as i32;
+                self.frames_read += 1;
+                *buf = data;
+                Ok(Some(FourCC(fourcc)))
+            }
+            Response::Error(msg) => Err(anyhow!("{}", msg)),
+            Response::EndOfStream => Ok(None),
+            _ => Err(anyhow!("Unexpected response in read_raw")),
+        }
+    }
+
+    fn request_frame(&mut self) -> Result<Response> {
+        let (cmd_tx, res_rx) = match &mut self.source {
+            Source::Camera { cmd_tx, res_rx } => (cmd_tx, res_rx),
+            Source::File(file) => return Ok(file.next_frame()),
+        };
+        if cmd_tx.send(Command::NextFrame).is_err() {
+            return Err(anyhow!("Background worker is dead"));
+        }
+
+        res_rx
+            .recv()
+            .map_err(|_| anyhow!("Failed to receive response"))
+    }
+
     /// 【新增】设置分辨率
     /// 这是一个同步阻塞调用，会等待后台完成硬件重启
     pub fn set_resolution(&mut self, width: u32, height: u32) -> Result<()> {
@@ -271,14 +355,17 @@ impl VideoCapture {
             return Err(anyhow!("Camera not opened"));
         }
 
+        let Source::Camera { cmd_tx, res_rx } = &self.source else {
+            return Err(anyhow!("Cannot change the resolution of a video file"));
+        };
+
         // 发送指令
-        self.cmd_tx
+        cmd_tx
             .send(Command::SetResolution(width, height))
             .map_err(|_| anyhow!("Background worker is dead"))?;
 
         // 等待确认
-        let response = self
-            .res_rx
+        let response = res_rx
             .recv()
             .map_err(|_| anyhow!("Failed to receive response"))?;
 
@@ -300,6 +387,38 @@ impl VideoCapture {
         self.height
     }
 
+    /// 文件的总帧数，相机返回 `None`
+    pub fn get_frame_count(&self) -> Option<usize> {
+        match &self.source {
+            Source::File(file) => Some(file.frame_count()),
+            Source::Camera { .. } => None,
+        }
+    }
+
+    /// 文件的标称帧率 (图像序列和相机返回 `None`)
+    pub fn get_fps(&self) -> Option<f64> {
+        match &self.source {
+            Source::File(file) => file.fps(),
+            Source::Camera { .. } => None,
+        }
+    }
+
+    /// 下一次 `read` 的帧号 (相机为已读取的帧数)
+    pub fn get_position(&self) -> usize {
+        match &self.source {
+            Source::File(file) => file.position(),
+            Source::Camera { .. } => self.frames_read,
+        }
+    }
+
+    /// 跳转到指定帧 (仅文件支持)
+    pub fn seek(&mut self, frame: usize) -> Result<()> {
+        match &mut self.source {
+            Source::File(file) => file.seek(frame),
+            Source::Camera { .. } => Err(anyhow!("Cannot seek a live camera")),
+        }
+    }
+
     // 内部辅助：解决设备 ID 的平台差异
     #[allow(unused_variables)]
     fn resolve_device_id(driver: &dyn Driver, index: u32) -> Result<String> {
@@ -336,64 +455,138 @@ impl VideoCapture {
 
 impl Drop for VideoCapture {
     fn drop(&mut self) {
-        let _ = self.cmd_tx.send(Command::Stop);
+        if let Source::Camera { cmd_tx, .. } = &self.source {
+            let _ = cmd_tx.send(Command::Stop);
+        }
     }
 }
 
-// 辅助：YUYV -> BGR (保留之前的实现)
-fn yuyv_to_bgr(src: &[u8], dest: &mut [u8], width: usize, height: usize) {
-    let frame_len = width * height * 2;
-    if src.len() < frame_len {
-        return;
-    }
-    for i in 0..(width * height / 2) {
-        let src_idx = i * 4;
-        let dst_idx = i * 6;
-        let y0 = src[src_idx] as i32;
-        let u = src[src_idx + 1] as i32 - 128;
-        let y1 = src[src_idx + 2] as i32;
-        let v = src[src_idx + 3] as i32 - 128;
-        let c0 = y0 - 16;
-        let r0 = (298 * c0 + 409 * v + 128) >> 8;
-        let g0 = (298 * c0 - 100 * u - 208 * v + 128) >> 8;
-        let b0 = (298 * c0 + 516 * u + 128) >> 8;
-        let c1 = y1 - 16;
-        let r1 = (298 * c1 + 409 * v + 128) >> 8;
-        let g1 = (298 * c1 - 100 * u - 208 * v + 128) >> 8;
-        let b1 = (298 * c1 + 516 * u + 128) >> 8;
-        dest[dst_idx] = clamp(b0);
-        dest[dst_idx + 1] = clamp(g0);
-        dest[dst_idx + 2] = clamp(r0);
-        dest[dst_idx + 3] = clamp(b1);
-        dest[dst_idx + 4] = clamp(g1);
-        dest[dst_idx + 5] = clamp(r1);
-    }
+/// 把相机输出的紧凑数据包装为 Mat 视图，数据不足一帧时返回 `None`
+fn packed_view(data: &[u8], width: u32, height: u32, channels: u8) -> Option<MatView<'_>> {
+    let step = width as usize * channels as usize;
+    let data = data.get(..step * height as usize)?;
+    Some(MatView {
+        data,
+        rows: height as i32,
+        cols: width as i32,
+        step,
+        channels,
+        depth: CV_8U,
+    })
 }
 
-#[inline(always)]
-fn clamp(val: i32) -> u8 {
-    if val < 0 {
-        0
-    } else if val > 255 {
-        255
-    } else {
-        val as u8
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustcv_core::frame::{Frame, FrameMetadata, Timestamp};
+    use rustcv_simulation::{SimDevice, SimDriver};
+    use std::time::Duration;
+
+    #[test]
+    fn read_decodes_injected_yuyv() {
+        let device = SimDevice::new("inj", "Injected");
+        let injector = device.injector();
+
+        // 2x1 白色 YUYV
+        let data = [235u8, 128, 235, 128];
+        injector.push(&Frame {
+            data: &data,
+            width: 2,
+            height: 1,
+            stride: 4,
+            format: PixelFormat::Known(FourCC::YUYV),
+            sequence: 0,
+            timestamp: Timestamp {
+                hw_raw_ns: 0,
+                system_synced: Duration::ZERO,
+            },
+            metadata: FrameMetadata::default(),
+            backend_handle: &(),
+        });
+
+        let driver = SimDriver::with_devices(vec![device]);
+        let mut cap = VideoCapture::with_driver(Box::new(driver), "inj").unwrap();
+        let mut mat = Mat::empty();
+
+        assert!(cap.read(&mut mat).unwrap());
+        assert_eq!((mat.rows, mat.cols, mat.channels), (1, 2, 3));
+        assert!(mat.data.iter().all(|&v| v > 240), "{:?}", mat.data);
+
+        // 队列耗尽后 read 返回错误
+        assert!(cap.read(&mut mat).is_err());
+    }
+
+    fn gray_mat(rows: i32, cols: i32, value: u8) -> Mat {
+        let mut mat = Mat::new(rows, cols, 3);
+        mat.data.fill(value);
+        mat
     }
-}
 
-// 辅助：BGRA -> BGR
-fn bgra_to_bgr(src: &[u8], dest: &mut [u8], width: usize, height: usize) {
-    let src_len = width * height * 4;
-    let dst_len = width * height * 3;
-    if src.len() < src_len || dest.len() < dst_len {
-        return;
+    #[test]
+    fn open_file_reads_and_seeks_avi() {
+        let path = std::env::temp_dir().join(format!("rustcv-capture-{}.avi", std::process::id()));
+        let mut writer = VideoWriter::new(
+            &path,
+            FourCC::MJPEG,
+            10.0,
+            crate::imgproc::Size::new(16, 16),
+        )
+        .unwrap();
+        for v in [20u8, 120, 220] {
+            writer.write(&gray_mat(16, 16, v)).unwrap();
+        }
+        writer.release().unwrap();
+
+        let mut cap = VideoCapture::open_file(&path).unwrap();
+        assert_eq!(cap.get_frame_count(), Some(3));
+        assert_eq!(cap.get_fps(), Some(10.0));
+        assert_eq!((cap.get_width(), cap.get_height()), (16, 16));
+
+        let mut mat = Mat::empty();
+        let mut shared = vec![];
+        for v in [20u8, 120, 220] {
+            assert!(cap.read(&mut mat).unwrap());
+            assert!(mat.data[0].abs_diff(v) < 4, "{} vs {}", mat.data[0], v);
+            shared.push(mat.clone());
+        }
+        assert!(!cap.read(&mut mat).unwrap());
+        // 分发出去的帧不会被之后的 read 覆盖
+        for (frame, v) in shared.iter().zip([20u8, 120, 220]) {
+            assert!(frame.data[0].abs_diff(v) < 4);
+        }
+
+        cap.seek(1).unwrap();
+        assert_eq!(cap.get_position(), 1);
+        assert!(cap.read(&mut mat).unwrap());
+        assert!(mat.data[0].abs_diff(120) < 4);
+        assert_eq!(cap.get_position(), 2);
+        assert!(cap.seek(4).is_err());
+        assert!(cap.set_resolution(8, 8).is_err());
+        std::fs::remove_file(&path).unwrap();
     }
-    for (s_chunk, d_chunk) in src[..src_len]
-        .chunks_exact(4)
-        .zip(dest[..dst_len].chunks_exact_mut(3))
-    {
-        d_chunk[0] = s_chunk[0]; // B
-        d_chunk[1] = s_chunk[1]; // G
-        d_chunk[2] = s_chunk[2]; // R
+
+    #[test]
+    fn open_file_reads_image_sequences() {
+        let dir = std::env::temp_dir().join(format!("rustcv-seq-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // 编号从 1 开始，第 4 张缺失时序列到此为止
+        for (i, v) in [(1, 10u8), (2, 20), (3, 30), (5, 50)] {
+            let path = dir.join(format!("img_{:04}.png", i));
+            crate::imgcodecs::imwrite(path, &gray_mat(4, 6, v)).unwrap();
+        }
+
+        let mut cap = VideoCapture::open_file(dir.join("img_%04d.png")).unwrap();
+        assert_eq!(cap.get_frame_count(), Some(3));
+        assert_eq!((cap.get_width(), cap.get_height()), (6, 4));
+
+        let mut mat = Mat::empty();
+        cap.seek(2).unwrap();
+        assert!(cap.read(&mut mat).unwrap());
+        assert_eq!((mat.rows, mat.cols, mat.channels), (4, 6, 3));
+        assert!(mat.data.iter().all(|&p| p == 30));
+        assert!(!cap.read(&mut mat).unwrap());
+
+        assert!(VideoCapture::open_file(dir.join("none_%d.png")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
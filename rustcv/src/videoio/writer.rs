@@ -0,0 +1,317 @@
+use crate::core::mat::{Mat, CV_8U};
+use crate::imgproc::Size;
+use anyhow::{anyhow, Result};
+use image::codecs::jpeg::JpegEncoder;
+use rustcv_core::frame::Frame;
+use rustcv_core::pixel_format::{FourCC, PixelFormat};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use super::avi::{jpeg_size, AviWriter};
+
+/// 默认 JPEG 质量 (与 OpenCV `VIDEOWRITER_PROP_QUALITY` 默认值一致)
+const DEFAULT_QUALITY: u8 = 95;
+
+/// 视频写入器 (对应 OpenCV 的 `cv::VideoWriter`)
+///
+/// 目前只支持 MJPEG AVI。两种写入方式：
+/// - `write`: 把 BGR/灰度 `Mat` 编码为 JPEG 后写入
+/// - `write_jpeg` / `write_frame`: 相机输出的 MJPEG 帧原样写入，不重新编码
+pub struct VideoWriter {
+    avi: Option<AviWriter<BufWriter<File>>>,
+    size: Size,
+    quality: u8,
+    // 复用的编码缓冲
+    rgb: Vec<u8>,
+    jpeg: Vec<u8>,
+}
+
+impl VideoWriter {
+    /// 由 4 个字符构造 FourCC，例如 `VideoWriter::fourcc('M', 'J', 'P', 'G')`
+    pub fn fourcc(c1: char, c2: char, c3: char, c4: char) -> FourCC {
+        FourCC::new(c1 as u8, c2 as u8, c3 as u8, c4 as u8)
+    }
+
+    /// 创建视频文件
+    ///
+    /// * `fourcc`: 目前只支持 `FourCC::MJPEG`
+    /// * `fps`: 帧率，支持非整数 (如 29.97)
+    /// * `size`: 帧尺寸，之后写入的每一帧都必须与之一致
+    pub fn new<P: AsRef<Path>>(path: P, fourcc: FourCC, fps: f64, size: Size) -> Result<Self> {
+        if fourcc != FourCC::MJPEG {
+            return Err(anyhow!(
+                "Unsupported codec {}, only MJPG is available",
+                fourcc
+            ));
+        }
+        if !(fps > 0.0 && fps.is_finite()) {
+            return Err(anyhow!("Invalid frame rate: {}", fps));
+        }
+        if size.width <= 0
+            || size.height <= 0
+            || size.width > u16::MAX as i32
+            || size.height > u16::MAX as i32
+        {
+            return Err(anyhow!(
+                "Invalid frame size: {}x{}",
+                size.width,
+                size.height
+            ));
+        }
+
+        let file = File::create(path).map_err(|e| anyhow!("Failed to create video: {}", e))?;
+        let avi = AviWriter::new(
+            BufWriter::new(file),
+            size.width as u32,
+            size.height as u32,
+            fps,
+        )?;
+
+        Ok(Self {
+            avi: Some(avi),
+            size,
+            quality: DEFAULT_QUALITY,
+            rgb: vec![],
+            jpeg: vec![],
+        })
+    }
+
+    pub fn is_opened(&self) -> bool {
+        self.avi.is_some()
+    }
+
+    /// 设置 JPEG 质量 (1-100)，对之后 `write` 的帧生效
+    pub fn set_quality(&mut self, quality: u8) {
+        self.quality = quality.clamp(1, 100);
+    }
+
+    pub fn get_quality(&self) -> u8 {
+        self.quality
+    }
+
+    /// 已写入的帧数
+    pub fn get_frame_count(&self) -> u32 {
+        self.avi.as_ref().map_or(0, |avi| avi.frames())
+    }
+
+    /// 编码并写入一帧 BGR (3 通道) 或灰度 (1 通道) 图像
+    pub fn write(&mut self, mat: &Mat) -> Result<()> {
+        if mat.rows != self.size.height || mat.cols != self.size.width {
+            return Err(anyhow!(
+                "Frame size {}x{} does not match writer size {}x{}",
+                mat.cols,
+                mat.rows,
+                self.size.width,
+                self.size.height
+            ));
+        }
+
+        if mat.depth != CV_8U {
+            return Err(anyhow!("Only 8-bit frames can be written to video"));
+        }
+
+        // BGR -> RGB (逐行，兼容带 Padding 的 Mat)
+        self.rgb.clear();
+        let color = match mat.channels {
+            3 => {
+                for r in 0..mat.rows {
+                    for px in mat.row_bytes(r).chunks_exact(3) {
+                        self.rgb.extend_from_slice(&[px[2], px[1], px[0]]);
+                    }
+                }
+                image::ColorType::Rgb8
+            }
+            1 => {
+                for r in 0..mat.rows {
+                    self.rgb.extend_from_slice(mat.row_bytes(r));
+                }
+                image::ColorType::L8
+            }
+            n => return Err(anyhow!("Unsupported channel count for video: {}", n)),
+        };
+
+        self.jpeg.clear();
+        JpegEncoder::new_with_quality(&mut self.jpeg, self.quality)
+            .encode(&self.rgb, mat.cols as u32, mat.rows as u32, color)
+            .map_err(|e| anyhow!("Failed to encode frame: {}", e))?;
+
+        let avi = self
+            .avi
+            .as_mut()
+            .ok_or_else(|| anyhow!("VideoWriter is released"))?;
+        avi.write_frame(&self.jpeg)?;
+        Ok(())
+    }
+
+    /// 直接写入一帧 JPEG 数据 (不重新编码)
+    ///
+    /// 只检查 JPEG 头部的尺寸，不做完整解码。
+    pub fn write_jpeg(&mut self, jpeg: &[u8]) -> Result<()> {
+        let (w, h) = jpeg_size(jpeg).ok_or_else(|| anyhow!("Not a valid JPEG frame"))?;
+        if (w as i32, h as i32) != (self.size.width, self.size.height) {
+            return Err(anyhow!(
+                "JPEG size {}x{} does not match writer size {}x{}",
+                w,
+                h,
+                self.size.width,
+                self.size.height
+            ));
+        }
+
+        let avi = self
+            .avi
+            .as_mut()
+            .ok_or_else(|| anyhow!("VideoWriter is released"))?;
+        avi.write_frame(jpeg)?;
+        Ok(())
+    }
+
+    /// 写入相机输出的 MJPEG 帧 (不重新编码)
+    pub fn write_frame(&mut self, frame: &Frame<'_>) -> Result<()> {
+        if frame.format != PixelFormat::Known(FourCC::MJPEG) {
+            return Err(anyhow!(
+                "Passthrough requires MJPEG frames, got {:?}; decode and use write() instead",
+                frame.format
+            ));
+        }
+        self.write_jpeg(frame.data)
+    }
+
+    /// 写入索引并关闭文件
+    ///
+    /// Drop 时会自动调用，但只有显式调用才能拿到错误。
+    pub fn release(&mut self) -> Result<()> {
+        if let Some(avi) = self.avi.take() {
+            let out = avi.finish()?;
+            out.into_inner()
+                .map_err(|e| anyhow!("Failed to flush video: {}", e.error()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VideoWriter {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::videoio::VideoCapture;
+    use rustcv_core::frame::{FrameMetadata, Timestamp};
+    use rustcv_simulation::{SimDevice, SimDriver};
+    use std::time::Duration;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rustcv-{}-{}.avi", name, std::process::id()))
+    }
+
+    fn encode_jpeg(w: u32, h: u32, rgb: [u8; 3]) -> Vec<u8> {
+        let pixels: Vec<u8> = (0..w * h).flat_map(|_| rgb).collect();
+        let mut out = vec![];
+        JpegEncoder::new_with_quality(&mut out, 90)
+            .encode(&pixels, w, h, image::ColorType::Rgb8)
+            .unwrap();
+        out
+    }
+
+    /// 按 idx1 取出所有帧数据
+    fn read_frames(file: &[u8]) -> Vec<&[u8]> {
+        let u32_at = |at: usize| u32::from_le_bytes(file[at..at + 4].try_into().unwrap()) as usize;
+        let movi = file.windows(4).position(|w| w == b"movi").unwrap();
+        let idx1 = file.windows(4).rposition(|w| w == b"idx1").unwrap();
+        (0..u32_at(idx1 + 4) / 16)
+            .map(|i| {
+                let entry = idx1 + 8 + 16 * i;
+                let chunk = movi + u32_at(entry + 8);
+                &file[chunk + 8..chunk + 8 + u32_at(entry + 12)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn writes_decodable_mjpeg_avi() {
+        let path = temp_path("writer-bgr");
+        let size = Size::new(32, 16);
+        let mut writer =
+            VideoWriter::new(&path, VideoWriter::fourcc('M', 'J', 'P', 'G'), 30.0, size).unwrap();
+
+        // 纯蓝 (BGR)
+        let mut mat = Mat::new(16, 32, 3);
+        for px in mat.data.chunks_exact_mut(3) {
+            px.copy_from_slice(&[200, 30, 10]);
+        }
+        for _ in 0..3 {
+            writer.write(&mat).unwrap();
+        }
+        assert_eq!(writer.get_frame_count(), 3);
+        assert!(writer.write(&Mat::new(8, 8, 3)).is_err());
+        writer.release().unwrap();
+        assert!(!writer.is_opened());
+
+        let file = std::fs::read(&path).unwrap();
+        let frames = read_frames(&file);
+        assert_eq!(frames.len(), 3);
+        let img = image::load_from_memory(frames[0]).unwrap().to_rgb8();
+        assert_eq!(img.dimensions(), (32, 16));
+        let [r, g, b] = img.get_pixel(16, 8).0;
+        assert!(r.abs_diff(10) < 8 && g.abs_diff(30) < 8 && b.abs_diff(200) < 8);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn passthrough_keeps_camera_bytes() {
+        let jpeg = encode_jpeg(16, 8, [0, 255, 0]);
+        let device = SimDevice::new("cam", "MJPEG Camera");
+        let injector = device.injector();
+        for sequence in 0..2 {
+            injector.push(&Frame {
+                data: &jpeg,
+                width: 16,
+                height: 8,
+                stride: 0,
+                format: PixelFormat::Known(FourCC::MJPEG),
+                sequence,
+                timestamp: Timestamp {
+                    hw_raw_ns: 0,
+                    system_synced: Duration::ZERO,
+                },
+                metadata: FrameMetadata::default(),
+                backend_handle: &(),
+            });
+        }
+
+        let path = temp_path("writer-passthrough");
+        let mut writer = VideoWriter::new(&path, FourCC::MJPEG, 15.0, Size::new(16, 8)).unwrap();
+        let driver = SimDriver::with_devices(vec![device]);
+        let mut cap = VideoCapture::with_driver(Box::new(driver), "cam").unwrap();
+        let mut raw = vec![];
+        for _ in 0..2 {
+            assert_eq!(cap.read_raw(&mut raw).unwrap(), Some(FourCC::MJPEG));
+            writer.write_jpeg(&raw).unwrap();
+        }
+        // 尺寸不符与非 JPEG 数据被拒绝
+        assert!(writer.write_jpeg(&encode_jpeg(8, 8, [0; 3])).is_err());
+        assert!(writer.write_jpeg(&[0u8; 16]).is_err());
+        drop(writer);
+
+        let file = std::fs::read(&path).unwrap();
+        let frames = read_frames(&file);
+        assert_eq!(frames, vec![&jpeg[..], &jpeg[..]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_unsupported_codecs() {
+        let path = temp_path("writer-h264");
+        assert!(VideoWriter::new(&path, FourCC::H264, 30.0, Size::new(8, 8)).is_err());
+        assert!(VideoWriter::new(&path, FourCC::MJPEG, 0.0, Size::new(8, 8)).is_err());
+        // JPEG 的宽高都是 16 位
+        assert!(VideoWriter::new(&path, FourCC::MJPEG, 30.0, Size::new(8, 65536)).is_err());
+        assert!(VideoWriter::new(&path, FourCC::MJPEG, 30.0, Size::new(65536, 8)).is_err());
+        assert!(!path.exists());
+    }
+}